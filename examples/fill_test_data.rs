@@ -42,6 +42,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             total_eth_saved_wei: (1_000_000_000_000u128) * (i as u128) * (factor as u128),
             total_blob_data_gas: 1_000 * factor * i, // 1000,2000,...
             total_pectra_data_gas: 2_000 * factor * i, // 2000,4000,...
+            total_eip_7918_extra_wei: Some(100_000_000_000u128 * (i as u128) * (factor as u128)),
+            total_data_bytes: Some(120_000 * factor * i),
+            total_zero_bytes: Some(20_000 * factor * i),
+            total_data_tokens: Some(420_000 * factor * i),
+            floor_applied_txs: Some(10 * factor * i),
+            wrong_mode_txs: Some(factor * i),
+            total_regret_wei: Some(10_000_000_000u128 * (i as u128) * (factor as u128)),
+            net_regret_wei: Some(-1_000_000_000_000i128 * (i as i128) * (factor as i128)),
+            timed_txs: Some(90 + factor * i),
+            optimal_timing_txs: Some(30 + factor * i),
+            total_timing_premium_wei: Some(50_000_000_000u128 * (i as u128) * (factor as u128)),
         };
 
         snapshot_rows.push(make_row(base_batcher, 1));
@@ -0,0 +1,63 @@
+use alloy_chains::NamedChain;
+use alloy_primitives::{FixedBytes, hex::FromHex};
+use alloy_provider::Provider;
+use pectralizer::{
+    provider::ProviderState,
+    simulation::{
+        self, SimulationFixture,
+        database::{RecordingDatabase, RpcDatabase},
+    },
+};
+use revm::database_interface::async_db::WrapDatabaseAsync;
+
+/// Record the state touched by a transaction into a JSON fixture, so that the simulation can be
+/// replayed offline in tests.
+///
+/// Usage: `cargo run --example record_simulation_fixture <tx_hash> <output_path>`
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv::dotenv().ok();
+    let ethereum_provider_url = std::env::var("ETHEREUM_PROVIDER")?;
+    let mut args = std::env::args().skip(1);
+    let tx_hash = args.next().ok_or("missing tx hash")?;
+    let output_path = args.next().ok_or("missing output path")?;
+
+    let provider_state =
+        ProviderState::new(&ethereum_provider_url, "", NamedChain::Mainnet.into()).await;
+    let provider = provider_state.ethereum_provider.clone();
+
+    println!("📥 Fetching transaction {}...", tx_hash);
+    let tx = provider
+        .get_transaction_by_hash(FixedBytes::from_hex(&tx_hash)?)
+        .await?
+        .ok_or("transaction not found")?;
+    let block = provider
+        .get_block_by_hash(tx.block_hash.ok_or("transaction is pending")?)
+        .await?
+        .ok_or("block not found")?;
+    let block_env = simulation::block_env(&block.header.inner);
+    let tx_env = simulation::tx_env(&tx, tx.inner.signer());
+
+    println!("🔁 Replaying transaction and recording state...");
+    let db = RpcDatabase::new(provider, block.header.number - 1);
+    let state = tokio::task::spawn_blocking({
+        let (block_env, tx_env) = (block_env.clone(), tx_env.clone());
+        let handle = tokio::runtime::Handle::current();
+        move || {
+            let db = RecordingDatabase::new(WrapDatabaseAsync::with_handle(db, handle));
+            let simulation = simulation::simulate(&db, &block_env, &tx_env);
+            simulation.map(|_| db.into_fixture())
+        }
+    })
+    .await??;
+
+    let fixture = SimulationFixture {
+        block: block_env,
+        tx: tx_env,
+        state,
+    };
+    std::fs::write(&output_path, serde_json::to_string_pretty(&fixture)?)?;
+    println!("✅ Fixture saved to {}", output_path);
+
+    Ok(())
+}
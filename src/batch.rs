@@ -0,0 +1,50 @@
+use crate::server::types::L2BatchSummary;
+use alloy_primitives::{Address, Bytes, Selector};
+use thiserror::Error;
+
+pub mod arbitrum;
+pub mod op_stack;
+
+use arbitrum::ArbitrumDecoder;
+use op_stack::OpStackDecoder;
+
+/// The errors that can occur while decoding an L2 batch.
+#[derive(Error, Debug)]
+pub enum BatchDecodeError {
+    #[error("Unsupported derivation version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("Unexpected end of data while reading {0}")]
+    UnexpectedEnd(&'static str),
+    #[error("Invalid blob encoding: {0}")]
+    InvalidBlobEncoding(String),
+    #[error("Unknown channel compression: {0:#04x}")]
+    UnknownCompression(u8),
+    #[error("Failed to decompress channel: {0}")]
+    Decompression(#[from] std::io::Error),
+    #[error("Invalid batch encoding: {0}")]
+    InvalidBatch(#[from] alloy_rlp::Error),
+    #[error("Unknown batch type: {0}")]
+    UnknownBatchType(u8),
+    #[error("Unknown function selector: {0}")]
+    UnknownSelector(Selector),
+    #[error("Invalid calldata: {0}")]
+    InvalidCalldata(&'static str),
+}
+
+/// A decoder of the batches a rollup posts to L1.
+pub trait BatchDecoder: Send + Sync {
+    /// It decodes the batch posted in the calldata or in the blobs of a transaction.
+    ///
+    /// The blobs are the raw 131072 bytes blobs.
+    fn decode(&self, calldata: &[u8], blobs: &[Bytes]) -> Result<L2BatchSummary, BatchDecodeError>;
+}
+
+/// It returns the decoder of the batches sent to the given address, if it is a known inbox.
+pub fn decoder_for(inbox: Address) -> Option<Box<dyn BatchDecoder>> {
+    OpStackDecoder::for_inbox(inbox)
+        .map(|decoder| Box::new(decoder) as Box<dyn BatchDecoder>)
+        .or_else(|| {
+            ArbitrumDecoder::for_inbox(inbox)
+                .map(|decoder| Box::new(decoder) as Box<dyn BatchDecoder>)
+        })
+}
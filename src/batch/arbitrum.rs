@@ -0,0 +1,277 @@
+use super::{BatchDecodeError, BatchDecoder};
+use crate::{
+    blob::decode_field_elements,
+    server::types::{ArbitrumBatch, L2BatchSummary},
+};
+use alloy_primitives::{Address, Bytes, Selector, U256, address, fixed_bytes};
+use alloy_rlp::Header;
+
+/// The selector of `addSequencerL2BatchFromOrigin(uint256,bytes,uint256,address,uint256,uint256)`.
+pub const ADD_SEQUENCER_L2_BATCH_FROM_ORIGIN_SELECTOR: Selector = fixed_bytes!("0x8f111f3c");
+/// The selector of `addSequencerL2BatchFromBlobs(uint256,uint256,address,uint256,uint256)`.
+pub const ADD_SEQUENCER_L2_BATCH_FROM_BLOBS_SELECTOR: Selector = fixed_bytes!("0x3e5aa082");
+/// The header byte of the brotli compressed batches.
+pub const BROTLI_MESSAGE_HEADER_BYTE: u8 = 0x00;
+/// The header flag of the batches kept by a data availability committee.
+pub const DAS_MESSAGE_HEADER_FLAG: u8 = 0x80;
+/// The header flag the sequencer inbox gives to the batches posted in blobs.
+pub const BLOB_HASHES_HEADER_FLAG: u8 = 0x50;
+
+/// An Arbitrum chain with its sequencer inbox.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArbitrumChain {
+    /// The name of the chain.
+    pub name: &'static str,
+    /// The sequencer inbox address.
+    pub sequencer_inbox: Address,
+}
+
+/// The Arbitrum chains whose batches can be decoded.
+pub const ARBITRUM_CHAINS: [ArbitrumChain; 2] = [
+    ArbitrumChain {
+        name: "arbitrum-one",
+        sequencer_inbox: address!("0x1c479675ad559DC151F6Ec7ed3FbF8ceE79582B6"),
+    },
+    ArbitrumChain {
+        name: "arbitrum-nova",
+        sequencer_inbox: address!("0x211E1c4c7f1bF5351Ac850Ed10FD68CFfCF6c21b"),
+    },
+];
+
+/// The decoder of the Arbitrum batches.
+#[derive(Debug, Clone, Copy)]
+pub struct ArbitrumDecoder {
+    /// The chain whose batches are decoded.
+    pub chain: ArbitrumChain,
+}
+
+impl ArbitrumDecoder {
+    /// It returns the decoder of the chain using the given sequencer inbox, if any.
+    pub fn for_inbox(inbox: Address) -> Option<Self> {
+        ARBITRUM_CHAINS
+            .into_iter()
+            .find(|chain| chain.sequencer_inbox == inbox)
+            .map(|chain| Self { chain })
+    }
+}
+
+impl BatchDecoder for ArbitrumDecoder {
+    fn decode(&self, calldata: &[u8], blobs: &[Bytes]) -> Result<L2BatchSummary, BatchDecodeError> {
+        if calldata.len() < 4 {
+            return Err(BatchDecodeError::UnexpectedEnd("function selector"));
+        }
+        let (selector, arguments) = calldata.split_at(4);
+        let selector = Selector::from_slice(selector);
+        let batch = if selector == ADD_SEQUENCER_L2_BATCH_FROM_ORIGIN_SELECTOR {
+            let data = abi_bytes(arguments, 1)?;
+            let Some(&header_flag) = data.first() else {
+                return Err(BatchDecodeError::UnexpectedEnd("batch data"));
+            };
+            ArbitrumBatch {
+                sequence_number: abi_u64(arguments, 0)?,
+                prev_message_count: abi_u64(arguments, 4)?,
+                new_message_count: abi_u64(arguments, 5)?,
+                header_flag,
+                brotli: header_flag == BROTLI_MESSAGE_HEADER_BYTE,
+                das: header_flag & DAS_MESSAGE_HEADER_FLAG != 0,
+                blob: false,
+            }
+        } else if selector == ADD_SEQUENCER_L2_BATCH_FROM_BLOBS_SELECTOR {
+            // the header of the batch content is the first byte of the blobs payload
+            let brotli = if blobs.is_empty() {
+                false
+            } else {
+                decode_blobs(blobs)?.first() == Some(&BROTLI_MESSAGE_HEADER_BYTE)
+            };
+            ArbitrumBatch {
+                sequence_number: abi_u64(arguments, 0)?,
+                prev_message_count: abi_u64(arguments, 3)?,
+                new_message_count: abi_u64(arguments, 4)?,
+                header_flag: BLOB_HASHES_HEADER_FLAG,
+                brotli,
+                das: false,
+                blob: true,
+            }
+        } else {
+            return Err(BatchDecodeError::UnknownSelector(selector));
+        };
+        Ok(L2BatchSummary {
+            rollup: self.chain.name.to_string(),
+            // each L2 message produces an L2 block
+            l2_block_count: Some(
+                batch
+                    .new_message_count
+                    .saturating_sub(batch.prev_message_count),
+            ),
+            l2_tx_count: None,
+            op_stack: None,
+            arbitrum: Some(batch),
+        })
+    }
+}
+
+/// It returns the abi encoded word at the given position of the arguments.
+fn abi_word(arguments: &[u8], position: usize) -> Result<U256, BatchDecodeError> {
+    arguments
+        .get(position * 32..(position + 1) * 32)
+        .map(U256::from_be_slice)
+        .ok_or(BatchDecodeError::UnexpectedEnd("abi arguments"))
+}
+
+/// It returns the abi encoded integer at the given position of the arguments.
+fn abi_u64(arguments: &[u8], position: usize) -> Result<u64, BatchDecodeError> {
+    u64::try_from(abi_word(arguments, position)?)
+        .map_err(|_| BatchDecodeError::InvalidCalldata("integer argument overflows u64"))
+}
+
+/// It returns the abi encoded dynamic bytes whose offset is at the given position.
+fn abi_bytes(arguments: &[u8], position: usize) -> Result<&[u8], BatchDecodeError> {
+    let offset = usize::try_from(abi_word(arguments, position)?)
+        .map_err(|_| BatchDecodeError::InvalidCalldata("bytes offset overflows usize"))?;
+    let length = usize::try_from(abi_word(&arguments[offset.min(arguments.len())..], 0)?)
+        .map_err(|_| BatchDecodeError::InvalidCalldata("bytes length overflows usize"))?;
+    let range = offset
+        .checked_add(32)
+        .and_then(|start| start.checked_add(length).map(|end| start..end))
+        .ok_or(BatchDecodeError::InvalidCalldata(
+            "bytes range overflows usize",
+        ))?;
+    arguments
+        .get(range)
+        .ok_or(BatchDecodeError::UnexpectedEnd("abi bytes"))
+}
+
+/// It decodes the batch posted in blobs by the Arbitrum batch poster.
+///
+/// The batch is rlp encoded and then packed in the 31 low bytes of each field element.
+pub fn decode_blobs(blobs: &[Bytes]) -> Result<Vec<u8>, BatchDecodeError> {
+    let payload: Vec<u8> = blobs
+        .iter()
+        .flat_map(|blob| decode_field_elements(blob))
+        .collect();
+    Ok(Header::decode_bytes(&mut payload.as_slice(), false)?.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob::{BYTES_PER_FIELD_ELEMENT, FIELD_ELEMENTS_PER_BLOB};
+    use alloy_primitives::keccak256;
+    use alloy_rlp::Encodable;
+
+    fn word(value: u64) -> [u8; 32] {
+        U256::from(value).to_be_bytes()
+    }
+
+    #[test]
+    fn test_selectors() {
+        assert_eq!(
+            ADD_SEQUENCER_L2_BATCH_FROM_ORIGIN_SELECTOR,
+            keccak256(
+                "addSequencerL2BatchFromOrigin(uint256,bytes,uint256,address,uint256,uint256)"
+            )[..4]
+        );
+        assert_eq!(
+            ADD_SEQUENCER_L2_BATCH_FROM_BLOBS_SELECTOR,
+            keccak256("addSequencerL2BatchFromBlobs(uint256,uint256,address,uint256,uint256)")[..4]
+        );
+    }
+
+    #[test]
+    fn test_decode_batch_from_origin() {
+        // a data availability certificate, as posted by Nova
+        let data = [0x88, 0x01, 0x02, 0x03];
+        let mut calldata = ADD_SEQUENCER_L2_BATCH_FROM_ORIGIN_SELECTOR.to_vec();
+        for argument in [
+            word(712_345),
+            word(6 * 32),
+            word(1_800_000),
+            [0u8; 32],
+            word(90_000_000),
+            word(90_000_250),
+            word(data.len() as u64),
+        ] {
+            calldata.extend_from_slice(&argument);
+        }
+        calldata.extend_from_slice(&data);
+        calldata.resize(calldata.len() + 32 - data.len(), 0);
+
+        let decoder = ArbitrumDecoder::for_inbox(ARBITRUM_CHAINS[1].sequencer_inbox).unwrap();
+        let summary = decoder.decode(&calldata, &[]).unwrap();
+        assert_eq!(summary.rollup, "arbitrum-nova");
+        assert_eq!(summary.l2_block_count, Some(250));
+        assert_eq!(summary.l2_tx_count, None);
+        assert_eq!(
+            summary.arbitrum,
+            Some(ArbitrumBatch {
+                sequence_number: 712_345,
+                prev_message_count: 90_000_000,
+                new_message_count: 90_000_250,
+                header_flag: 0x88,
+                brotli: false,
+                das: true,
+                blob: false,
+            })
+        );
+    }
+
+    #[test]
+    fn test_abi_bytes_bounds() {
+        // 3 bytes at the offset 32
+        let mut arguments = word(32).to_vec();
+        arguments.extend_from_slice(&word(3));
+        arguments.extend_from_slice(&[1, 2, 3]);
+        assert_eq!(abi_bytes(&arguments, 0).unwrap(), &[1, 2, 3]);
+
+        // past the end of the arguments
+        arguments[63] = 4;
+        assert!(matches!(
+            abi_bytes(&arguments, 0),
+            Err(BatchDecodeError::UnexpectedEnd(_))
+        ));
+
+        // a length overflowing the end of the range
+        arguments[32..64].copy_from_slice(&word(u64::MAX));
+        assert!(matches!(
+            abi_bytes(&arguments, 0),
+            Err(BatchDecodeError::InvalidCalldata(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_batch_from_blobs() {
+        let mut calldata = ADD_SEQUENCER_L2_BATCH_FROM_BLOBS_SELECTOR.to_vec();
+        for argument in [
+            word(954_321),
+            word(2_000_000),
+            [0u8; 32],
+            word(300_000_000),
+            word(300_001_000),
+        ] {
+            calldata.extend_from_slice(&argument);
+        }
+        let mut payload = Vec::new();
+        [BROTLI_MESSAGE_HEADER_BYTE, 0x1b, 0x2c]
+            .as_slice()
+            .encode(&mut payload);
+        let mut blob = vec![0u8; FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT];
+        blob[1..1 + payload.len()].copy_from_slice(&payload);
+
+        let decoder = ArbitrumDecoder::for_inbox(ARBITRUM_CHAINS[0].sequencer_inbox).unwrap();
+        let summary = decoder.decode(&calldata, &[Bytes::from(blob)]).unwrap();
+        assert_eq!(summary.rollup, "arbitrum-one");
+        assert_eq!(summary.l2_block_count, Some(1_000));
+        assert_eq!(
+            summary.arbitrum,
+            Some(ArbitrumBatch {
+                sequence_number: 954_321,
+                prev_message_count: 300_000_000,
+                new_message_count: 300_001_000,
+                header_flag: BLOB_HASHES_HEADER_FLAG,
+                brotli: true,
+                das: false,
+                blob: true,
+            })
+        );
+    }
+}
@@ -0,0 +1,605 @@
+use super::{BatchDecodeError, BatchDecoder};
+use crate::server::types::{L2BatchSummary, OpStackBatch};
+use alloy_primitives::{Address, Bytes, address};
+use alloy_rlp::Header;
+use std::{collections::HashMap, io::Read};
+
+/// The derivation version of the batcher transactions data.
+pub const DERIVATION_VERSION_0: u8 = 0;
+/// The length of a channel id.
+pub const CHANNEL_ID_LENGTH: usize = 16;
+/// The maximum size of a decompressed channel, since Fjord.
+pub const MAX_RLP_BYTES_PER_CHANNEL: u64 = 100_000_000;
+/// The maximum number of data bytes in a blob with the OP Stack encoding.
+///
+/// Each round of 4 field elements holds 127 bytes, and the first round starts with the
+/// encoding version and the 3 bytes data length.
+pub const MAX_BLOB_DATA_SIZE: usize = (4 * 31 + 3) * 1024 - 4;
+/// The version of the OP Stack blob encoding.
+const BLOB_ENCODING_VERSION: u8 = 0;
+/// The number of rounds of 4 field elements in a blob.
+const BLOB_ENCODING_ROUNDS: usize = 1024;
+/// The size of a raw blob.
+const BLOB_SIZE: usize = 4096 * 32;
+/// The version byte of the brotli compressed channels.
+const CHANNEL_VERSION_BROTLI: u8 = 1;
+/// The type of a batch holding a single L2 block.
+const SINGULAR_BATCH_TYPE: u8 = 0;
+/// The type of a batch holding a span of L2 blocks.
+const SPAN_BATCH_TYPE: u8 = 1;
+
+/// An OP Stack chain with the address its batches are sent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpStackChain {
+    /// The name of the chain.
+    pub name: &'static str,
+    /// The batch inbox address.
+    pub batch_inbox: Address,
+}
+
+/// The OP Stack chains whose batches can be decoded.
+pub const OP_STACK_CHAINS: [OpStackChain; 2] = [
+    OpStackChain {
+        name: "base",
+        batch_inbox: address!("0xff00000000000000000000000000000000008453"),
+    },
+    OpStackChain {
+        name: "optimism",
+        batch_inbox: address!("0xff00000000000000000000000000000000000010"),
+    },
+];
+
+/// A frame of a channel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The id of the channel the frame belongs to.
+    pub channel_id: [u8; CHANNEL_ID_LENGTH],
+    /// The position of the frame in the channel.
+    pub frame_number: u16,
+    /// The slice of the compressed channel carried by the frame.
+    pub frame_data: Bytes,
+    /// Whether this is the last frame of the channel.
+    pub is_last: bool,
+}
+
+/// The L2 blocks and transactions found in a channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChannelContent {
+    /// The number of L2 blocks.
+    pub l2_block_count: u64,
+    /// The number of L2 transactions.
+    pub l2_tx_count: u64,
+}
+
+impl ChannelContent {
+    /// It adds the blocks and the transactions of another batch or channel, failing on overflow.
+    fn add(&mut self, other: ChannelContent) -> Result<(), BatchDecodeError> {
+        self.l2_block_count = self
+            .l2_block_count
+            .checked_add(other.l2_block_count)
+            .ok_or(BatchDecodeError::InvalidBatch(alloy_rlp::Error::Overflow))?;
+        self.l2_tx_count = self
+            .l2_tx_count
+            .checked_add(other.l2_tx_count)
+            .ok_or(BatchDecodeError::InvalidBatch(alloy_rlp::Error::Overflow))?;
+        Ok(())
+    }
+}
+
+/// The decoder of the OP Stack batches.
+#[derive(Debug, Clone, Copy)]
+pub struct OpStackDecoder {
+    /// The chain whose batches are decoded.
+    pub chain: OpStackChain,
+}
+
+impl OpStackDecoder {
+    /// It returns the decoder of the chain using the given batch inbox, if any.
+    pub fn for_inbox(inbox: Address) -> Option<Self> {
+        OP_STACK_CHAINS
+            .into_iter()
+            .find(|chain| chain.batch_inbox == inbox)
+            .map(|chain| Self { chain })
+    }
+}
+
+impl BatchDecoder for OpStackDecoder {
+    fn decode(&self, calldata: &[u8], blobs: &[Bytes]) -> Result<L2BatchSummary, BatchDecodeError> {
+        // each blob carries its own versioned frames, like the calldata of a non-blob tx
+        let mut frames = Vec::new();
+        if blobs.is_empty() {
+            frames.extend(parse_frames(calldata)?);
+        } else {
+            for blob in blobs {
+                frames.extend(parse_frames(&decode_blob(blob)?)?);
+            }
+        }
+        let frame_count = frames.len() as u64;
+        let mut channel_count = 0;
+        let mut incomplete_channel_count = 0;
+        let mut content = ChannelContent::default();
+        for channel in assemble_channels(frames) {
+            match channel {
+                Some(data) => {
+                    let channel_content = decode_channel(&data)?;
+                    channel_count += 1;
+                    content.add(channel_content)?;
+                }
+                None => incomplete_channel_count += 1,
+            }
+        }
+        Ok(L2BatchSummary {
+            rollup: self.chain.name.to_string(),
+            l2_block_count: Some(content.l2_block_count),
+            l2_tx_count: Some(content.l2_tx_count),
+            op_stack: Some(OpStackBatch {
+                frame_count,
+                channel_count,
+                incomplete_channel_count,
+            }),
+            arbitrum: None,
+        })
+    }
+}
+
+/// It takes the next `len` bytes of the data.
+fn take<'a>(
+    data: &mut &'a [u8],
+    len: usize,
+    field: &'static str,
+) -> Result<&'a [u8], BatchDecodeError> {
+    if data.len() < len {
+        return Err(BatchDecodeError::UnexpectedEnd(field));
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+/// It reads an unsigned varint, as encoded by the Go `binary.PutUvarint`.
+fn read_uvarint(data: &mut &[u8], field: &'static str) -> Result<u64, BatchDecodeError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = take(data, 1, field)?[0];
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(BatchDecodeError::UnexpectedEnd(field))
+}
+
+/// It parses the frames of a batcher transaction data.
+pub fn parse_frames(data: &[u8]) -> Result<Vec<Frame>, BatchDecodeError> {
+    let mut data = data;
+    let version = take(&mut data, 1, "derivation version")?[0];
+    if version != DERIVATION_VERSION_0 {
+        return Err(BatchDecodeError::UnsupportedVersion(version));
+    }
+    let mut frames = Vec::new();
+    while !data.is_empty() {
+        let channel_id = take(&mut data, CHANNEL_ID_LENGTH, "channel id")?
+            .try_into()
+            .unwrap(); // safe unwrap as the slice has the channel id length
+        let frame_number =
+            u16::from_be_bytes(take(&mut data, 2, "frame number")?.try_into().unwrap());
+        let frame_data_length =
+            u32::from_be_bytes(take(&mut data, 4, "frame data length")?.try_into().unwrap());
+        let frame_data =
+            Bytes::copy_from_slice(take(&mut data, frame_data_length as usize, "frame data")?);
+        let is_last = take(&mut data, 1, "is last")?[0] == 1;
+        frames.push(Frame {
+            channel_id,
+            frame_number,
+            frame_data,
+            is_last,
+        });
+    }
+    Ok(frames)
+}
+
+/// It groups the frames by channel, in order of appearance.
+///
+/// It returns the data of each channel, or None if some of its frames are not in the given
+/// frames, e.g. because the channel spans multiple transactions.
+pub fn assemble_channels(frames: Vec<Frame>) -> Vec<Option<Vec<u8>>> {
+    let mut channel_ids = Vec::new();
+    let mut channels: HashMap<[u8; CHANNEL_ID_LENGTH], Vec<Frame>> = HashMap::new();
+    for frame in frames {
+        let channel = channels.entry(frame.channel_id).or_insert_with(|| {
+            channel_ids.push(frame.channel_id);
+            Vec::new()
+        });
+        // duplicated frames are ignored, like in the derivation pipeline
+        if channel
+            .iter()
+            .all(|other| other.frame_number != frame.frame_number)
+        {
+            channel.push(frame);
+        }
+    }
+    channel_ids
+        .into_iter()
+        .map(|channel_id| {
+            let mut frames = channels.remove(&channel_id).unwrap_or_default();
+            frames.sort_by_key(|frame| frame.frame_number);
+            let is_complete = frames
+                .iter()
+                .enumerate()
+                .all(|(position, frame)| frame.frame_number as usize == position)
+                && frames.last().is_some_and(|frame| frame.is_last);
+            is_complete.then(|| {
+                frames
+                    .iter()
+                    .flat_map(|frame| frame.frame_data.iter().copied())
+                    .collect()
+            })
+        })
+        .collect()
+}
+
+/// It decodes a blob encoded with the OP Stack blob encoding.
+///
+/// Each round of 4 field elements carries 31 bytes in each element, plus 3 bytes spread across
+/// the 6 low bits of the first byte of each element.
+pub fn decode_blob(blob: &[u8]) -> Result<Vec<u8>, BatchDecodeError> {
+    if blob.len() != BLOB_SIZE {
+        return Err(BatchDecodeError::InvalidBlobEncoding(format!(
+            "invalid blob size {}",
+            blob.len()
+        )));
+    }
+    if blob[1] != BLOB_ENCODING_VERSION {
+        return Err(BatchDecodeError::InvalidBlobEncoding(format!(
+            "unsupported encoding version {}",
+            blob[1]
+        )));
+    }
+    let output_length = u32::from_be_bytes([0, blob[2], blob[3], blob[4]]) as usize;
+    if output_length > MAX_BLOB_DATA_SIZE {
+        return Err(BatchDecodeError::InvalidBlobEncoding(format!(
+            "data length {} exceeds the maximum",
+            output_length
+        )));
+    }
+    let mut output = vec![0u8; MAX_BLOB_DATA_SIZE];
+    let mut encoded_bytes = [0u8; 4];
+    // the first field element holds the version and the length in place of 4 bytes of data
+    output[0..27].copy_from_slice(&blob[5..32]);
+    encoded_bytes[0] = blob[0];
+    let mut output_position = 28;
+    let mut input_position = 32;
+    for round in 0..BLOB_ENCODING_ROUNDS {
+        if round > 0 && output_position >= output_length {
+            break;
+        }
+        let first_element = if round == 0 { 1 } else { 0 };
+        for encoded_byte in encoded_bytes.iter_mut().skip(first_element) {
+            let field_element = &blob[input_position..input_position + 32];
+            // the two highest order bits of each field element must be empty
+            if field_element[0] & 0b1100_0000 != 0 {
+                return Err(BatchDecodeError::InvalidBlobEncoding(format!(
+                    "invalid field element at {}",
+                    input_position
+                )));
+            }
+            *encoded_byte = field_element[0];
+            output[output_position..output_position + 31].copy_from_slice(&field_element[1..]);
+            output_position += 32;
+            input_position += 32;
+        }
+        // the 4 chunks of 6 bits are reassembled in the gaps left after each element
+        output_position -= 1;
+        output[output_position - 96] =
+            (encoded_bytes[0] & 0b0011_1111) | ((encoded_bytes[1] & 0b0011_0000) << 2);
+        output[output_position - 64] =
+            (encoded_bytes[1] & 0b0000_1111) | ((encoded_bytes[3] & 0b0000_1111) << 4);
+        output[output_position - 32] =
+            (encoded_bytes[2] & 0b0011_1111) | ((encoded_bytes[3] & 0b0011_0000) << 2);
+    }
+    if output[output_length..].iter().any(|byte| *byte != 0)
+        || blob[input_position..].iter().any(|byte| *byte != 0)
+    {
+        return Err(BatchDecodeError::InvalidBlobEncoding(
+            "non-zero data after the declared length".to_string(),
+        ));
+    }
+    output.truncate(output_length);
+    Ok(output)
+}
+
+/// It decompresses the data of a channel, compressed either with zlib or with brotli.
+pub fn decompress_channel(data: &[u8]) -> Result<Vec<u8>, BatchDecodeError> {
+    let Some(&first_byte) = data.first() else {
+        return Err(BatchDecodeError::UnexpectedEnd("channel"));
+    };
+    let mut decompressed = Vec::new();
+    // zlib streams start with the compression method in the low nibble of the header
+    if first_byte & 0x0f == 0x08 || first_byte & 0x0f == 0x0f {
+        flate2::read::ZlibDecoder::new(data)
+            .take(MAX_RLP_BYTES_PER_CHANNEL)
+            .read_to_end(&mut decompressed)?;
+    } else if first_byte == CHANNEL_VERSION_BROTLI {
+        brotli::Decompressor::new(&data[1..], 4096)
+            .take(MAX_RLP_BYTES_PER_CHANNEL)
+            .read_to_end(&mut decompressed)?;
+    } else {
+        return Err(BatchDecodeError::UnknownCompression(first_byte));
+    }
+    Ok(decompressed)
+}
+
+/// It counts the L2 blocks and transactions in a channel.
+pub fn decode_channel(data: &[u8]) -> Result<ChannelContent, BatchDecodeError> {
+    let decompressed = decompress_channel(data)?;
+    let mut batches = decompressed.as_slice();
+    let mut content = ChannelContent::default();
+    // the channel is a stream of rlp strings, each one holding a typed batch
+    while !batches.is_empty() {
+        let mut batch = Header::decode_bytes(&mut batches, false)?;
+        let batch_type = take(&mut batch, 1, "batch type")?[0];
+        let batch_content = match batch_type {
+            SINGULAR_BATCH_TYPE => decode_singular_batch(batch)?,
+            SPAN_BATCH_TYPE => decode_span_batch(batch)?,
+            _ => return Err(BatchDecodeError::UnknownBatchType(batch_type)),
+        };
+        content.add(batch_content)?;
+    }
+    Ok(content)
+}
+
+/// It counts the transactions of a singular batch.
+///
+/// The batch is the rlp list `[parent_hash, epoch_number, epoch_hash, timestamp, transactions]`.
+fn decode_singular_batch(data: &[u8]) -> Result<ChannelContent, BatchDecodeError> {
+    let mut data = data;
+    let mut fields = Header::decode_bytes(&mut data, true)?;
+    // skip the parent hash, the epoch number, the epoch hash and the timestamp
+    for _ in 0..4 {
+        let header = Header::decode(&mut fields)?;
+        take(&mut fields, header.payload_length, "batch field")?;
+    }
+    let mut transactions = Header::decode_bytes(&mut fields, true)?;
+    let mut l2_tx_count = 0;
+    while !transactions.is_empty() {
+        let header = Header::decode(&mut transactions)?;
+        take(&mut transactions, header.payload_length, "transaction")?;
+        l2_tx_count += 1;
+    }
+    Ok(ChannelContent {
+        l2_block_count: 1,
+        l2_tx_count,
+    })
+}
+
+/// It counts the blocks and the transactions of a span batch.
+///
+/// The transactions are not decoded, as the number of transactions of each block precedes
+/// them.
+fn decode_span_batch(data: &[u8]) -> Result<ChannelContent, BatchDecodeError> {
+    let mut data = data;
+    // prefix: relative timestamp, L1 origin number, parent check and L1 origin check
+    read_uvarint(&mut data, "relative timestamp")?;
+    read_uvarint(&mut data, "L1 origin number")?;
+    take(&mut data, 20, "parent check")?;
+    take(&mut data, 20, "L1 origin check")?;
+    // payload: block count, origin bits and the transaction count of each block
+    let l2_block_count = read_uvarint(&mut data, "block count")?;
+    take(
+        &mut data,
+        l2_block_count.div_ceil(8) as usize,
+        "origin bits",
+    )?;
+    let mut l2_tx_count: u64 = 0;
+    for _ in 0..l2_block_count {
+        l2_tx_count = l2_tx_count
+            .checked_add(read_uvarint(&mut data, "block transaction count")?)
+            .ok_or(BatchDecodeError::InvalidBatch(alloy_rlp::Error::Overflow))?;
+    }
+    Ok(ChannelContent {
+        l2_block_count,
+        l2_tx_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rlp::Encodable;
+    use std::io::Write;
+
+    /// It encodes the data with the OP Stack blob encoding.
+    fn encode_blob(data: &[u8]) -> Vec<u8> {
+        let mut blob = vec![0u8; BLOB_SIZE];
+        let mut read_position = 0;
+        let mut write_position = 0;
+        let read_byte = |read_position: &mut usize| {
+            let byte = data.get(*read_position).copied().unwrap_or_default();
+            *read_position += 1;
+            byte
+        };
+        let mut write_element = |blob: &mut Vec<u8>, first_byte: u8, bytes: &[u8; 31]| {
+            blob[write_position] = first_byte;
+            blob[write_position + 1..write_position + 32].copy_from_slice(bytes);
+            write_position += 32;
+        };
+        let mut round = 0;
+        while round < BLOB_ENCODING_ROUNDS && read_position < data.len() {
+            let mut chunks = [[0u8; 31]; 4];
+            if round == 0 {
+                chunks[0][0] = BLOB_ENCODING_VERSION;
+                chunks[0][1..4].copy_from_slice(&(data.len() as u32).to_be_bytes()[1..]);
+                for byte in chunks[0][4..].iter_mut() {
+                    *byte = read_byte(&mut read_position);
+                }
+            } else {
+                for byte in chunks[0].iter_mut() {
+                    *byte = read_byte(&mut read_position);
+                }
+            }
+            let x = read_byte(&mut read_position);
+            write_element(&mut blob, x & 0b0011_1111, &chunks[0]);
+            for byte in chunks[1].iter_mut() {
+                *byte = read_byte(&mut read_position);
+            }
+            let y = read_byte(&mut read_position);
+            write_element(
+                &mut blob,
+                (y & 0b0000_1111) | ((x & 0b1100_0000) >> 2),
+                &chunks[1],
+            );
+            for byte in chunks[2].iter_mut() {
+                *byte = read_byte(&mut read_position);
+            }
+            let z = read_byte(&mut read_position);
+            write_element(&mut blob, z & 0b0011_1111, &chunks[2]);
+            for byte in chunks[3].iter_mut() {
+                *byte = read_byte(&mut read_position);
+            }
+            write_element(
+                &mut blob,
+                ((z & 0b1100_0000) >> 2) | ((y & 0b1111_0000) >> 4),
+                &chunks[3],
+            );
+            round += 1;
+        }
+        blob
+    }
+
+    /// It encodes the frames of a channel, prefixed by the derivation version.
+    fn encode_frames(channel_id: u8, channel: &[u8], frame_count: usize) -> Vec<u8> {
+        let mut data = vec![DERIVATION_VERSION_0];
+        let chunk_size = channel.len().div_ceil(frame_count);
+        for (frame_number, chunk) in channel.chunks(chunk_size).enumerate() {
+            data.extend_from_slice(&[channel_id; CHANNEL_ID_LENGTH]);
+            data.extend_from_slice(&(frame_number as u16).to_be_bytes());
+            data.extend_from_slice(&(chunk.len() as u32).to_be_bytes());
+            data.extend_from_slice(chunk);
+            data.push((frame_number == frame_count - 1) as u8);
+        }
+        data
+    }
+
+    fn singular_batch(tx_count: usize) -> Vec<u8> {
+        let mut fields = Vec::new();
+        [1u8; 32].encode(&mut fields);
+        100u64.encode(&mut fields);
+        [2u8; 32].encode(&mut fields);
+        1_700_000_000u64.encode(&mut fields);
+        vec![Bytes::from_static(&[0x02, 0xaa, 0xbb]); tx_count].encode(&mut fields);
+        let mut batch = vec![SINGULAR_BATCH_TYPE];
+        Header {
+            list: true,
+            payload_length: fields.len(),
+        }
+        .encode(&mut batch);
+        batch.extend_from_slice(&fields);
+        batch
+    }
+
+    fn span_batch(block_tx_counts: &[u8]) -> Vec<u8> {
+        let mut batch = vec![SPAN_BATCH_TYPE];
+        // relative timestamp and L1 origin number
+        batch.extend_from_slice(&[0xac, 0x02, 0x64]);
+        batch.extend_from_slice(&[0u8; 40]);
+        batch.push(block_tx_counts.len() as u8);
+        batch.push(0b0000_0001);
+        batch.extend_from_slice(block_tx_counts);
+        // the transactions are never read
+        batch.extend_from_slice(&[0xde, 0xad]);
+        batch
+    }
+
+    #[test]
+    fn test_decode_zlib_singular_batches_from_calldata() {
+        let mut channel = Vec::new();
+        singular_batch(2).as_slice().encode(&mut channel);
+        singular_batch(3).as_slice().encode(&mut channel);
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&channel).unwrap();
+        let calldata = encode_frames(7, &encoder.finish().unwrap(), 2);
+
+        let decoder = OpStackDecoder::for_inbox(OP_STACK_CHAINS[0].batch_inbox).unwrap();
+        let summary = decoder.decode(&calldata, &[]).unwrap();
+        assert_eq!(summary.rollup, "base");
+        assert_eq!(
+            summary.op_stack,
+            Some(OpStackBatch {
+                frame_count: 2,
+                channel_count: 1,
+                incomplete_channel_count: 0,
+            })
+        );
+        assert_eq!(summary.l2_block_count, Some(2));
+        assert_eq!(summary.l2_tx_count, Some(5));
+    }
+
+    #[test]
+    fn test_decode_brotli_span_batch_from_blob() {
+        let mut channel = Vec::new();
+        span_batch(&[1, 0, 4]).as_slice().encode(&mut channel);
+        let mut compressed = vec![CHANNEL_VERSION_BROTLI];
+        {
+            let mut encoder = brotli::CompressorWriter::new(&mut compressed, 4096, 9, 22);
+            encoder.write_all(&channel).unwrap();
+        }
+        let mut data = encode_frames(1, &compressed, 1);
+        // a frame of a channel that continues in another transaction
+        data.extend_from_slice(&encode_frames(2, &[0x78, 0x9c, 0x01], 2)[1..]);
+        data.truncate(data.len() - 24);
+        let blob = encode_blob(&data);
+        assert_eq!(decode_blob(&blob).unwrap(), data);
+
+        let decoder = OpStackDecoder::for_inbox(OP_STACK_CHAINS[1].batch_inbox).unwrap();
+        let summary = decoder.decode(&[], &[Bytes::from(blob)]).unwrap();
+        assert_eq!(summary.rollup, "optimism");
+        assert_eq!(
+            summary.op_stack,
+            Some(OpStackBatch {
+                frame_count: 2,
+                channel_count: 1,
+                incomplete_channel_count: 1,
+            })
+        );
+        assert_eq!(summary.l2_block_count, Some(3));
+        assert_eq!(summary.l2_tx_count, Some(5));
+    }
+
+    #[test]
+    fn test_transaction_count_overflow() {
+        // the uvarint of u64::MAX
+        let max_count = [[0xff; 9].as_slice(), &[0x01]].concat();
+        let mut batch = vec![0xac, 0x02, 0x64];
+        batch.extend_from_slice(&[0u8; 40]);
+        batch.extend_from_slice(&[2, 0b0000_0000]);
+        batch.extend_from_slice(&max_count);
+        batch.extend_from_slice(&max_count);
+        assert!(matches!(
+            decode_span_batch(&batch),
+            Err(BatchDecodeError::InvalidBatch(_))
+        ));
+
+        // two batches of a channel adding up past u64::MAX
+        let mut span_batch = vec![SPAN_BATCH_TYPE, 0xac, 0x02, 0x64];
+        span_batch.extend_from_slice(&[0u8; 40]);
+        span_batch.extend_from_slice(&[1, 0b0000_0001]);
+        span_batch.extend_from_slice(&max_count);
+        let mut channel = Vec::new();
+        span_batch.as_slice().encode(&mut channel);
+        span_batch.as_slice().encode(&mut channel);
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&channel).unwrap();
+        assert!(matches!(
+            decode_channel(&encoder.finish().unwrap()),
+            Err(BatchDecodeError::InvalidBatch(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_full_blob() {
+        let data: Vec<u8> = (0..MAX_BLOB_DATA_SIZE).map(|i| (i % 251) as u8).collect();
+        assert_eq!(decode_blob(&encode_blob(&data)).unwrap(), data);
+    }
+}
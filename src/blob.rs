@@ -0,0 +1,230 @@
+use crate::{
+    batch::op_stack::{MAX_BLOB_DATA_SIZE, OpStackDecoder, decode_blob},
+    server::types::{BlobUsage, BlobUtilization},
+    utils::BYTES_PER_BLOB,
+};
+use alloy_primitives::{Address, B256};
+use revm::primitives::eip4844::GAS_PER_BLOB;
+use std::str::FromStr;
+
+pub mod fee;
+pub mod sharing;
+
+/// The number of field elements in a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+/// The number of bytes in a field element.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+/// The number of payload bytes that fit in a field element.
+///
+/// The first byte of each field element is left empty so that the element stays below the
+/// BLS modulus.
+pub const USABLE_BYTES_PER_FIELD_ELEMENT: usize = 31;
+/// The number of payload bytes that fit in a blob.
+pub const USABLE_BYTES_PER_BLOB: u64 =
+    (FIELD_ELEMENTS_PER_BLOB * USABLE_BYTES_PER_FIELD_ELEMENT) as u64;
+
+/// The encoding used to pack a payload into blobs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlobEncoding {
+    /// The payload fills the 31 low bytes of each field element.
+    #[default]
+    Raw,
+    /// The OP Stack encoding, which also packs 6 bits in the first byte of each field element.
+    OpStack,
+    /// A custom encoding with the given number of payload bytes per blob.
+    Custom(u64),
+}
+
+impl BlobEncoding {
+    /// It returns the encoding used by the batcher sending to the given address.
+    pub fn for_inbox(inbox: Option<Address>) -> Self {
+        match inbox.and_then(OpStackDecoder::for_inbox) {
+            Some(_) => Self::OpStack,
+            None => Self::Raw,
+        }
+    }
+
+    /// It returns the number of payload bytes that fit in a blob.
+    pub fn usable_bytes_per_blob(&self) -> u64 {
+        match self {
+            Self::Raw => USABLE_BYTES_PER_BLOB,
+            Self::OpStack => MAX_BLOB_DATA_SIZE as u64,
+            Self::Custom(usable_bytes) => *usable_bytes,
+        }
+    }
+
+    /// It returns the length of the payload in a blob of this encoding.
+    ///
+    /// OP Stack blobs carry the length of their data, blobs that fail to decode fall back to the
+    /// raw payload length.
+    pub fn payload_length(&self, blob: &[u8]) -> u64 {
+        match self {
+            Self::OpStack => decode_blob(blob)
+                .map(|data| data.len() as u64)
+                .unwrap_or_else(|_| payload_length(blob)),
+            Self::Raw | Self::Custom(_) => payload_length(blob),
+        }
+    }
+
+    /// It returns the number of blobs needed to post a payload of the given length.
+    pub fn blobs_needed(&self, payload_length: u64) -> u64 {
+        payload_length.div_ceil(self.usable_bytes_per_blob())
+    }
+}
+
+impl FromStr for BlobEncoding {
+    type Err = String;
+
+    /// It parses `raw`, `op_stack` or `custom:<usable bytes per blob>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "raw" => Ok(Self::Raw),
+            "op_stack" => Ok(Self::OpStack),
+            _ => {
+                let usable_bytes = s
+                    .strip_prefix("custom:")
+                    .and_then(|usable_bytes| usable_bytes.parse::<u64>().ok())
+                    .ok_or_else(|| format!("unknown blob encoding: {}", s))?;
+                if usable_bytes == 0 || usable_bytes > BYTES_PER_BLOB {
+                    return Err(format!(
+                        "usable bytes per blob must be between 1 and {}",
+                        BYTES_PER_BLOB
+                    ));
+                }
+                Ok(Self::Custom(usable_bytes))
+            }
+        }
+    }
+}
+
+/// It returns the payload bytes of a blob, dropping the first byte of each field element.
+pub fn decode_field_elements(blob: &[u8]) -> Vec<u8> {
+    blob.chunks(BYTES_PER_FIELD_ELEMENT)
+        .flat_map(|field_element| field_element.iter().skip(1))
+        .copied()
+        .collect()
+}
+
+/// It returns the payload of a blob, without the trailing zero padding.
+pub fn blob_payload(blob: &[u8]) -> Vec<u8> {
+    let mut payload = decode_field_elements(blob);
+    let length = payload
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |last_non_zero| last_non_zero + 1);
+    payload.truncate(length);
+    payload
+}
+
+/// It returns the length of the payload in a blob, ignoring the trailing zero padding.
+pub fn payload_length(blob: &[u8]) -> u64 {
+    blob_payload(blob).len() as u64
+}
+
+/// It returns how much of the blob is actually used and the wei paid for the empty space, against
+/// the capacity of the blob encoding.
+pub fn blob_usage(
+    versioned_hash: B256,
+    blob: &[u8],
+    blob_gas_price: u128,
+    encoding: BlobEncoding,
+) -> BlobUsage {
+    let used_bytes = encoding.payload_length(blob);
+    let capacity_bytes = encoding.usable_bytes_per_blob();
+    let blob_wei_spent = GAS_PER_BLOB as u128 * blob_gas_price;
+    let empty_space_wei_spent =
+        blob_wei_spent * capacity_bytes.saturating_sub(used_bytes) as u128 / capacity_bytes as u128;
+    BlobUsage {
+        versioned_hash,
+        used_bytes,
+        capacity_bytes,
+        utilization_pct: utilization_pct(used_bytes, capacity_bytes),
+        empty_space_wei_spent,
+    }
+}
+
+/// It aggregates the usage of the blobs of a transaction.
+pub fn blob_utilization(blobs: Vec<BlobUsage>) -> BlobUtilization {
+    let used_bytes = blobs.iter().map(|blob| blob.used_bytes).sum();
+    let capacity_bytes = blobs.iter().map(|blob| blob.capacity_bytes).sum();
+    let empty_space_wei_spent = blobs.iter().map(|blob| blob.empty_space_wei_spent).sum();
+    BlobUtilization {
+        used_bytes,
+        capacity_bytes,
+        utilization_pct: utilization_pct(used_bytes, capacity_bytes),
+        empty_space_wei_spent,
+        blobs,
+    }
+}
+
+/// It returns the percentage of the capacity that is used.
+pub fn utilization_pct(used_bytes: u64, capacity_bytes: u64) -> f64 {
+    if capacity_bytes == 0 {
+        return 0.0;
+    }
+    used_bytes as f64 / capacity_bytes as f64 * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_usage() {
+        let mut blob = vec![0u8; FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT];
+        // fill the first field element and the first payload byte of the second one
+        blob[1..32].fill(0xaa);
+        blob[33] = 0xbb;
+        assert_eq!(payload_length(&blob), 32);
+
+        let usage = blob_usage(B256::ZERO, &blob, 10, BlobEncoding::Raw);
+        assert_eq!(usage.used_bytes, 32);
+        assert_eq!(usage.capacity_bytes, 126_976);
+        assert_eq!(
+            usage.empty_space_wei_spent,
+            GAS_PER_BLOB as u128 * 10 * (126_976 - 32) / 126_976
+        );
+
+        let utilization = blob_utilization(vec![usage.clone(), usage]);
+        assert_eq!(utilization.used_bytes, 64);
+        assert_eq!(utilization.capacity_bytes, 2 * 126_976);
+        assert_eq!(
+            utilization.utilization_pct,
+            64.0 / (2.0 * 126_976.0) * 100.0
+        );
+    }
+
+    #[test]
+    fn test_op_stack_blob_usage() {
+        // an OP Stack blob of version 0 carrying 1000 bytes
+        let mut blob = vec![0u8; FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT];
+        blob[4] = 0xe8;
+        blob[3] = 0x03;
+        blob[5..32].fill(0xaa);
+
+        let usage = blob_usage(B256::ZERO, &blob, 10, BlobEncoding::OpStack);
+        assert_eq!(usage.used_bytes, 1000);
+        assert_eq!(usage.capacity_bytes, 130_044);
+        assert_eq!(
+            usage.empty_space_wei_spent,
+            GAS_PER_BLOB as u128 * 10 * (130_044 - 1000) / 130_044
+        );
+    }
+
+    #[test]
+    fn test_blob_encoding() {
+        // a full raw blob payload fits in a single OP Stack blob
+        let payload_length = USABLE_BYTES_PER_BLOB + 1;
+        assert_eq!(BlobEncoding::Raw.blobs_needed(payload_length), 2);
+        assert_eq!(BlobEncoding::OpStack.blobs_needed(payload_length), 1);
+        assert_eq!(BlobEncoding::OpStack.usable_bytes_per_blob(), 130_044);
+        assert_eq!(BlobEncoding::Raw.blobs_needed(0), 0);
+
+        assert_eq!("raw".parse(), Ok(BlobEncoding::Raw));
+        assert_eq!("op_stack".parse(), Ok(BlobEncoding::OpStack));
+        assert_eq!("custom:100000".parse(), Ok(BlobEncoding::Custom(100_000)));
+        assert!("custom:0".parse::<BlobEncoding>().is_err());
+        assert!("custom:131073".parse::<BlobEncoding>().is_err());
+        assert!("zstd".parse::<BlobEncoding>().is_err());
+    }
+}
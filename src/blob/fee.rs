@@ -0,0 +1,199 @@
+use crate::{
+    blob::USABLE_BYTES_PER_BLOB,
+    fork::{BlobScheduleParams, ForkParams},
+    server::types::BlobFeeProjection,
+};
+use alloy_primitives::U256;
+use revm::primitives::eip4844::GAS_PER_BLOB;
+
+/// The minimum blob base fee, in wei.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-4844
+pub const MIN_BASE_FEE_PER_BLOB_GAS: u128 = 1;
+
+/// The execution gas a blob costs at least under EIP-7918, priced at the execution base fee.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-7918
+pub const BLOB_BASE_COST: u64 = 1 << 13;
+
+/// The payload of the typical batch of the break-even blob base fee: a blob filled with
+/// compressed data, where almost every byte is non-zero.
+pub const TYPICAL_BATCH_BYTES: u64 = USABLE_BYTES_PER_BLOB;
+
+/// The largest excess blob gas accepted in a projection, in base fee update fractions: the blob
+/// base fee is about `e ** 80`, far above any fee that could be paid, and it still fits a u128.
+pub const MAX_EXCESS_BLOB_GAS_FRACTIONS: u64 = 80;
+
+/// The number of calldata tokens of a non-zero byte.
+const NON_ZERO_BYTE_TOKENS: u64 = 4;
+
+/// It approximates `factor * e ** (numerator / denominator)` using a Taylor expansion.
+///
+/// The result saturates at `u128::MAX`, a fee that can't be paid anyway, and the expansion stops
+/// as soon as it gets there.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-4844#helpers
+pub fn fake_exponential(factor: u128, numerator: u64, denominator: u64) -> u128 {
+    let numerator = U256::from(numerator);
+    let denominator = U256::from(denominator);
+    let max_output = U256::from(u128::MAX) * denominator;
+    let mut i = U256::from(1);
+    let mut output = U256::ZERO;
+    let mut numerator_accum = U256::from(factor) * denominator;
+    while !numerator_accum.is_zero() {
+        output += numerator_accum;
+        if output > max_output {
+            return u128::MAX;
+        }
+        let Some(next_accum) = numerator_accum.checked_mul(numerator) else {
+            return u128::MAX;
+        };
+        numerator_accum = next_accum / (denominator * i);
+        i += U256::from(1);
+    }
+    u128::try_from(output / denominator).unwrap_or(u128::MAX)
+}
+
+/// It returns the blob base fee for the given excess blob gas.
+pub fn blob_base_fee(excess_blob_gas: u64, blob_params: &BlobScheduleParams) -> u128 {
+    fake_exponential(
+        MIN_BASE_FEE_PER_BLOB_GAS,
+        excess_blob_gas,
+        blob_params.base_fee_update_fraction,
+    )
+}
+
+/// It returns the blob base fee under the EIP-7918 reserve price, so that a blob costs at least
+/// [`BLOB_BASE_COST`] execution gas.
+///
+/// It's a static counterfactual: the effect of the reserve price on the excess blob gas of the
+/// following blocks is ignored.
+pub fn reserve_blob_base_fee(blob_base_fee: u128, base_fee_per_gas: u64) -> u128 {
+    let reserve_price = BLOB_BASE_COST as u128 * base_fee_per_gas as u128 / GAS_PER_BLOB as u128;
+    blob_base_fee.max(reserve_price)
+}
+
+/// It returns the blob base fee above which the typical batch costs less as EIP-7623 calldata
+/// than as a blob, at the given execution base fee.
+///
+/// The typical batch fills one blob with [`TYPICAL_BATCH_BYTES`] non-zero bytes. The priority fee
+/// is left out, as a batch pays one in both modes.
+pub fn break_even_blob_base_fee(base_fee_per_gas: u64, fork_params: &ForkParams) -> u128 {
+    let calldata_gas =
+        TYPICAL_BATCH_BYTES * NON_ZERO_BYTE_TOKENS * fork_params.total_cost_floor_per_token;
+    calldata_gas as u128 * base_fee_per_gas as u128 / GAS_PER_BLOB as u128
+}
+
+/// It returns the excess blob gas of the block following a block with the given excess blob gas
+/// and blob gas used.
+pub fn next_excess_blob_gas(
+    excess_blob_gas: u64,
+    blob_gas_used: u64,
+    blob_params: &BlobScheduleParams,
+) -> u64 {
+    let target_blob_gas = blob_params.target_blobs_per_block * GAS_PER_BLOB;
+    excess_blob_gas
+        .saturating_add(blob_gas_used)
+        .saturating_sub(target_blob_gas)
+}
+
+/// It projects the blob base fee over the next blocks, given the number of blobs in each block.
+///
+/// The first projection is the block with the given excess blob gas, and each blob count moves
+/// the excess blob gas to the next block.
+pub fn project_blob_base_fee(
+    excess_blob_gas: u64,
+    blob_counts: &[u64],
+    blob_params: &BlobScheduleParams,
+) -> Vec<BlobFeeProjection> {
+    let mut excess_blob_gas = excess_blob_gas;
+    let mut projections = Vec::with_capacity(blob_counts.len());
+    for (block_offset, blob_count) in blob_counts.iter().enumerate() {
+        projections.push(BlobFeeProjection {
+            block_offset: block_offset as u64,
+            excess_blob_gas,
+            blob_count: *blob_count,
+            blob_base_fee: blob_base_fee(excess_blob_gas, blob_params),
+        });
+        excess_blob_gas = next_excess_blob_gas(
+            excess_blob_gas,
+            blob_count.saturating_mul(GAS_PER_BLOB),
+            blob_params,
+        );
+    }
+    projections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::context_interface::block::BlobExcessGasAndPrice;
+
+    #[test]
+    fn test_blob_base_fee_matches_revm() {
+        for excess_blob_gas in [0, 1_000_000, 10_000_000, 50_000_000, 120_000_000] {
+            assert_eq!(
+                blob_base_fee(excess_blob_gas, &BlobScheduleParams::cancun()),
+                BlobExcessGasAndPrice::new(excess_blob_gas, false).blob_gasprice
+            );
+            assert_eq!(
+                blob_base_fee(excess_blob_gas, &BlobScheduleParams::prague()),
+                BlobExcessGasAndPrice::new(excess_blob_gas, true).blob_gasprice
+            );
+        }
+    }
+
+    #[test]
+    fn test_blob_base_fee_saturates() {
+        let blob_params = BlobScheduleParams::prague();
+        assert_eq!(blob_base_fee(u64::MAX, &blob_params), u128::MAX);
+        assert_eq!(fake_exponential(u128::MAX, u64::MAX, 1), u128::MAX);
+        assert!(blob_base_fee(MAX_EXCESS_BLOB_GAS_FRACTIONS * 5_007_716, &blob_params) < u128::MAX);
+        assert_eq!(
+            next_excess_blob_gas(u64::MAX, u64::MAX, &blob_params),
+            u64::MAX - 6 * GAS_PER_BLOB
+        );
+        // the excess blob gas stops growing instead of overflowing
+        let projections = project_blob_base_fee(u64::MAX - 1, &[u64::MAX, 0], &blob_params);
+        assert_eq!(projections[1].blob_base_fee, u128::MAX);
+    }
+
+    #[test]
+    fn test_reserve_blob_base_fee() {
+        // a blob costs at least 8192 gas at 2 gwei, 1/16 of the base fee per blob gas
+        assert_eq!(reserve_blob_base_fee(1, 2_000_000_000), 125_000_000);
+        assert_eq!(
+            reserve_blob_base_fee(300_000_000, 2_000_000_000),
+            300_000_000
+        );
+        assert_eq!(reserve_blob_base_fee(1, 0), 1);
+    }
+
+    #[test]
+    fn test_break_even_blob_base_fee() {
+        let fork_params = crate::fork::Fork::Prague.default_params();
+        // 126976 bytes of 4 tokens at 10 gas cost 38.75 times the blob gas
+        assert_eq!(
+            break_even_blob_base_fee(1_000_000_000, &fork_params),
+            38_750_000_000
+        );
+        assert_eq!(break_even_blob_base_fee(0, &fork_params), 0);
+    }
+
+    #[test]
+    fn test_project_blob_base_fee() {
+        let blob_params = BlobScheduleParams::prague();
+        // full blocks raise the excess by 3 blobs, empty blocks lower it by 6 blobs
+        let projections = project_blob_base_fee(300 * GAS_PER_BLOB, &[9, 9, 6, 0, 0], &blob_params);
+        let excess: Vec<u64> = projections.iter().map(|p| p.excess_blob_gas).collect();
+        assert_eq!(
+            excess,
+            [300, 303, 306, 306, 300].map(|blobs| blobs * GAS_PER_BLOB)
+        );
+        assert!(projections[2].blob_base_fee > projections[0].blob_base_fee);
+        assert_eq!(
+            projections[4].blob_base_fee,
+            blob_base_fee(300 * GAS_PER_BLOB, &blob_params)
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use crate::{
+    blob::USABLE_BYTES_PER_BLOB,
+    server::types::{BatcherBlobSharing, BlobSharingSimulation},
+};
+use revm::primitives::eip4844::GAS_PER_BLOB;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The default number of blocks whose batches are regrouped together.
+pub const DEFAULT_SHARING_WINDOW_BLOCKS: u64 = 1;
+
+/// A blob batch of a tracked batcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobBatch {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// The block number of the batch.
+    pub block_number: u64,
+    /// Payload bytes in the blobs, without the trailing zero padding.
+    pub used_bytes: u64,
+    /// The number of blobs posted.
+    pub blob_count: u64,
+    /// The blob base fee of the block.
+    pub blob_gas_price: u128,
+}
+
+impl BlobBatch {
+    /// Wei paid for the blobs of the batch.
+    pub fn blob_wei_spent(&self) -> u128 {
+        self.blob_count as u128 * GAS_PER_BLOB as u128 * self.blob_gas_price
+    }
+}
+
+/// It simulates a blob sharing service regrouping the payloads of the batches posted in the same
+/// window of blocks into shared blobs.
+///
+/// The payloads of a window are packed back to back, a payload spanning several blobs if needed,
+/// and the shared blobs are posted with the last batch of the window at its blob base fee. The
+/// cost of the shared blobs is split between the batches pro rata of their payload bytes.
+///
+/// Only the windows with the batches of more than one batcher are shared. The blobs of the other
+/// windows are kept as posted, and what packing them would have saved is reported as
+/// self-aggregation.
+pub fn simulate_blob_sharing(batches: &[BlobBatch], window_blocks: u64) -> BlobSharingSimulation {
+    // the batches of each window, in block order
+    let mut windows: BTreeMap<u64, Vec<&BlobBatch>> = BTreeMap::new();
+    for batch in batches {
+        windows
+            .entry(batch.block_number / window_blocks)
+            .or_default()
+            .push(batch);
+    }
+
+    let mut simulation = BlobSharingSimulation {
+        window_blocks,
+        window_count: windows.len() as u64,
+        shared_window_count: 0,
+        blob_count: 0,
+        shared_blob_count: 0,
+        total_blob_wei_spent: 0,
+        total_shared_blob_wei_spent: 0,
+        total_savings_wei: 0,
+        total_self_aggregation_savings_wei: 0,
+        batchers: Vec::new(),
+    };
+    let mut batcher_sharing: HashMap<&str, BatcherBlobSharing> = HashMap::new();
+
+    for window in windows.values_mut() {
+        window.sort_by_key(|batch| batch.block_number);
+        let used_bytes: u64 = window.iter().map(|batch| batch.used_bytes).sum();
+        let packed_blob_count = used_bytes.div_ceil(USABLE_BYTES_PER_BLOB);
+        // the packed blobs wait for the last batch of the window
+        let blob_gas_price = window.last().map_or(0, |batch| batch.blob_gas_price);
+        let packed_blob_wei_spent =
+            packed_blob_count as u128 * GAS_PER_BLOB as u128 * blob_gas_price;
+        let is_shared = window
+            .iter()
+            .map(|batch| batch.batcher_address.as_str())
+            .collect::<HashSet<_>>()
+            .len()
+            > 1;
+
+        simulation.shared_window_count += is_shared as u64;
+        if is_shared {
+            simulation.shared_blob_count += packed_blob_count;
+            simulation.total_shared_blob_wei_spent += packed_blob_wei_spent;
+        }
+
+        for batch in window.iter() {
+            let blob_wei_spent = batch.blob_wei_spent();
+            let packed_share_wei = if used_bytes > 0 {
+                packed_blob_wei_spent * batch.used_bytes as u128 / used_bytes as u128
+            } else {
+                0
+            };
+            // a batcher alone in its window keeps its blobs
+            let (share_wei, self_aggregation_savings_wei) = if is_shared {
+                (packed_share_wei, 0)
+            } else {
+                simulation.shared_blob_count += batch.blob_count;
+                simulation.total_shared_blob_wei_spent += blob_wei_spent;
+                (
+                    blob_wei_spent,
+                    blob_wei_spent as i128 - packed_share_wei as i128,
+                )
+            };
+            simulation.blob_count += batch.blob_count;
+            simulation.total_blob_wei_spent += blob_wei_spent;
+            simulation.total_self_aggregation_savings_wei += self_aggregation_savings_wei;
+
+            let entry = batcher_sharing
+                .entry(batch.batcher_address.as_str())
+                .or_insert_with(|| BatcherBlobSharing {
+                    batcher_address: batch.batcher_address.clone(),
+                    batch_count: 0,
+                    shared_batch_count: 0,
+                    used_bytes: 0,
+                    blob_count: 0,
+                    blob_wei_spent: 0,
+                    shared_blob_wei_spent: 0,
+                    savings_wei: 0,
+                    self_aggregation_savings_wei: 0,
+                });
+            entry.batch_count += 1;
+            entry.shared_batch_count += is_shared as u64;
+            entry.used_bytes += batch.used_bytes;
+            entry.blob_count += batch.blob_count;
+            entry.blob_wei_spent += blob_wei_spent;
+            entry.shared_blob_wei_spent += share_wei;
+            entry.savings_wei += blob_wei_spent as i128 - share_wei as i128;
+            entry.self_aggregation_savings_wei += self_aggregation_savings_wei;
+        }
+    }
+
+    simulation.total_savings_wei =
+        simulation.total_blob_wei_spent as i128 - simulation.total_shared_blob_wei_spent as i128;
+    simulation.batchers = batcher_sharing.into_values().collect();
+    simulation
+        .batchers
+        .sort_by(|a, b| a.batcher_address.cmp(&b.batcher_address));
+    simulation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulate_blob_sharing() {
+        let quarter_blob = USABLE_BYTES_PER_BLOB / 4;
+        let blob_wei = GAS_PER_BLOB as u128;
+        let batch = |batcher_address: &str, block_number, used_bytes, blob_gas_price| BlobBatch {
+            batcher_address: batcher_address.to_string(),
+            block_number,
+            used_bytes,
+            blob_count: used_bytes.div_ceil(USABLE_BYTES_PER_BLOB),
+            blob_gas_price,
+        };
+        let batches = [
+            // two quarter-full blobs of different batchers in the same block
+            batch("0xa", 100, quarter_blob, 2),
+            batch("0xb", 100, quarter_blob, 2),
+            // a three quarter-full blob in the next block
+            batch("0xb", 101, 3 * quarter_blob, 3),
+            // a batcher alone in its block
+            batch("0xa", 102, quarter_blob, 1),
+        ];
+
+        // the batches of each block share their blobs
+        let simulation = simulate_blob_sharing(&batches, 1);
+        assert_eq!(simulation.window_count, 3);
+        assert_eq!(simulation.shared_window_count, 1);
+        assert_eq!(simulation.blob_count, 4);
+        assert_eq!(simulation.shared_blob_count, 3);
+        assert_eq!(simulation.total_blob_wei_spent, blob_wei * (2 + 2 + 3 + 1));
+        assert_eq!(
+            simulation.total_shared_blob_wei_spent,
+            blob_wei * (2 + 3 + 1)
+        );
+        assert_eq!(simulation.total_savings_wei, blob_wei as i128 * 2);
+        assert_eq!(
+            simulation.batchers,
+            vec![
+                BatcherBlobSharing {
+                    batcher_address: "0xa".to_string(),
+                    batch_count: 2,
+                    shared_batch_count: 1,
+                    used_bytes: 2 * quarter_blob,
+                    blob_count: 2,
+                    blob_wei_spent: blob_wei * (2 + 1),
+                    shared_blob_wei_spent: blob_wei + blob_wei,
+                    savings_wei: blob_wei as i128,
+                    self_aggregation_savings_wei: 0,
+                },
+                BatcherBlobSharing {
+                    batcher_address: "0xb".to_string(),
+                    batch_count: 2,
+                    shared_batch_count: 1,
+                    used_bytes: 4 * quarter_blob,
+                    blob_count: 2,
+                    blob_wei_spent: blob_wei * (2 + 3),
+                    shared_blob_wei_spent: blob_wei + blob_wei * 3,
+                    savings_wei: blob_wei as i128,
+                    self_aggregation_savings_wei: 0,
+                },
+            ]
+        );
+
+        // the blocks 100 and 101 share a window posted at the blob base fee of the block 101
+        let simulation = simulate_blob_sharing(&batches, 2);
+        assert_eq!(simulation.window_count, 2);
+        assert_eq!(simulation.shared_window_count, 1);
+        // 5 quarters of payload fill 2 blobs
+        assert_eq!(simulation.shared_blob_count, 2 + 1);
+        assert_eq!(
+            simulation.total_shared_blob_wei_spent,
+            blob_wei * 2 * 3 + blob_wei
+        );
+        assert_eq!(
+            simulation.batchers[1].shared_blob_wei_spent,
+            blob_wei * 2 * 3 * 4 / 5
+        );
+    }
+
+    #[test]
+    fn test_single_batcher_is_not_shared() {
+        let quarter_blob = USABLE_BYTES_PER_BLOB / 4;
+        let blob_wei = GAS_PER_BLOB as u128;
+        let batch = |block_number, blob_gas_price| BlobBatch {
+            batcher_address: "0xa".to_string(),
+            block_number,
+            used_bytes: quarter_blob,
+            blob_count: 1,
+            blob_gas_price,
+        };
+        // one batcher posting a quarter-full blob in each block of the window
+        let batches = [batch(100, 2), batch(101, 3)];
+
+        let simulation = simulate_blob_sharing(&batches, 2);
+        assert_eq!(simulation.window_count, 1);
+        assert_eq!(simulation.shared_window_count, 0);
+        // the blobs are kept as posted
+        assert_eq!(simulation.shared_blob_count, 2);
+        assert_eq!(simulation.total_shared_blob_wei_spent, blob_wei * (2 + 3));
+        assert_eq!(simulation.total_savings_wei, 0);
+        // packing both payloads in one blob at the block 101
+        assert_eq!(
+            simulation.total_self_aggregation_savings_wei,
+            blob_wei as i128 * (2 + 3 - 3)
+        );
+        assert_eq!(simulation.batchers[0].shared_batch_count, 0);
+        assert_eq!(simulation.batchers[0].savings_wei, 0);
+        assert_eq!(
+            simulation.batchers[0].self_aggregation_savings_wei,
+            blob_wei as i128 * 2
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, io::Write, str::FromStr};
+
+/// The window size of the brotli compression, the one used by the OP Stack batcher.
+const BROTLI_WINDOW_BITS: u32 = 22;
+
+/// A compression algorithm a batcher can use on its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionAlgorithm {
+    Zlib,
+    Brotli,
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    /// It returns the range of the levels of the algorithm.
+    pub const fn levels(&self) -> (u32, u32) {
+        match self {
+            CompressionAlgorithm::Zlib => (0, 9),
+            CompressionAlgorithm::Brotli => (0, 11),
+            CompressionAlgorithm::Zstd => (1, 22),
+        }
+    }
+
+    /// It returns the level used when none is given.
+    pub const fn default_level(&self) -> u32 {
+        match self {
+            CompressionAlgorithm::Zlib => 6,
+            CompressionAlgorithm::Brotli => 10,
+            CompressionAlgorithm::Zstd => 3,
+        }
+    }
+}
+
+impl fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CompressionAlgorithm::Zlib => "zlib",
+            CompressionAlgorithm::Brotli => "brotli",
+            CompressionAlgorithm::Zstd => "zstd",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A compression algorithm with its level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Compression {
+    /// The compression algorithm.
+    pub algorithm: CompressionAlgorithm,
+    /// The compression level.
+    pub level: u32,
+}
+
+impl Compression {
+    /// It compresses the data.
+    pub fn compress(&self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self.algorithm {
+            CompressionAlgorithm::Zlib => {
+                let mut encoder = flate2::write::ZlibEncoder::new(
+                    Vec::new(),
+                    flate2::Compression::new(self.level),
+                );
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+            CompressionAlgorithm::Brotli => {
+                let mut compressed = Vec::new();
+                {
+                    let mut encoder = brotli::CompressorWriter::new(
+                        &mut compressed,
+                        4096,
+                        self.level,
+                        BROTLI_WINDOW_BITS,
+                    );
+                    encoder.write_all(data)?;
+                }
+                Ok(compressed)
+            }
+            CompressionAlgorithm::Zstd => zstd::bulk::compress(data, self.level as i32),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm, self.level)
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    /// It parses `<algorithm>` or `<algorithm>:<level>`, e.g. `zlib`, `brotli:11` or `zstd:19`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (algorithm, level) = match s.split_once(':') {
+            Some((algorithm, level)) => (algorithm, Some(level)),
+            None => (s, None),
+        };
+        let algorithm = match algorithm {
+            "zlib" => CompressionAlgorithm::Zlib,
+            "brotli" => CompressionAlgorithm::Brotli,
+            "zstd" => CompressionAlgorithm::Zstd,
+            _ => return Err(format!("unknown compression algorithm: {}", algorithm)),
+        };
+        let level = match level {
+            Some(level) => level
+                .parse()
+                .map_err(|_| format!("invalid compression level: {}", level))?,
+            None => algorithm.default_level(),
+        };
+        let (min_level, max_level) = algorithm.levels();
+        if !(min_level..=max_level).contains(&level) {
+            return Err(format!(
+                "{} levels must be between {} and {}",
+                algorithm, min_level, max_level
+            ));
+        }
+        Ok(Compression { algorithm, level })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_compress_round_trip() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 7) as u8).collect();
+        for compression in ["zlib:9", "brotli:11", "zstd:19"] {
+            let compression: Compression = compression.parse().unwrap();
+            let compressed = compression.compress(&data).unwrap();
+            assert!(compressed.len() < data.len() / 10);
+            let mut decompressed = Vec::new();
+            match compression.algorithm {
+                CompressionAlgorithm::Zlib => {
+                    flate2::read::ZlibDecoder::new(compressed.as_slice())
+                        .read_to_end(&mut decompressed)
+                        .unwrap();
+                }
+                CompressionAlgorithm::Brotli => {
+                    brotli::Decompressor::new(compressed.as_slice(), 4096)
+                        .read_to_end(&mut decompressed)
+                        .unwrap();
+                }
+                CompressionAlgorithm::Zstd => {
+                    decompressed = zstd::decode_all(compressed.as_slice()).unwrap();
+                }
+            }
+            assert_eq!(decompressed, data);
+        }
+    }
+
+    #[test]
+    fn test_parse_compression() {
+        assert_eq!(
+            "brotli".parse(),
+            Ok(Compression {
+                algorithm: CompressionAlgorithm::Brotli,
+                level: 10
+            })
+        );
+        assert_eq!(
+            "zstd:19".parse::<Compression>().unwrap().to_string(),
+            "zstd:19"
+        );
+        assert!("zlib:10".parse::<Compression>().is_err());
+        assert!("zstd:0".parse::<Compression>().is_err());
+        assert!("lz4".parse::<Compression>().is_err());
+    }
+}
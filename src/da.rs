@@ -0,0 +1,183 @@
+use crate::server::types::{AltDaAnalysis, AltDaCost};
+use serde::{Deserialize, Serialize};
+
+/// The number of bytes in the MB of the per MB rates.
+pub const BYTES_PER_MB: u64 = 1_000_000;
+
+/// The unit an alternative data availability layer charges for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DaPriceUnit {
+    /// Each byte of the payload.
+    Byte,
+    /// Each MB of the payload, prorated to the byte.
+    Mb,
+    /// Each blob of the layer the payload fills, the last one being charged in full.
+    Blob,
+}
+
+/// The price of a unit from a given moment on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaRate {
+    /// The timestamp the rate applies from.
+    pub from_timestamp: u64,
+    /// The price of a unit, in wei.
+    pub price_wei: u128,
+}
+
+/// An alternative data availability layer and its historical rates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaLayer {
+    /// The name of the layer, e.g. `celestia`.
+    pub name: String,
+    /// The unit the rates are charged for.
+    pub unit: DaPriceUnit,
+    /// The size of a blob of the layer, required by the per blob rates.
+    #[serde(default)]
+    pub blob_size: Option<u64>,
+    /// The rates, sorted by timestamp.
+    pub rates: Vec<DaRate>,
+}
+
+impl DaLayer {
+    /// The price of a unit at the timestamp: the one of the latest rate at or before it.
+    pub fn price_at(&self, timestamp: u64) -> Option<u128> {
+        let index = self
+            .rates
+            .partition_point(|rate| rate.from_timestamp <= timestamp);
+        index
+            .checked_sub(1)
+            .map(|index| self.rates[index].price_wei)
+    }
+
+    /// It returns the cost of a payload of the given size at the timestamp.
+    ///
+    /// None if the layer has no rate at the timestamp.
+    pub fn cost(&self, payload_size: u64, timestamp: u64) -> Option<AltDaCost> {
+        let price_wei = self.price_at(timestamp)?;
+        let wei_spent = match self.unit {
+            DaPriceUnit::Byte => payload_size as u128 * price_wei,
+            DaPriceUnit::Mb => (payload_size as u128 * price_wei).div_ceil(BYTES_PER_MB as u128),
+            DaPriceUnit::Blob => {
+                // the table is validated on load
+                let blob_size = self.blob_size.unwrap_or(1);
+                payload_size.div_ceil(blob_size) as u128 * price_wei
+            }
+        };
+        Some(AltDaCost {
+            layer: self.name.clone(),
+            unit: self.unit,
+            price_wei,
+            wei_spent,
+        })
+    }
+}
+
+/// A local table of the prices of alternative data availability layers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaPriceTable {
+    /// The layers the payloads are priced on.
+    pub layers: Vec<DaLayer>,
+}
+
+impl DaPriceTable {
+    /// Parse the JSON content of a price table.
+    pub fn parse(content: &str) -> eyre::Result<Self> {
+        let mut table: DaPriceTable = serde_json::from_str(content)?;
+        for layer in &mut table.layers {
+            if layer.unit == DaPriceUnit::Blob && layer.blob_size.is_none_or(|size| size == 0) {
+                eyre::bail!("The per blob rates of {} require a blob_size", layer.name);
+            }
+            layer.rates.sort_by_key(|rate| rate.from_timestamp);
+        }
+        Ok(table)
+    }
+
+    /// Load a price table from a JSON file.
+    pub fn from_json_file(path: &str) -> eyre::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    /// It prices a payload of the given size on each layer with a rate at the timestamp.
+    pub fn analysis(&self, payload_size: u64, timestamp: u64) -> AltDaAnalysis {
+        AltDaAnalysis {
+            payload_size,
+            costs: self
+                .layers
+                .iter()
+                .filter_map(|layer| layer.cost(payload_size, timestamp))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_da_price_table() {
+        let table = DaPriceTable::parse(
+            r#"{"layers": [
+                {"name": "per_byte", "unit": "byte", "rates": [
+                    {"from_timestamp": 2000, "price_wei": 3},
+                    {"from_timestamp": 1000, "price_wei": 2}
+                ]},
+                {"name": "per_mb", "unit": "mb", "rates": [
+                    {"from_timestamp": 1000, "price_wei": 1000000000}
+                ]},
+                {"name": "per_blob", "unit": "blob", "blob_size": 1000, "rates": [
+                    {"from_timestamp": 1500, "price_wei": 7}
+                ]}
+            ]}"#,
+        )
+        .unwrap();
+
+        // before the first rate of the per blob layer
+        let analysis = table.analysis(2500, 1000);
+        assert_eq!(analysis.payload_size, 2500);
+        assert_eq!(
+            analysis.costs,
+            vec![
+                AltDaCost {
+                    layer: "per_byte".to_string(),
+                    unit: DaPriceUnit::Byte,
+                    price_wei: 2,
+                    wei_spent: 5000,
+                },
+                AltDaCost {
+                    layer: "per_mb".to_string(),
+                    unit: DaPriceUnit::Mb,
+                    price_wei: 1000000000,
+                    wei_spent: 2500000,
+                },
+            ]
+        );
+
+        // the rates are sorted on load
+        let analysis = table.analysis(2500, 2000);
+        assert_eq!(analysis.costs[0].wei_spent, 7500);
+        // 3 blobs, the last one partially filled
+        assert_eq!(analysis.costs[2].wei_spent, 21);
+
+        // prorated to the byte and rounded up to the wei
+        assert_eq!(table.layers[1].cost(1, 1000).unwrap().wei_spent, 1000);
+        let cheap_mb = DaLayer {
+            rates: vec![DaRate {
+                from_timestamp: 0,
+                price_wei: 1,
+            }],
+            ..table.layers[1].clone()
+        };
+        assert_eq!(cheap_mb.cost(1, 0).unwrap().wei_spent, 1);
+
+        // per blob rates without a blob size
+        assert!(
+            DaPriceTable::parse(
+                r#"{"layers": [{"name": "per_blob", "unit": "blob", "rates": []}]}"#
+            )
+            .is_err()
+        );
+    }
+}
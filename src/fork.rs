@@ -0,0 +1,512 @@
+use alloy_chains::NamedChain;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// The block number of the istanbul hard fork on Ethereum mainnet.
+pub const MAINNET_ISTANBUL_BLOCK_NUMBER: u64 = 9_069_000;
+/// The block number of the berlin hard fork on Ethereum mainnet.
+pub const MAINNET_BERLIN_BLOCK_NUMBER: u64 = 12_244_000;
+/// The block number of the london hard fork on Ethereum mainnet.
+pub const MAINNET_LONDON_BLOCK_NUMBER: u64 = 12_965_000;
+/// The timestamp of the shanghai hard fork on Ethereum mainnet.
+pub const MAINNET_SHANGHAI_TIMESTAMP: u64 = 1_681_338_455;
+/// The timestamp of the cancun hard fork on Ethereum mainnet.
+pub const MAINNET_CANCUN_TIMESTAMP: u64 = 1_710_338_135;
+/// The timestamp of the prague hard fork on Ethereum mainnet.
+pub const MAINNET_PRAGUE_TIMESTAMP: u64 = 1_746_612_311;
+/// The timestamp of the osaka hard fork on Ethereum mainnet.
+pub const MAINNET_OSAKA_TIMESTAMP: u64 = 1_764_798_551;
+/// The timestamp of the first blob parameter only fork on Ethereum mainnet.
+pub const MAINNET_BPO1_TIMESTAMP: u64 = 1_765_290_071;
+/// The timestamp of the second blob parameter only fork on Ethereum mainnet.
+pub const MAINNET_BPO2_TIMESTAMP: u64 = 1_767_747_671;
+
+/// The standard cost of a calldata token.
+pub const STANDARD_TOKEN_COST: u64 = 4;
+/// The cost of the calldata floor per token.
+///
+/// This is taken from the EIP-7623 spec.
+pub const TOTAL_COST_FLOOR_PER_TOKEN: u64 = 10;
+
+/// The hard forks that change how calldata and blobs are priced.
+///
+/// Variants are ordered by activation, so `fork >= Fork::Prague` can be used to check
+/// whether a rule is in effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Fork {
+    /// Every fork before istanbul, calldata non-zero bytes cost 68 gas.
+    Frontier,
+    /// EIP-2028, calldata non-zero bytes cost 16 gas.
+    Istanbul,
+    /// EIP-2930, access lists.
+    Berlin,
+    /// EIP-1559, base fee.
+    London,
+    /// EIP-3860, initcode metering.
+    Shanghai,
+    /// EIP-4844, blobs.
+    Cancun,
+    /// EIP-7623 and EIP-7691, calldata floor and blob throughput increase.
+    Prague,
+    /// EIP-7918 and EIP-7594, blob reserve price and PeerDAS.
+    Osaka,
+    /// EIP-7892, first blob parameter only fork.
+    Bpo1,
+    /// EIP-7892, second blob parameter only fork.
+    Bpo2,
+}
+
+impl Fork {
+    /// The default parameters of the fork on Ethereum mainnet.
+    pub const fn default_params(self) -> ForkParams {
+        let blob = match self {
+            Fork::Frontier | Fork::Istanbul | Fork::Berlin | Fork::London | Fork::Shanghai => None,
+            Fork::Cancun => Some(BlobScheduleParams::cancun()),
+            Fork::Prague | Fork::Osaka => Some(BlobScheduleParams::prague()),
+            Fork::Bpo1 => Some(BlobScheduleParams::bpo1()),
+            Fork::Bpo2 => Some(BlobScheduleParams::bpo2()),
+        };
+        ForkParams {
+            fork: self,
+            standard_token_cost: STANDARD_TOKEN_COST,
+            total_cost_floor_per_token: TOTAL_COST_FLOOR_PER_TOKEN,
+            blob,
+        }
+    }
+}
+
+/// The moment a fork becomes active, either by block number or by block timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForkActivation {
+    /// The fork is active from this block number onwards.
+    Block(u64),
+    /// The fork is active from the first block with this timestamp or later.
+    Timestamp(u64),
+}
+
+impl ForkActivation {
+    /// Is the activation reached at the given block?
+    pub const fn is_active(&self, block_number: u64, timestamp: u64) -> bool {
+        match *self {
+            ForkActivation::Block(activation_block) => block_number >= activation_block,
+            ForkActivation::Timestamp(activation_timestamp) => timestamp >= activation_timestamp,
+        }
+    }
+}
+
+/// The blob parameters of a fork.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-7840
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobScheduleParams {
+    /// The target number of blobs per block.
+    pub target_blobs_per_block: u64,
+    /// The maximum number of blobs per block.
+    pub max_blobs_per_block: u64,
+    /// The update fraction of the blob base fee.
+    pub base_fee_update_fraction: u64,
+}
+
+impl BlobScheduleParams {
+    /// The blob parameters introduced by EIP-4844 in cancun.
+    pub const fn cancun() -> Self {
+        Self {
+            target_blobs_per_block: 3,
+            max_blobs_per_block: 6,
+            base_fee_update_fraction: 3_338_477,
+        }
+    }
+
+    /// The blob parameters introduced by EIP-7691 in prague.
+    pub const fn prague() -> Self {
+        Self {
+            target_blobs_per_block: 6,
+            max_blobs_per_block: 9,
+            base_fee_update_fraction: 5_007_716,
+        }
+    }
+
+    /// The blob parameters of the first blob parameter only fork.
+    pub const fn bpo1() -> Self {
+        Self {
+            target_blobs_per_block: 10,
+            max_blobs_per_block: 15,
+            base_fee_update_fraction: 8_346_193,
+        }
+    }
+
+    /// The blob parameters of the second blob parameter only fork.
+    pub const fn bpo2() -> Self {
+        Self {
+            target_blobs_per_block: 14,
+            max_blobs_per_block: 21,
+            base_fee_update_fraction: 11_684_671,
+        }
+    }
+}
+
+/// The pricing parameters in effect at a given block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkParams {
+    /// The latest active fork.
+    pub fork: Fork,
+    /// The standard cost of a calldata token.
+    pub standard_token_cost: u64,
+    /// The EIP-7623 floor cost of a calldata token.
+    ///
+    /// It's set even before prague, so that the EIP-7623 counterfactual can be computed.
+    pub total_cost_floor_per_token: u64,
+    /// The blob parameters, None before cancun.
+    pub blob: Option<BlobScheduleParams>,
+}
+
+impl ForkParams {
+    /// Is EIP-2028 (istanbul) calldata pricing in effect?
+    pub fn is_istanbul(&self) -> bool {
+        self.fork >= Fork::Istanbul
+    }
+
+    /// Is the EIP-7623 calldata floor in effect?
+    pub fn is_eip_7623_enabled(&self) -> bool {
+        self.fork >= Fork::Prague
+    }
+}
+
+/// A calldata pricing schedule, used to evaluate repricing proposals against the real one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalldataPricing {
+    /// The standard cost of a calldata token.
+    pub standard_token_cost: u64,
+    /// The floor cost of a calldata token.
+    pub total_cost_floor_per_token: u64,
+    /// The number of tokens of a zero byte.
+    pub zero_byte_tokens: u64,
+    /// The number of tokens of a non-zero byte.
+    pub non_zero_byte_tokens: u64,
+}
+
+impl CalldataPricing {
+    /// The calldata pricing introduced by EIP-7623 in prague.
+    pub const fn eip_7623() -> Self {
+        Self {
+            standard_token_cost: STANDARD_TOKEN_COST,
+            total_cost_floor_per_token: TOTAL_COST_FLOOR_PER_TOKEN,
+            zero_byte_tokens: 1,
+            non_zero_byte_tokens: 4,
+        }
+    }
+
+    /// The calldata floor increase proposed by EIP-7976.
+    ///
+    /// Link: https://eips.ethereum.org/EIPS/eip-7976
+    pub const fn eip_7976() -> Self {
+        Self {
+            total_cost_floor_per_token: 15,
+            ..Self::eip_7623()
+        }
+    }
+
+    /// It returns the pricing with the given name, either `eip7623` or `eip7976`.
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "eip7623" => Some(Self::eip_7623()),
+            "eip7976" => Some(Self::eip_7976()),
+            _ => None,
+        }
+    }
+
+    /// It returns the number of tokens of the given calldata bytes.
+    pub const fn tokens(&self, zero_bytes: u64, non_zero_bytes: u64) -> u64 {
+        zero_bytes * self.zero_byte_tokens + non_zero_bytes * self.non_zero_byte_tokens
+    }
+
+    /// It returns the gas cost of the given calldata bytes at the standard token cost.
+    pub const fn legacy_calldata_gas(&self, zero_bytes: u64, non_zero_bytes: u64) -> u64 {
+        self.standard_token_cost * self.tokens(zero_bytes, non_zero_bytes)
+    }
+
+    /// It returns the gas cost of the given calldata bytes at the floor token cost.
+    pub const fn floor_calldata_gas(&self, zero_bytes: u64, non_zero_bytes: u64) -> u64 {
+        self.total_cost_floor_per_token * self.tokens(zero_bytes, non_zero_bytes)
+    }
+}
+
+/// A single entry of a fork schedule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkEntry {
+    /// The fork.
+    pub fork: Fork,
+    /// When the fork activates.
+    pub activation: ForkActivation,
+    /// Parameters overriding the fork defaults, useful for devnets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<ForkParams>,
+}
+
+impl ForkEntry {
+    /// Create a new entry using the default parameters of the fork.
+    pub const fn new(fork: Fork, activation: ForkActivation) -> Self {
+        Self {
+            fork,
+            activation,
+            params: None,
+        }
+    }
+
+    /// The parameters in effect while this entry is the latest active fork.
+    pub fn params(&self) -> ForkParams {
+        self.params.unwrap_or_else(|| self.fork.default_params())
+    }
+}
+
+/// The fork schedule of a chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkSchedule {
+    /// The chain id.
+    pub chain_id: u64,
+    /// The forks of the chain, sorted by activation.
+    pub forks: Vec<ForkEntry>,
+}
+
+impl ForkSchedule {
+    /// Create a new fork schedule, sorting the entries by fork.
+    pub fn new(chain_id: u64, mut forks: Vec<ForkEntry>) -> Self {
+        forks.sort_by_key(|entry| entry.fork);
+        Self { chain_id, forks }
+    }
+
+    /// The Ethereum mainnet fork schedule.
+    pub fn mainnet() -> Self {
+        Self::new(
+            NamedChain::Mainnet.into(),
+            vec![
+                ForkEntry::new(Fork::Frontier, ForkActivation::Block(0)),
+                ForkEntry::new(
+                    Fork::Istanbul,
+                    ForkActivation::Block(MAINNET_ISTANBUL_BLOCK_NUMBER),
+                ),
+                ForkEntry::new(
+                    Fork::Berlin,
+                    ForkActivation::Block(MAINNET_BERLIN_BLOCK_NUMBER),
+                ),
+                ForkEntry::new(
+                    Fork::London,
+                    ForkActivation::Block(MAINNET_LONDON_BLOCK_NUMBER),
+                ),
+                ForkEntry::new(
+                    Fork::Shanghai,
+                    ForkActivation::Timestamp(MAINNET_SHANGHAI_TIMESTAMP),
+                ),
+                ForkEntry::new(
+                    Fork::Cancun,
+                    ForkActivation::Timestamp(MAINNET_CANCUN_TIMESTAMP),
+                ),
+                ForkEntry::new(
+                    Fork::Prague,
+                    ForkActivation::Timestamp(MAINNET_PRAGUE_TIMESTAMP),
+                ),
+                ForkEntry::new(
+                    Fork::Osaka,
+                    ForkActivation::Timestamp(MAINNET_OSAKA_TIMESTAMP),
+                ),
+                ForkEntry::new(
+                    Fork::Bpo1,
+                    ForkActivation::Timestamp(MAINNET_BPO1_TIMESTAMP),
+                ),
+                ForkEntry::new(
+                    Fork::Bpo2,
+                    ForkActivation::Timestamp(MAINNET_BPO2_TIMESTAMP),
+                ),
+            ],
+        )
+    }
+
+    /// The Sepolia testnet fork schedule.
+    pub fn sepolia() -> Self {
+        Self::new(
+            NamedChain::Sepolia.into(),
+            vec![
+                ForkEntry::new(Fork::London, ForkActivation::Block(0)),
+                ForkEntry::new(Fork::Shanghai, ForkActivation::Timestamp(1_677_557_088)),
+                ForkEntry::new(Fork::Cancun, ForkActivation::Timestamp(1_706_655_072)),
+                ForkEntry::new(Fork::Prague, ForkActivation::Timestamp(1_741_159_776)),
+                ForkEntry::new(Fork::Osaka, ForkActivation::Timestamp(1_760_427_360)),
+                ForkEntry::new(Fork::Bpo1, ForkActivation::Timestamp(1_761_017_184)),
+                ForkEntry::new(Fork::Bpo2, ForkActivation::Timestamp(1_761_607_008)),
+            ],
+        )
+    }
+
+    /// The Holesky testnet fork schedule.
+    pub fn holesky() -> Self {
+        Self::new(
+            NamedChain::Holesky.into(),
+            vec![
+                ForkEntry::new(Fork::London, ForkActivation::Block(0)),
+                ForkEntry::new(Fork::Shanghai, ForkActivation::Timestamp(1_696_000_704)),
+                ForkEntry::new(Fork::Cancun, ForkActivation::Timestamp(1_707_305_664)),
+                ForkEntry::new(Fork::Prague, ForkActivation::Timestamp(1_740_434_112)),
+                ForkEntry::new(Fork::Osaka, ForkActivation::Timestamp(1_759_308_480)),
+                ForkEntry::new(Fork::Bpo1, ForkActivation::Timestamp(1_759_800_000)),
+                ForkEntry::new(Fork::Bpo2, ForkActivation::Timestamp(1_760_389_824)),
+            ],
+        )
+    }
+
+    /// The built-in fork schedule of a chain.
+    pub fn for_chain(chain_id: u64) -> Self {
+        match NamedChain::try_from(chain_id) {
+            Ok(NamedChain::Mainnet) => Self::mainnet(),
+            Ok(NamedChain::Sepolia) => Self::sepolia(),
+            Ok(NamedChain::Holesky) => Self::holesky(),
+            _ => {
+                warn!(
+                    "We don't have a fork schedule for chain id {}, fallback to mainnet",
+                    chain_id
+                );
+                Self::mainnet()
+            }
+        }
+    }
+
+    /// Load a custom fork schedule from a JSON file, e.g. for devnets.
+    pub fn from_json_file(path: &str) -> eyre::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let schedule: ForkSchedule = serde_json::from_str(&content)?;
+        Ok(Self::new(schedule.chain_id, schedule.forks))
+    }
+
+    /// The latest fork entry active at the given block, if any.
+    fn active_entry(&self, block_number: u64, timestamp: u64) -> Option<&ForkEntry> {
+        self.forks
+            .iter()
+            .rev()
+            .find(|entry| entry.activation.is_active(block_number, timestamp))
+    }
+
+    /// The latest fork active at the given block.
+    pub fn active_fork(&self, block_number: u64, timestamp: u64) -> Fork {
+        self.active_entry(block_number, timestamp)
+            .map_or(Fork::Frontier, |entry| entry.fork)
+    }
+
+    /// Is the fork active at the given block?
+    pub fn is_active(&self, fork: Fork, block_number: u64, timestamp: u64) -> bool {
+        self.active_fork(block_number, timestamp) >= fork
+    }
+
+    /// The pricing parameters in effect at the given block.
+    pub fn params_at(&self, block_number: u64, timestamp: u64) -> ForkParams {
+        self.active_entry(block_number, timestamp)
+            .map_or(Fork::Frontier.default_params(), ForkEntry::params)
+    }
+
+    /// The pricing parameters of a fork in this schedule, or its defaults if it's not scheduled.
+    pub fn fork_params(&self, fork: Fork) -> ForkParams {
+        self.forks
+            .iter()
+            .find(|entry| entry.fork == fork)
+            .map_or(fork.default_params(), ForkEntry::params)
+    }
+}
+
+impl Default for ForkSchedule {
+    fn default() -> Self {
+        Self::mainnet()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mainnet_schedule() {
+        let schedule = ForkSchedule::mainnet();
+        assert_eq!(schedule.active_fork(9_068_999, 0), Fork::Frontier);
+        assert_eq!(
+            schedule.active_fork(MAINNET_ISTANBUL_BLOCK_NUMBER, 0),
+            Fork::Istanbul
+        );
+        // a block right before pectra must not be treated as post-pectra
+        let pre_pectra = schedule.params_at(22_400_000, MAINNET_PRAGUE_TIMESTAMP - 12);
+        assert_eq!(pre_pectra.fork, Fork::Cancun);
+        assert!(!pre_pectra.is_eip_7623_enabled());
+        assert_eq!(pre_pectra.blob, Some(BlobScheduleParams::cancun()));
+        let post_pectra = schedule.params_at(22_431_084, MAINNET_PRAGUE_TIMESTAMP);
+        assert_eq!(post_pectra.fork, Fork::Prague);
+        assert!(post_pectra.is_eip_7623_enabled());
+        assert_eq!(post_pectra.blob, Some(BlobScheduleParams::prague()));
+        // the blob parameter only forks keep the rules of osaka
+        let osaka = schedule.params_at(23_935_694, MAINNET_BPO1_TIMESTAMP - 12);
+        assert_eq!(osaka.fork, Fork::Osaka);
+        assert_eq!(osaka.blob, Some(BlobScheduleParams::prague()));
+        let post_bpo1 = schedule.params_at(23_975_796, MAINNET_BPO1_TIMESTAMP);
+        assert_eq!(post_bpo1.blob, Some(BlobScheduleParams::bpo1()));
+        let post_bpo2 = schedule.params_at(24_180_000, MAINNET_BPO2_TIMESTAMP + 12);
+        assert_eq!(post_bpo2.fork, Fork::Bpo2);
+        assert!(post_bpo2.is_eip_7623_enabled());
+        assert!(schedule.is_active(Fork::Osaka, 24_180_000, MAINNET_BPO2_TIMESTAMP + 12));
+        assert_eq!(post_bpo2.blob.unwrap().base_fee_update_fraction, 11_684_671);
+    }
+
+    #[test]
+    fn test_custom_schedule() {
+        let json = r#"{
+            "chain_id": 1337,
+            "forks": [
+                { "fork": "prague", "activation": { "timestamp": 100 } },
+                { "fork": "cancun", "activation": { "block": 0 },
+                  "params": {
+                    "fork": "cancun",
+                    "standard_token_cost": 4,
+                    "total_cost_floor_per_token": 10,
+                    "blob": { "target_blobs_per_block": 1, "max_blobs_per_block": 2, "base_fee_update_fraction": 1000 }
+                  }
+                }
+            ]
+        }"#;
+        let schedule: ForkSchedule = serde_json::from_str(json).unwrap();
+        let schedule = ForkSchedule::new(schedule.chain_id, schedule.forks);
+        assert_eq!(schedule.forks[0].fork, Fork::Cancun);
+        assert_eq!(
+            schedule.params_at(5, 99).blob.unwrap().max_blobs_per_block,
+            2
+        );
+        assert_eq!(schedule.active_fork(5, 100), Fork::Prague);
+        assert!(schedule.is_active(Fork::Cancun, 5, 100));
+        assert_eq!(
+            schedule
+                .fork_params(Fork::Cancun)
+                .blob
+                .unwrap()
+                .max_blobs_per_block,
+            2
+        );
+        assert_eq!(
+            schedule.fork_params(Fork::Osaka),
+            Fork::Osaka.default_params()
+        );
+    }
+
+    #[test]
+    fn test_calldata_pricing() {
+        // 2 zero bytes and 3 non-zero bytes, 14 tokens
+        let eip_7623 = CalldataPricing::named("eip7623").unwrap();
+        assert_eq!(eip_7623.tokens(2, 3), 14);
+        assert_eq!(eip_7623.legacy_calldata_gas(2, 3), 56);
+        assert_eq!(eip_7623.floor_calldata_gas(2, 3), 140);
+        let eip_7976 = CalldataPricing::named("eip7976").unwrap();
+        assert_eq!(eip_7976.legacy_calldata_gas(2, 3), 56);
+        assert_eq!(eip_7976.floor_calldata_gas(2, 3), 210);
+        // a flat pricing of every byte
+        let flat = CalldataPricing {
+            zero_byte_tokens: 4,
+            ..eip_7976
+        };
+        assert_eq!(flat.floor_calldata_gas(2, 3), 300);
+        assert_eq!(CalldataPricing::named("eip9999"), None);
+    }
+}
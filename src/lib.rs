@@ -1,4 +1,12 @@
+pub mod batch;
+pub mod blob;
+pub mod compression;
+pub mod da;
+pub mod fork;
+pub mod price;
 pub mod provider;
 pub mod server;
+pub mod simulation;
+pub mod timing;
 pub mod tracker;
 pub mod utils;
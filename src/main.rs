@@ -1,14 +1,26 @@
 use alloy_provider::Provider;
-use axum::{Router, routing::get};
+use axum::{
+    Router,
+    routing::{get, post},
+};
 use pectralizer::{
+    da::DaPriceTable,
+    fork::ForkSchedule,
+    price::{chainlink::ChainlinkPriceSource, csv::CsvPriceSource},
     provider::ProviderState,
     server::{
         AppState,
         handlers::{
-            all_blob_data_gas_handler, all_daily_txs_handler, all_eth_saved_handler,
-            all_pectra_data_gas_handler, blob_data_gas_handler, contract_handler,
-            daily_txs_handler, eth_saved_handler, pectra_data_gas_handler, root_handler,
-            seven_day_stats_handler, tx_handler,
+            all_alt_da_cost_handler, all_blob_data_gas_handler, all_blob_utilization_handler,
+            all_compression_handler, all_daily_byte_profile_handler,
+            all_daily_eip_7918_extra_cost_handler, all_daily_regret_handler,
+            all_daily_timing_handler, all_daily_txs_handler, all_eth_saved_handler,
+            all_fee_breakdown_handler, all_l2_tx_cost_handler, all_pectra_data_gas_handler,
+            all_timing_efficiency_handler, all_wasted_spend_handler,
+            all_wrong_mode_batches_handler, blob_data_gas_handler, blob_fee_projection_handler,
+            blob_sharing_handler, break_even_fees_handler, contract_handler, daily_txs_handler,
+            estimate_handler, eth_saved_handler, pectra_data_gas_handler, raw_tx_handler,
+            root_handler, seven_day_stats_handler, simulate_handler, tx_handler,
         },
     },
     tracker::{
@@ -76,9 +88,58 @@ async fn main() -> eyre::Result<()> {
         .parse()?;
 
     // initialize shared provider state
-    let provider_state =
+    let mut provider_state =
         ProviderState::new(&ethereum_provider_url, &etherscan_api_key, chain_id).await;
 
+    // load a custom fork schedule if provided, e.g. for devnets
+    if let Ok(fork_schedule_path) = std::env::var("FORK_SCHEDULE_PATH") {
+        let fork_schedule = ForkSchedule::from_json_file(&fork_schedule_path)
+            .map_err(|e| eyre::eyre!("Failed to load fork schedule: {}", e))?;
+        info!("Loaded custom fork schedule from {}", fork_schedule_path);
+        provider_state = provider_state.with_fork_schedule(fork_schedule);
+    }
+
+    // convert wei to fiat with a CSV of historical prices, or with the Chainlink ETH/USD feed
+    if let Ok(price_csv_path) = std::env::var("PRICE_CSV_PATH") {
+        let price_source = CsvPriceSource::from_file(&price_csv_path)
+            .map_err(|e| eyre::eyre!("Failed to load price CSV: {}", e))?;
+        info!("Loaded ETH prices from {}", price_csv_path);
+        provider_state = provider_state.with_price_source(Arc::new(price_source));
+    } else {
+        let feed = match std::env::var("CHAINLINK_ETH_USD_FEED") {
+            Ok(feed) => Some(
+                feed.parse()
+                    .map_err(|_| eyre::eyre!("CHAINLINK_ETH_USD_FEED must be an address"))?,
+            ),
+            Err(_) => ChainlinkPriceSource::eth_usd_feed(chain_id),
+        };
+        if let Some(feed) = feed {
+            info!(
+                "Reading ETH prices from the Chainlink aggregator {:#x}",
+                feed
+            );
+            let price_source =
+                ChainlinkPriceSource::new(provider_state.ethereum_provider.clone(), feed);
+            provider_state = provider_state.with_price_source(Arc::new(price_source));
+        }
+    }
+
+    // compare the fees paid by the tracked batches with the ones of the blocks around them
+    if let Ok(timing_window_blocks) = std::env::var("TIMING_WINDOW_BLOCKS") {
+        let timing_window_blocks = timing_window_blocks
+            .parse()
+            .map_err(|_| eyre::eyre!("TIMING_WINDOW_BLOCKS must be a number of blocks"))?;
+        provider_state = provider_state.with_timing_window_blocks(timing_window_blocks);
+    }
+
+    // price the payloads on alternative data availability layers
+    if let Ok(da_price_table_path) = std::env::var("DA_PRICE_TABLE_PATH") {
+        let da_price_table = DaPriceTable::from_json_file(&da_price_table_path)
+            .map_err(|e| eyre::eyre!("Failed to load DA price table: {}", e))?;
+        info!("Loaded DA price table from {}", da_price_table_path);
+        provider_state = provider_state.with_da_price_table(da_price_table);
+    }
+
     // initialize the database for API endpoints
     let current_block = provider_state
         .ethereum_provider
@@ -112,7 +173,11 @@ async fn main() -> eyre::Result<()> {
     let app = Router::new()
         .route("/", get(root_handler))
         .route("/tx", get(tx_handler))
+        .route("/tx/simulate", get(simulate_handler))
+        .route("/tx/raw", post(raw_tx_handler))
         .route("/contract", get(contract_handler))
+        .route("/blob_fee_projection", get(blob_fee_projection_handler))
+        .route("/estimate", post(estimate_handler))
         .route("/daily_txs", get(daily_txs_handler))
         .route("/eth_saved", get(eth_saved_handler))
         .route("/blob_data_gas", get(blob_data_gas_handler))
@@ -121,7 +186,30 @@ async fn main() -> eyre::Result<()> {
         .route("/all_eth_saved", get(all_eth_saved_handler))
         .route("/all_blob_data_gas", get(all_blob_data_gas_handler))
         .route("/all_pectra_data_gas", get(all_pectra_data_gas_handler))
+        .route("/all_blob_utilization", get(all_blob_utilization_handler))
+        .route("/all_compression", get(all_compression_handler))
+        .route("/all_alt_da_cost", get(all_alt_da_cost_handler))
+        .route("/blob_sharing", get(blob_sharing_handler))
+        .route("/all_l2_tx_cost", get(all_l2_tx_cost_handler))
+        .route("/all_fee_breakdown", get(all_fee_breakdown_handler))
+        .route("/all_wasted_spend", get(all_wasted_spend_handler))
+        .route(
+            "/all_wrong_mode_batches",
+            get(all_wrong_mode_batches_handler),
+        )
+        .route("/all_daily_regret", get(all_daily_regret_handler))
+        .route("/break_even_fees", get(break_even_fees_handler))
+        .route("/all_timing_efficiency", get(all_timing_efficiency_handler))
+        .route("/all_daily_timing", get(all_daily_timing_handler))
         .route("/seven_day_stats", get(seven_day_stats_handler))
+        .route(
+            "/all_daily_eip_7918_extra_cost",
+            get(all_daily_eip_7918_extra_cost_handler),
+        )
+        .route(
+            "/all_daily_byte_profile",
+            get(all_daily_byte_profile_handler),
+        )
         .layer(CorsLayer::permissive())
         .with_state(app_state.clone());
 
@@ -135,7 +223,13 @@ async fn main() -> eyre::Result<()> {
     info!("   Individual Batcher Endpoints:");
     info!("   - GET  /           - Welcome message");
     info!("   - GET  /tx         - Transaction analysis");
+    info!("   - GET  /tx/simulate - Transaction re-execution under Cancun and Prague rules");
+    info!("   - POST /tx/raw     - Signed raw transaction analysis, without chain access");
     info!("   - GET  /contract   - Contract analysis");
+    info!("   - GET  /blob_fee_projection - Blob base fee projection over the next blocks");
+    info!(
+        "   - POST /estimate - Calldata and blob cost estimate of raw data, without chain access"
+    );
     info!("   - GET  /daily_txs  - Daily transactions analysis (specific batcher)");
     info!("   - GET  /eth_saved  - Ethereum saved analysis (specific batcher)");
     info!("   - GET  /blob_data_gas - Blob data gas analysis (specific batcher)");
@@ -145,7 +239,27 @@ async fn main() -> eyre::Result<()> {
     info!("   - GET  /all_eth_saved - ETH saved data for all batchers");
     info!("   - GET  /all_blob_data_gas - Blob data gas for all batchers");
     info!("   - GET  /all_pectra_data_gas - Pectra data gas for all batchers");
+    info!("   - GET  /all_blob_utilization - Average blob fill rate for all batchers");
+    info!("   - GET  /all_compression - Payload compressibility for all batchers");
+    info!("   - GET  /all_alt_da_cost - Payload cost on alternative DA layers for all batchers");
+    info!("   - GET  /blob_sharing - Savings of sharing blobs between the tracked batchers");
+    info!("   - GET  /all_l2_tx_cost - L1 cost per L2 transaction for all batchers");
+    info!("   - GET  /all_fee_breakdown - Base, priority and blob fees paid by all batchers");
+    info!("   - GET  /all_wasted_spend - Wei burned on reverted batches for all batchers");
+    info!("   - GET  /all_wrong_mode_batches - Batches posted in the more expensive mode");
+    info!("   - GET  /all_daily_regret - Daily regret of the posting mode for all batchers");
+    info!("   - GET  /break_even_fees - Hourly blob vs calldata break-even blob base fee");
+    info!(
+        "   - GET  /all_timing_efficiency - Premium paid over the cheapest block of the window for all batchers"
+    );
+    info!("   - GET  /all_daily_timing - Daily timing premium for all batchers");
     info!("   - GET  /seven_day_stats - Last 7-day snapshot series for all batchers");
+    info!(
+        "   - GET  /all_daily_eip_7918_extra_cost - Daily extra blob cost under EIP-7918 for all batchers"
+    );
+    info!(
+        "   - GET  /all_daily_byte_profile - Daily byte and token profile of the posted data for all batchers"
+    );
 
     // run both services concurrently
     tokio::select! {
@@ -167,18 +281,33 @@ async fn main() -> eyre::Result<()> {
 #[cfg(test)]
 mod tests {
     use alloy_chains::NamedChain;
-    use axum::extract::{Query, State};
+    use alloy_consensus::{
+        SignableTransaction, TxEip1559, TxEip4844, TxEip4844Variant, TxEip4844WithSidecar,
+        TxEnvelope,
+    };
+    use alloy_eips::{
+        eip2718::Encodable2718,
+        eip4844::{Blob, BlobTransactionSidecar, Bytes48},
+    };
+    use alloy_primitives::{Address, B256, Bytes, Signature, TxKind};
+    use axum::{
+        Json,
+        extract::{Query, State},
+    };
     use pectralizer::{
+        da::DaPriceTable,
+        fork::Fork,
         provider::ProviderState,
         server::{
             AppState,
             handlers::{
-                blob_data_gas_handler, contract_handler, daily_txs_handler, eth_saved_handler,
-                pectra_data_gas_handler, tx_handler,
+                blob_data_gas_handler, contract_handler, daily_txs_handler, estimate_handler,
+                eth_saved_handler, pectra_data_gas_handler, raw_tx_handler, tx_handler,
             },
             types::{
-                ContractQuery, DailyTxsQuery, EthSavedQuery, GasUsageQuery, TxAnalysisResponse,
-                TxHashQuery,
+                CalldataPricingQuery, ContractQuery, CurrencyQuery, DailyTxsQuery, EstimateRequest,
+                EstimateResponse, EthSavedQuery, GasBreakdown, GasUsageQuery, ModeRegret,
+                PostingMode, RawTxAnalysisRequest, RawTxContext, TxAnalysisResponse, TxHashQuery,
             },
         },
         tracker::database::{Database, SqliteDatabase, TrackedBatch},
@@ -244,19 +373,86 @@ mod tests {
         let query = TxHashQuery {
             tx_hash: "0xd367c556c43058a3718362a0b2e624471c69e7f00846fe4474469a9895310bbd"
                 .to_string(),
+            blob_encoding: None,
+            compression: None,
+            timing_window: None,
         };
-        let response = tx_handler(State(app_state), Query(query)).await.unwrap();
+        let response = tx_handler(
+            State(app_state),
+            Query(query),
+            Query(CalldataPricingQuery::default()),
+            Query(CurrencyQuery::default()),
+        )
+        .await
+        .unwrap();
+        // the calldata has 1343 tokens
+        let TxAnalysisResponse {
+            block_number,
+            zero_bytes,
+            non_zero_bytes,
+            calldata,
+            fees,
+            ..
+        } = response.0;
+        assert_eq!(
+            fees.base_fee_wei_burned + fees.priority_fee_wei_paid,
+            74557 * 1014646161
+        );
+        assert_eq!(zero_bytes + 4 * non_zero_bytes, 1343);
+        assert_eq!(calldata.tokens, 1343);
+        assert_eq!(calldata.standard_token_gas, 5372);
+        assert_eq!(calldata.floor_token_gas, 13430);
+        // a blob of non-zero bytes costs 38.75 times more as calldata gas than as blob gas
+        let break_even_blob_base_fee = Some(fees.base_fee_per_gas as u128 * 155 / 4);
         let expected_response = TxAnalysisResponse {
             timestamp: 1746290387,
+            block_number,
+            success: true,
             gas_used: 74557,
             gas_price: 1014646161,
             blob_gas_price: Some(441344044),
             blob_gas_used: 0,
             eip_7623_calldata_gas: 13430,
             legacy_calldata_gas: 5372,
+            zero_bytes,
+            non_zero_bytes,
+            calldata,
+            blob_payload: None,
+            fees,
+            gas_breakdown: GasBreakdown {
+                intrinsic_gas: 21000,
+                intrinsic_wei: 21000 * 1014646161,
+                calldata_gas: 5372,
+                calldata_wei: 5372 * 1014646161,
+                execution_gas: 48185,
+                execution_wei: 48185 * 1014646161,
+                blob_gas: 0,
+                blob_wei: 0,
+            },
+            regret: Some(ModeRegret {
+                chosen_mode: PostingMode::Calldata,
+                chosen_mode_wei: 13626697942230,
+                alternative_mode_wei: 57847846535168,
+                regret_wei: 13626697942230 - 57847846535168,
+            }),
+            break_even_blob_base_fee,
+            timing: None,
             blob_data_wei_spent: Some(57847846535168),
+            eip_7918_blob_gas_price: Some(441344044),
+            eip_7918_blob_data_wei_spent: Some(57847846535168),
             legacy_calldata_wei_spent: 5450679176892,
             eip_7623_calldata_wei_spent: 13626697942230,
+            alt_da: None,
+            standard_intrinsic_gas: 26372,
+            floor_gas: 34430,
+            execution_gas: 48185,
+            floor_applied: false,
+            blob_utilization: None,
+            l2_batch: None,
+            authorization: None,
+            hypothetical_calldata_cost: None,
+            compression: None,
+            fiat: None,
         };
         assert_eq!(response.0, expected_response);
     }
@@ -267,8 +463,17 @@ mod tests {
         let query = TxHashQuery {
             tx_hash: "0xf9b3708d3c8a07f7c26bbd336c2746977787b126fbc95e2df816a74d599957c4"
                 .to_string(),
+            blob_encoding: None,
+            compression: None,
+            timing_window: None,
         };
-        let response = tx_handler(State(app_state), Query(query)).await;
+        let response = tx_handler(
+            State(app_state),
+            Query(query),
+            Query(CalldataPricingQuery::default()),
+            Query(CurrencyQuery::default()),
+        )
+        .await;
 
         // Handle the case where blob data might not be available from the RPC endpoint
         match response {
@@ -296,8 +501,17 @@ mod tests {
         let query = TxHashQuery {
             tx_hash: "0x6516958cca067ee7de225b23f8034ce0a79aae16af176d566bf894e35722f34d"
                 .to_string(),
+            blob_encoding: None,
+            compression: None,
+            timing_window: None,
         };
-        let response = tx_handler(State(app_state), Query(query)).await;
+        let response = tx_handler(
+            State(app_state),
+            Query(query),
+            Query(CalldataPricingQuery::default()),
+            Query(CurrencyQuery::default()),
+        )
+        .await;
 
         // Handle the case where blob data might not be available from the RPC endpoint
         match response {
@@ -341,6 +555,376 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_estimate_handler() {
+        // no RPC call is made
+        let app_state = create_test_app_state().await;
+        // 2 zero bytes and 2 non-zero bytes, 10 tokens
+        let data = Bytes::from_static(&[0, 0, 1, 2]);
+        let request = EstimateRequest {
+            calldata: Some(data.clone()),
+            gas_price: Some(10),
+            blob_base_fee: Some(3),
+            fork: Some(Fork::Prague),
+            ..Default::default()
+        };
+        let response = estimate_handler(State(app_state.clone()), Json(request))
+            .await
+            .unwrap();
+        let expected_response = EstimateResponse {
+            fork: Fork::Prague,
+            data_size: 4,
+            zero_bytes: 2,
+            non_zero_bytes: 2,
+            legacy_calldata_gas: 40,
+            eip_7623_calldata_gas: 100,
+            calldata_tx_gas: 21100,
+            legacy_calldata_wei_spent: Some(400),
+            eip_7623_calldata_wei_spent: Some(1000),
+            calldata_tx_wei_spent: Some(211000),
+            blob_count: 1,
+            blob_gas_used: 131072,
+            blob_gas_price: Some(3),
+            blob_data_wei_spent: Some(393216),
+            blob_tx_wei_spent: Some(210000 + 393216),
+        };
+        assert_eq!(response.0, expected_response);
+
+        // before prague the floor doesn't apply, and the blob base fee is derived
+        let request = EstimateRequest {
+            blob_payload: Some(data.clone()),
+            excess_blob_gas: Some(0),
+            fork: Some(Fork::Cancun),
+            ..Default::default()
+        };
+        let response = estimate_handler(State(app_state.clone()), Json(request))
+            .await
+            .unwrap();
+        assert_eq!(response.0.calldata_tx_gas, 21040);
+        assert_eq!(response.0.calldata_tx_wei_spent, None);
+        assert_eq!(response.0.blob_data_wei_spent, Some(131072));
+        assert_eq!(response.0.blob_tx_wei_spent, None);
+
+        let request = EstimateRequest {
+            calldata: Some(data.clone()),
+            blob_payload: Some(data),
+            ..Default::default()
+        };
+        assert!(
+            estimate_handler(State(app_state), Json(request))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_raw_tx_handler() {
+        // no RPC call is made
+        let app_state = create_test_app_state().await;
+        let context = RawTxContext {
+            base_fee_per_gas: Some(10),
+            blob_base_fee: Some(3),
+            fork: Some(Fork::Prague),
+            ..Default::default()
+        };
+        // 2 zero bytes and 2 non-zero bytes, 10 tokens
+        let tx = TxEip1559 {
+            chain_id: 1,
+            gas_limit: 30_000,
+            max_fee_per_gas: 20,
+            max_priority_fee_per_gas: 2,
+            to: TxKind::Call(Address::ZERO),
+            input: Bytes::from_static(&[0, 0, 1, 2]),
+            ..Default::default()
+        };
+        let raw_tx = TxEnvelope::from(tx.into_signed(Signature::test_signature()));
+        let request = RawTxAnalysisRequest {
+            raw_tx: raw_tx.encoded_2718().into(),
+            context,
+            blob_encoding: None,
+            compression: None,
+        };
+        let response = raw_tx_handler(
+            State(app_state.clone()),
+            Query(CalldataPricingQuery::default()),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert!(response.0.success);
+        // the gas used defaults to the EIP-7623 floor
+        assert_eq!(response.0.gas_used, 21100);
+        assert!(response.0.floor_applied);
+        assert_eq!(response.0.gas_price, 12);
+        assert_eq!(response.0.legacy_calldata_gas, 40);
+        assert_eq!(response.0.eip_7623_calldata_gas, 100);
+        assert_eq!(response.0.calldata.length, 4);
+        assert_eq!(response.0.calldata.tokens, 10);
+        assert_eq!(response.0.calldata.floor_token_gas, 100);
+        assert_eq!(response.0.blob_payload, None);
+        assert_eq!(response.0.fees.base_fee_wei_burned, 211000);
+        assert_eq!(response.0.fees.priority_fee_per_gas, 2);
+        assert_eq!(response.0.fees.priority_fee_wei_paid, 42200);
+        assert_eq!(response.0.fees.blob_fee_wei_burned, 0);
+        assert_eq!(response.0.fees.max_priority_fee_per_gas, Some(2));
+        assert_eq!(response.0.fees.max_fee_per_blob_gas, None);
+        // the floor sets the gas used
+        assert_eq!(
+            response.0.gas_breakdown,
+            GasBreakdown {
+                intrinsic_gas: 21000,
+                intrinsic_wei: 252000,
+                calldata_gas: 100,
+                calldata_wei: 1200,
+                execution_gas: 0,
+                execution_wei: 0,
+                blob_gas: 0,
+                blob_wei: 0,
+            }
+        );
+        // 100 calldata gas at 12 wei against a blob at 3 wei per blob gas
+        assert_eq!(response.0.regret.unwrap().regret_wei, 1200 - 393216);
+        assert_eq!(response.0.blob_gas_price, Some(3));
+        assert_eq!(response.0.blob_data_wei_spent, Some(393216));
+        // no price table is configured
+        assert_eq!(response.0.alt_da, None);
+
+        // before prague there is no floor, whatever the gas used
+        let request = RawTxAnalysisRequest {
+            raw_tx: raw_tx.encoded_2718().into(),
+            context: RawTxContext {
+                base_fee_per_gas: Some(10),
+                blob_base_fee: Some(3),
+                fork: Some(Fork::Cancun),
+                ..Default::default()
+            },
+            blob_encoding: None,
+            compression: None,
+        };
+        let response = raw_tx_handler(
+            State(app_state.clone()),
+            Query(CalldataPricingQuery::default()),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.0.gas_used, 21040);
+        assert!(!response.0.floor_applied);
+        assert_eq!(response.0.gas_breakdown.calldata_gas, 40);
+
+        // a blob transaction with its sidecar, posting calldata too
+        let blob_tx = TxEip4844 {
+            chain_id: 1,
+            gas_limit: 100_000,
+            max_fee_per_gas: 20,
+            max_priority_fee_per_gas: 2,
+            max_fee_per_blob_gas: 5,
+            blob_versioned_hashes: vec![B256::repeat_byte(1)],
+            input: Bytes::from_static(&[0, 0, 1, 2]),
+            ..Default::default()
+        };
+        // the blob holds a payload of two non-zero bytes
+        let mut blob = Blob::ZERO;
+        blob[1] = 1;
+        blob[2] = 2;
+        let sidecar = BlobTransactionSidecar {
+            blobs: vec![blob],
+            commitments: vec![Bytes48::ZERO],
+            proofs: vec![Bytes48::ZERO],
+        };
+        // the payload of the blobs is priced on the alternative DA layers
+        let da_price_table = DaPriceTable::parse(
+            r#"{"layers": [{"name": "per_byte", "unit": "byte", "rates": [{"from_timestamp": 0, "price_wei": 2}]}]}"#,
+        )
+        .unwrap();
+        let priced_app_state = AppState {
+            provider_state: app_state
+                .provider_state
+                .clone()
+                .with_da_price_table(da_price_table),
+            db: app_state.db.clone(),
+        };
+        let raw_tx = TxEnvelope::from(
+            TxEip4844Variant::TxEip4844WithSidecar(TxEip4844WithSidecar {
+                tx: blob_tx.clone(),
+                sidecar,
+            })
+            .into_signed(Signature::test_signature()),
+        );
+        let request = RawTxAnalysisRequest {
+            raw_tx: raw_tx.encoded_2718().into(),
+            context: RawTxContext {
+                gas_used: Some(60_000),
+                excess_blob_gas: Some(0),
+                fork: Some(Fork::Prague),
+                ..Default::default()
+            },
+            blob_encoding: None,
+            compression: None,
+        };
+        let response = raw_tx_handler(
+            State(priced_app_state),
+            Query(CalldataPricingQuery::default()),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.0.gas_used, 60_000);
+        // without base fee the gas price is the max fee
+        assert_eq!(response.0.gas_price, 20);
+        assert_eq!(response.0.blob_gas_used, 131072);
+        assert_eq!(response.0.blob_gas_price, Some(1));
+        assert_eq!(response.0.blob_data_wei_spent, Some(131072));
+        assert!(response.0.blob_utilization.is_some());
+        assert_eq!(response.0.calldata.length, 4);
+        assert_eq!(response.0.blob_payload.as_ref().unwrap().length, 2);
+        // the calldata alternative prices the payload, not the padded blob
+        assert_eq!(response.0.zero_bytes, 0);
+        assert_eq!(response.0.non_zero_bytes, 2);
+        assert_eq!(response.0.eip_7623_calldata_gas, 80);
+        assert_eq!(response.0.fees.base_fee_wei_burned, 0);
+        assert_eq!(response.0.fees.blob_fee_wei_burned, 131072);
+        assert_eq!(response.0.fees.max_fee_per_blob_gas, Some(5));
+        assert_eq!(response.0.fees.blob_fee_overbid_per_gas, Some(4));
+        assert_eq!(
+            response.0.gas_breakdown,
+            GasBreakdown {
+                intrinsic_gas: 21000,
+                intrinsic_wei: 420000,
+                calldata_gas: 40,
+                calldata_wei: 800,
+                execution_gas: 38960,
+                execution_wei: 779200,
+                blob_gas: 131072,
+                blob_wei: 131072,
+            }
+        );
+        assert_eq!(
+            response.0.regret,
+            Some(ModeRegret {
+                chosen_mode: PostingMode::Blob,
+                chosen_mode_wei: 131072,
+                alternative_mode_wei: 80 * 20,
+                regret_wei: 131072 - 80 * 20,
+            })
+        );
+        let alt_da = response.0.alt_da.unwrap();
+        assert_eq!(alt_da.payload_size, response.0.blob_payload.unwrap().length);
+        assert_eq!(alt_da.costs[0].wei_spent, 2 * alt_da.payload_size as u128);
+
+        // the blobs are required
+        let raw_tx = TxEnvelope::from(
+            TxEip4844Variant::TxEip4844(blob_tx).into_signed(Signature::test_signature()),
+        );
+        let request = RawTxAnalysisRequest {
+            raw_tx: raw_tx.encoded_2718().into(),
+            context: RawTxContext::default(),
+            blob_encoding: None,
+            compression: None,
+        };
+        assert!(
+            raw_tx_handler(
+                State(app_state.clone()),
+                Query(CalldataPricingQuery::default()),
+                Json(request),
+            )
+            .await
+            .is_err()
+        );
+
+        let request = RawTxAnalysisRequest {
+            raw_tx: Bytes::from_static(&[2, 1, 2, 3]),
+            context: RawTxContext::default(),
+            blob_encoding: None,
+            compression: None,
+        };
+        assert!(
+            raw_tx_handler(
+                State(app_state),
+                Query(CalldataPricingQuery::default()),
+                Json(request),
+            )
+            .await
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_raw_tx_handler_mirrored_regret() {
+        let app_state = create_test_app_state().await;
+        let payload = [1, 2, 3];
+        // the same data posted in a blob and as calldata
+        let mut blob = Blob::ZERO;
+        blob[1..4].copy_from_slice(&payload);
+        let blob_tx = TxEnvelope::from(
+            TxEip4844Variant::TxEip4844WithSidecar(TxEip4844WithSidecar {
+                tx: TxEip4844 {
+                    chain_id: 1,
+                    gas_limit: 100_000,
+                    max_fee_per_gas: 20,
+                    max_priority_fee_per_gas: 2,
+                    max_fee_per_blob_gas: 5,
+                    blob_versioned_hashes: vec![B256::repeat_byte(1)],
+                    ..Default::default()
+                },
+                sidecar: BlobTransactionSidecar {
+                    blobs: vec![blob],
+                    commitments: vec![Bytes48::ZERO],
+                    proofs: vec![Bytes48::ZERO],
+                },
+            })
+            .into_signed(Signature::test_signature()),
+        );
+        let calldata_tx = TxEnvelope::from(
+            TxEip1559 {
+                chain_id: 1,
+                gas_limit: 30_000,
+                max_fee_per_gas: 20,
+                max_priority_fee_per_gas: 2,
+                to: TxKind::Call(Address::ZERO),
+                input: Bytes::copy_from_slice(&payload),
+                ..Default::default()
+            }
+            .into_signed(Signature::test_signature()),
+        );
+        let mut regrets = Vec::new();
+        for raw_tx in [blob_tx, calldata_tx] {
+            let request = RawTxAnalysisRequest {
+                raw_tx: raw_tx.encoded_2718().into(),
+                context: RawTxContext {
+                    base_fee_per_gas: Some(10),
+                    blob_base_fee: Some(3),
+                    fork: Some(Fork::Prague),
+                    ..Default::default()
+                },
+                blob_encoding: None,
+                compression: None,
+            };
+            let response = raw_tx_handler(
+                State(app_state.clone()),
+                Query(CalldataPricingQuery::default()),
+                Json(request),
+            )
+            .await
+            .unwrap();
+            regrets.push(response.0.regret.unwrap());
+        }
+        // one blob at 3 wei against 12 non-zero byte tokens at the floor and 12 wei
+        assert_eq!(
+            regrets[0],
+            ModeRegret {
+                chosen_mode: PostingMode::Blob,
+                chosen_mode_wei: 131072 * 3,
+                alternative_mode_wei: 120 * 12,
+                regret_wei: 131072 * 3 - 120 * 12,
+            }
+        );
+        assert_eq!(regrets[1].chosen_mode, PostingMode::Calldata);
+        assert_eq!(regrets[1].chosen_mode_wei, regrets[0].alternative_mode_wei);
+        assert_eq!(regrets[1].alternative_mode_wei, regrets[0].chosen_mode_wei);
+        assert_eq!(regrets[1].regret_wei, -regrets[0].regret_wei);
+    }
+
     // Database functionality tests
 
     #[tokio::test]
@@ -368,13 +952,33 @@ mod tests {
             start_timestamp: 1000000000,
             end_timestamp: 2000000000,
         };
-        let response = eth_saved_handler(State(app_state), Query(query))
-            .await
-            .unwrap();
+        let response = eth_saved_handler(
+            State(app_state.clone()),
+            Query(query),
+            Query(CalldataPricingQuery::default()),
+        )
+        .await
+        .unwrap();
 
         // Since we have an empty database, expect 0 ETH saved
         assert_eq!(response.0.batcher_address, "0x456def");
         assert_eq!(response.0.total_eth_saved_wei, 0);
+
+        // the per-batcher ETH saved is not repriced
+        let query = EthSavedQuery {
+            batcher_address: "0x456def".to_string(),
+            start_timestamp: 1000000000,
+            end_timestamp: 2000000000,
+        };
+        let pricing_query = CalldataPricingQuery {
+            calldata_pricing: Some("eip7976".to_string()),
+            ..Default::default()
+        };
+        assert!(
+            eth_saved_handler(State(app_state), Query(query), Query(pricing_query))
+                .await
+                .is_err()
+        );
     }
 
     #[tokio::test]
@@ -401,9 +1005,13 @@ mod tests {
             start_timestamp: 1000000000,
             end_timestamp: 2000000000,
         };
-        let response = pectra_data_gas_handler(State(app_state), Query(query))
-            .await
-            .unwrap();
+        let response = pectra_data_gas_handler(
+            State(app_state),
+            Query(query),
+            Query(CalldataPricingQuery::default()),
+        )
+        .await
+        .unwrap();
 
         // Since we have an empty database, expect 0 gas
         assert_eq!(response.0.total_pectra_data_gas, 0);
@@ -421,6 +1029,9 @@ mod tests {
             analysis_result: r#"{"blob_gas_used": 131072, "eip_7623_calldata_gas": 1000, "blob_data_wei_spent": 1000000, "eip_7623_calldata_wei_spent": 2000000, "timestamp": 1600000000}"#.to_string(),
             timestamp: 1600000000,
             last_analyzed_block: None,
+            l2_tx_count: None,
+            sequence_number: None,
+            status: None,
         };
 
         app_state.db.save_tracked_batch(&batch).await.unwrap();
@@ -452,9 +1063,13 @@ mod tests {
             start_timestamp: 1500000000,
             end_timestamp: 1700000000,
         };
-        let response = eth_saved_handler(State(app_state.clone()), Query(query))
-            .await
-            .unwrap();
+        let response = eth_saved_handler(
+            State(app_state.clone()),
+            Query(query),
+            Query(CalldataPricingQuery::default()),
+        )
+        .await
+        .unwrap();
 
         assert_eq!(response.0.batcher_address, "0xbatcher123");
         assert_eq!(response.0.total_eth_saved_wei, 1000000); // 2000000 - 1000000 = 1000000
@@ -477,9 +1092,13 @@ mod tests {
             start_timestamp: 1500000000,
             end_timestamp: 1700000000,
         };
-        let response = pectra_data_gas_handler(State(app_state), Query(query))
-            .await
-            .unwrap();
+        let response = pectra_data_gas_handler(
+            State(app_state),
+            Query(query),
+            Query(CalldataPricingQuery::default()),
+        )
+        .await
+        .unwrap();
 
         assert_eq!(response.0.total_pectra_data_gas, 1000);
     }
@@ -496,6 +1115,9 @@ mod tests {
             analysis_result: r#"{"blob_gas_used": 100000, "eip_7623_calldata_gas": 500, "blob_data_wei_spent": 500000, "eip_7623_calldata_wei_spent": 1000000, "timestamp": 1600000000}"#.to_string(),
             timestamp: 1600000000,
             last_analyzed_block: None,
+            l2_tx_count: None,
+            sequence_number: None,
+            status: None,
         };
 
         let batch2 = TrackedBatch {
@@ -505,6 +1127,9 @@ mod tests {
             analysis_result: r#"{"blob_gas_used": 200000, "eip_7623_calldata_gas": 1000, "blob_data_wei_spent": 800000, "eip_7623_calldata_wei_spent": 1500000, "timestamp": 1600000000}"#.to_string(),
             timestamp: 1600000000,
             last_analyzed_block: None,
+            l2_tx_count: None,
+            sequence_number: None,
+            status: None,
         };
 
         app_state.db.save_tracked_batch(&batch1).await.unwrap();
@@ -542,9 +1167,13 @@ mod tests {
             start_timestamp: 1500000000,
             end_timestamp: 1700000000,
         };
-        let eth_response1 = eth_saved_handler(State(app_state.clone()), Query(eth_query1))
-            .await
-            .unwrap();
+        let eth_response1 = eth_saved_handler(
+            State(app_state.clone()),
+            Query(eth_query1),
+            Query(CalldataPricingQuery::default()),
+        )
+        .await
+        .unwrap();
 
         assert_eq!(eth_response1.0.total_eth_saved_wei, 500000); // 1000000 - 500000
 
@@ -553,9 +1182,13 @@ mod tests {
             start_timestamp: 1500000000,
             end_timestamp: 1700000000,
         };
-        let eth_response2 = eth_saved_handler(State(app_state), Query(eth_query2))
-            .await
-            .unwrap();
+        let eth_response2 = eth_saved_handler(
+            State(app_state),
+            Query(eth_query2),
+            Query(CalldataPricingQuery::default()),
+        )
+        .await
+        .unwrap();
 
         assert_eq!(eth_response2.0.total_eth_saved_wei, 700000); // 1500000 - 800000
     }
@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+
+pub mod chainlink;
+pub mod csv;
+
+/// The number of wei in one ETH.
+pub const WEI_PER_ETH: f64 = 1e18;
+
+/// A fiat currency wei values can be converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Currency {
+    Usd,
+}
+
+/// The point in time of a price: the block of a transaction and its timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PricePoint {
+    /// The block number.
+    ///
+    /// None if unknown, e.g. for batches tracked before the block number was recorded.
+    pub block_number: Option<u64>,
+    /// The block timestamp.
+    pub timestamp: u64,
+}
+
+/// A source of historical ETH prices.
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// It returns the price of one ETH in the given currency at the given point.
+    ///
+    /// None if the source has no price for that point.
+    async fn eth_price(&self, currency: Currency, at: PricePoint) -> Result<Option<f64>>;
+}
+
+/// A price source with the currency to convert wei to.
+#[derive(Clone, Copy)]
+pub struct FiatConversion<'a> {
+    /// The source of the ETH prices.
+    pub price_source: &'a dyn PriceSource,
+    /// The fiat currency.
+    pub currency: Currency,
+}
+
+impl FiatConversion<'_> {
+    /// It returns the price of one ETH in the currency at the given point.
+    pub async fn eth_price(&self, at: PricePoint) -> Result<Option<f64>> {
+        self.price_source.eth_price(self.currency, at).await
+    }
+}
+
+/// It converts wei to a fiat value at the given ETH price.
+pub fn wei_to_fiat(wei: u128, eth_price: f64) -> f64 {
+    wei as f64 / WEI_PER_ETH * eth_price
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wei_to_fiat() {
+        assert_eq!(wei_to_fiat(0, 2500.0), 0.0);
+        assert_eq!(wei_to_fiat(1_000_000_000_000_000_000, 2500.0), 2500.0);
+        assert_eq!(wei_to_fiat(2_000_000_000_000_000, 2500.0), 5.0);
+    }
+}
@@ -0,0 +1,101 @@
+use super::{Currency, PricePoint, PriceSource};
+use alloy_eips::BlockId;
+use alloy_primitives::{Address, Bytes, I256, Selector, address, fixed_bytes};
+use alloy_provider::{
+    Provider, RootProvider,
+    network::{Ethereum, Network, TransactionBuilder},
+};
+use async_trait::async_trait;
+use eyre::Result;
+use std::sync::Arc;
+
+/// The selector of `latestRoundData()`.
+pub const LATEST_ROUND_DATA_SELECTOR: Selector = fixed_bytes!("0xfeaf968c");
+/// The decimals of the answer of the ETH/USD aggregators.
+pub const ETH_USD_DECIMALS: i32 = 8;
+/// The ETH/USD aggregator proxy on Ethereum mainnet.
+pub const MAINNET_ETH_USD_FEED: Address = address!("0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419");
+/// The ETH/USD aggregator proxy on Sepolia.
+pub const SEPOLIA_ETH_USD_FEED: Address = address!("0x694AA1769357215DE4FAC081bf1f309aDC325306");
+
+/// A price source reading a Chainlink ETH/USD aggregator with `eth_call` at the block of the
+/// price point.
+///
+/// Reading past blocks requires an archive node.
+pub struct ChainlinkPriceSource {
+    provider: Arc<RootProvider>,
+    feed: Address,
+}
+
+impl ChainlinkPriceSource {
+    /// Create a new price source reading the given aggregator.
+    pub fn new(provider: Arc<RootProvider>, feed: Address) -> Self {
+        Self { provider, feed }
+    }
+
+    /// The ETH/USD aggregator of the chain, if known.
+    pub fn eth_usd_feed(chain_id: u64) -> Option<Address> {
+        match chain_id {
+            1 => Some(MAINNET_ETH_USD_FEED),
+            11155111 => Some(SEPOLIA_ETH_USD_FEED),
+            _ => None,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceSource for ChainlinkPriceSource {
+    async fn eth_price(&self, currency: Currency, at: PricePoint) -> Result<Option<f64>> {
+        let Currency::Usd = currency;
+        // the round of a past block can only be read at that block
+        let Some(block_number) = at.block_number else {
+            return Ok(None);
+        };
+        let request = <Ethereum as Network>::TransactionRequest::default()
+            .with_to(self.feed)
+            .with_input(Bytes::copy_from_slice(
+                LATEST_ROUND_DATA_SELECTOR.as_slice(),
+            ));
+        let output = self
+            .provider
+            .call(request)
+            .block(BlockId::number(block_number))
+            .await
+            .map_err(|e| eyre::eyre!("Failed to read the Chainlink aggregator: {}", e))?;
+        Ok(decode_latest_round_answer(&output))
+    }
+}
+
+/// It decodes the answer of the output of `latestRoundData()` as a price.
+///
+/// None if the output is too short, e.g. before the aggregator was deployed, or the answer is not
+/// positive.
+pub fn decode_latest_round_answer(output: &[u8]) -> Option<f64> {
+    // (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)
+    let answer = I256::try_from_be_slice(output.get(32..64)?)?;
+    if !answer.is_positive() {
+        return None;
+    }
+    Some(answer.to_string().parse::<f64>().ok()? / 10f64.powi(ETH_USD_DECIMALS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    #[test]
+    fn test_decode_latest_round_answer() {
+        let mut output = vec![0u8; 160];
+        // 2500.12345678 USD
+        output[32..64].copy_from_slice(&U256::from(250_012_345_678u64).to_be_bytes::<32>());
+        assert_eq!(decode_latest_round_answer(&output), Some(2500.12345678));
+
+        // no aggregator at the block
+        assert_eq!(decode_latest_round_answer(&[]), None);
+
+        // negative answer
+        output[32..64].copy_from_slice(&I256::MINUS_ONE.to_be_bytes::<32>());
+        assert_eq!(decode_latest_round_answer(&output), None);
+    }
+}
@@ -0,0 +1,83 @@
+use super::{Currency, PricePoint, PriceSource};
+use async_trait::async_trait;
+use eyre::Result;
+
+/// A price source reading historical ETH/USD prices from a CSV of `timestamp,price` rows.
+///
+/// The price at a point is the one of the latest row at or before its timestamp.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvPriceSource {
+    /// The (timestamp, price) rows, sorted by timestamp.
+    prices: Vec<(u64, f64)>,
+}
+
+impl CsvPriceSource {
+    /// Parse the CSV content. A header row is allowed.
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut prices = Vec::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some((timestamp, price)) = line.split_once(',') else {
+                eyre::bail!("Invalid price row {}: {}", index + 1, line);
+            };
+            let (Ok(timestamp), Ok(price)) =
+                (timestamp.trim().parse::<u64>(), price.trim().parse::<f64>())
+            else {
+                // the header row
+                if index == 0 {
+                    continue;
+                }
+                eyre::bail!("Invalid price row {}: {}", index + 1, line);
+            };
+            prices.push((timestamp, price));
+        }
+        prices.sort_by_key(|(timestamp, _)| *timestamp);
+        Ok(Self { prices })
+    }
+
+    /// Load the CSV file.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    /// The price of the latest row at or before the timestamp.
+    pub fn price_at(&self, timestamp: u64) -> Option<f64> {
+        let index = self
+            .prices
+            .partition_point(|(row_timestamp, _)| *row_timestamp <= timestamp);
+        index.checked_sub(1).map(|index| self.prices[index].1)
+    }
+}
+
+#[async_trait]
+impl PriceSource for CsvPriceSource {
+    async fn eth_price(&self, currency: Currency, at: PricePoint) -> Result<Option<f64>> {
+        let Currency::Usd = currency;
+        Ok(self.price_at(at.timestamp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_price_source() {
+        let source = CsvPriceSource::parse(
+            "timestamp,price\n1746316800,1835.5\n1746230400,1810.25\n\n1746403200,1815\n",
+        )
+        .unwrap();
+        assert_eq!(source.price_at(1746230399), None);
+        assert_eq!(source.price_at(1746230400), Some(1810.25));
+        assert_eq!(source.price_at(1746290387), Some(1810.25));
+        assert_eq!(source.price_at(1746316800), Some(1835.5));
+        assert_eq!(source.price_at(1800000000), Some(1815.0));
+
+        assert!(CsvPriceSource::parse("1746230400,1810.25\n1746316800").is_err());
+        assert!(CsvPriceSource::parse("1746230400,1810.25\n1746316800,abc").is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::provider::blob::BlobProvider;
+use crate::{
+    da::DaPriceTable, fork::ForkSchedule, price::PriceSource, provider::blob::BlobProvider,
+    timing::DEFAULT_TIMING_WINDOW_BLOCKS,
+};
 use alloy_provider::RootProvider;
 use etherscan::EtherscanProvider;
 use std::sync::Arc;
@@ -15,6 +18,15 @@ pub struct ProviderState {
     pub blob_provider: Arc<BlobProvider>,
     /// The etherscan provider
     pub etherscan_provider: Arc<EtherscanProvider>,
+    /// The fork schedule of the chain
+    pub fork_schedule: Arc<ForkSchedule>,
+    /// The source of the ETH prices used to convert wei to fiat, if configured
+    pub price_source: Option<Arc<dyn PriceSource>>,
+    /// The number of blocks before and after the inclusion of a tracked batch whose fees are
+    /// compared with the ones paid
+    pub timing_window_blocks: u64,
+    /// The price table of the alternative data availability layers, if configured
+    pub da_price_table: Option<Arc<DaPriceTable>>,
 }
 
 impl ProviderState {
@@ -26,6 +38,34 @@ impl ProviderState {
             ethereum_provider: Arc::new(ethereum_provider),
             blob_provider: Arc::new(BlobProvider::new(chain_id)),
             etherscan_provider: Arc::new(etherscan_provider),
+            fork_schedule: Arc::new(ForkSchedule::for_chain(chain_id)),
+            price_source: None,
+            timing_window_blocks: DEFAULT_TIMING_WINDOW_BLOCKS,
+            da_price_table: None,
         }
     }
+
+    /// Replace the built-in fork schedule with a custom one, e.g. for devnets
+    pub fn with_fork_schedule(mut self, fork_schedule: ForkSchedule) -> Self {
+        self.fork_schedule = Arc::new(fork_schedule);
+        self
+    }
+
+    /// Set the source of the ETH prices used to convert wei to fiat
+    pub fn with_price_source(mut self, price_source: Arc<dyn PriceSource>) -> Self {
+        self.price_source = Some(price_source);
+        self
+    }
+
+    /// Set the timing window of the tracked batches
+    pub fn with_timing_window_blocks(mut self, timing_window_blocks: u64) -> Self {
+        self.timing_window_blocks = timing_window_blocks;
+        self
+    }
+
+    /// Set the price table of the alternative data availability layers
+    pub fn with_da_price_table(mut self, da_price_table: DaPriceTable) -> Self {
+        self.da_price_table = Some(Arc::new(da_price_table));
+        self
+    }
 }
@@ -1,33 +1,11 @@
 use alloy_chains::NamedChain;
-use alloy_primitives::TxHash;
+use alloy_primitives::{B256, Bytes, hex::FromHex};
 use reqwest::Client;
-use serde::de::Error;
-use serde::{Deserialize, Deserializer};
 use tracing::warn;
 
 /// The url of the blob provider, aka blobscan.
-const MAINNET_BLOB_PROVIDER_URL: &str = "https://api.blobscan.com/transactions/";
-const SEPOLIA_BLOB_PROVIDER_URL: &str = "https://api.sepolia.blobscan.com/transactions/";
-
-/// Custom deserializer to convert string to u64
-fn deserialize_string_to_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    s.parse::<u64>().map_err(D::Error::custom)
-}
-
-/// The data of the blob.
-#[derive(Debug, Deserialize)]
-pub struct BlobData {
-    /// The amount of gas used that would have been used to store the blob data as calldata.
-    #[serde(
-        rename = "blobAsCalldataGasUsed",
-        deserialize_with = "deserialize_string_to_u64"
-    )]
-    pub blob_as_calldata_gas_used: u64,
-}
+const MAINNET_BLOB_PROVIDER_URL: &str = "https://api.blobscan.com";
+const SEPOLIA_BLOB_PROVIDER_URL: &str = "https://api.sepolia.blobscan.com";
 
 /// The provider of the blobs.
 #[derive(Debug)]
@@ -58,11 +36,15 @@ impl BlobProvider {
         }
     }
 
-    /// Make a blob request to the provider providing the transaction hash.
-    pub async fn get_blob_data(&self, tx_hash: &TxHash) -> eyre::Result<BlobData> {
-        let url = format!("{}{}", self.endpoint, tx_hash);
-        let response = self.client.get(url).send().await?;
-        let blob_data: BlobData = response.json().await?;
-        Ok(blob_data)
+    /// Make a blob request to the provider providing the blob versioned hash.
+    ///
+    /// It returns the raw content of the blob.
+    pub async fn get_blob(&self, versioned_hash: &B256) -> eyre::Result<Bytes> {
+        let url = format!("{}/blobs/{}/data", self.endpoint, versioned_hash);
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        // the data is a hex string, possibly json encoded
+        let data = response.text().await?;
+        let blob = Bytes::from_hex(data.trim().trim_matches('"'))?;
+        Ok(blob)
     }
 }
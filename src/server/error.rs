@@ -23,6 +23,18 @@ pub enum HandlerError {
     InvalidContract(String),
     #[error("Database error: {0}")]
     DatabaseError(String),
+    #[error("Simulation error: {0}")]
+    SimulationError(String),
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+    #[error("Compression error: {0}")]
+    CompressionError(String),
+    #[error("Price source error: {0}")]
+    PriceSourceError(String),
+    #[error("Price not found: {0}")]
+    PriceNotFound(String),
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(String),
 }
 
 impl IntoResponse for HandlerError {
@@ -36,6 +48,12 @@ impl IntoResponse for HandlerError {
             HandlerError::BlockNotFound(_) => StatusCode::NOT_FOUND,
             HandlerError::InvalidContract(_) => StatusCode::BAD_REQUEST,
             HandlerError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            HandlerError::SimulationError(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            HandlerError::InvalidQuery(_) => StatusCode::BAD_REQUEST,
+            HandlerError::CompressionError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            HandlerError::PriceSourceError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            HandlerError::PriceNotFound(_) => StatusCode::NOT_FOUND,
+            HandlerError::InvalidTransaction(_) => StatusCode::BAD_REQUEST,
         };
 
         (status, self.to_string()).into_response()
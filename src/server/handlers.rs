@@ -1,26 +1,64 @@
 use super::{
     error::HandlerError,
     types::{
-        AggregatedQuery, AllBlobDataGasResponse, AllDailyTxsResponse, AllEthSavedResponse,
-        AllPectraDataGasResponse, BlobDataGasResponse, ContractAnalysisResponse, ContractQuery,
+        AggregatedQuery, AllBlobDataGasResponse, AllBlobUtilizationResponse,
+        AllCompressionResponse, AllDailyTxsResponse, AllEthSavedResponse, AllL2TxCostResponse,
+        AllPectraDataGasResponse, AuthorizationAnalysis, BlobDataGasResponse,
+        BlobFeeProjectionQuery, BlobFeeProjectionResponse, CalldataPricingQuery,
+        CompressedPayloadCost, CompressionAnalysis, ContractAnalysisResponse, ContractQuery,
         DailyTxsQuery, DailyTxsResponse, EthSavedQuery, EthSavedResponse, GasUsageQuery,
-        PectraDataGasResponse, TxAnalysisResponse, TxHashQuery,
+        HypotheticalCalldataCost, L2BatchSummary, PectraDataGasResponse, SimulationResponse,
+        TxAnalysisResponse, TxHashQuery,
     },
 };
 use crate::{
+    batch,
+    blob::{
+        BlobEncoding, blob_payload, blob_usage, blob_utilization,
+        fee::{
+            MAX_EXCESS_BLOB_GAS_FRACTIONS, blob_base_fee, break_even_blob_base_fee,
+            next_excess_blob_gas, project_blob_base_fee, reserve_blob_base_fee,
+        },
+        sharing::DEFAULT_SHARING_WINDOW_BLOCKS,
+    },
+    compression::Compression,
+    da::DaPriceTable,
+    fork::{CalldataPricing, Fork, ForkParams, ForkSchedule},
+    price::{FiatConversion, PricePoint, wei_to_fiat},
     provider::ProviderState,
-    server::types::{AllBatchersSevenDayStatsResponse, BatcherSevenDayStats},
+    server::types::{
+        AllAltDaCostResponse, AllBatchersSevenDayStatsResponse, AllDailyByteProfileResponse,
+        AllDailyEip7918ExtraCostResponse, AllDailyRegretResponse, AllDailyTimingResponse,
+        AllFeeBreakdownResponse, AllTimingEfficiencyResponse, AllWastedSpendResponse,
+        AllWrongModeBatchesResponse, BatcherDailyByteProfile, BatcherDailyEip7918ExtraCost,
+        BatcherDailyRegret, BatcherDailyTiming, BatcherSevenDayStats, BlobSharingQuery,
+        BlobSharingSimulation, BreakEvenFeesResponse, CurrencyQuery, DailyBatcherStats,
+        DataBreakdown, EstimateRequest, EstimateResponse, FeeBreakdown, FiatCost, GasBreakdown,
+        ModeRegret, PostingMode, RawTxAnalysisRequest, RawTxContext,
+    },
+    simulation::{self, database::RpcDatabase},
+    timing::{BlockFees, MAX_TIMING_WINDOW_BLOCKS, PaidGas, timing_efficiency},
     utils::{
-        BASE_STIPEND, BYTES_PER_BLOB, ISTANBUL_BLOCK_NUMBER, compute_calldata_gas,
-        compute_legacy_calldata_gas,
+        AuthorityAccount, BASE_STIPEND, CalldataBytes, IntrinsicGas, compute_authorization_gas,
+        compute_calldata_gas, compute_intrinsic_gas, compute_legacy_calldata_gas,
+        count_calldata_bytes, count_calldata_tokens,
     },
 };
-use alloy_consensus::{Transaction, Typed2718};
-use alloy_primitives::{Address, FixedBytes, hex::FromHex};
+use alloy_consensus::{Transaction, TxEip4844Variant, TxEnvelope, Typed2718};
+use alloy_eips::{BlockId, BlockNumberOrTag, eip2718::Decodable2718, eip7702::SignedAuthorization};
+use alloy_primitives::{Address, Bytes, FixedBytes, hex::FromHex};
 use alloy_provider::Provider;
 use axum::{Json, extract::Query, extract::State};
+use revm::{database_interface::async_db::WrapDatabaseAsync, primitives::eip4844::GAS_PER_BLOB};
 use rustc_hash::FxHashSet;
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use tracing::warn;
+
+/// The maximum number of blocks in a blob fee projection, about one day.
+const MAX_PROJECTED_BLOCKS: u64 = 7_200;
 
 pub async fn root_handler() -> &'static str {
     concat!(
@@ -30,9 +68,51 @@ pub async fn root_handler() -> &'static str {
     )
 }
 
+/// The options of a transaction analysis.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisOptions {
+    /// The encoding used to count the blobs a calldata transaction would need.
+    ///
+    /// If None, the encoding of the batcher is guessed from the recipient.
+    pub blob_encoding: Option<BlobEncoding>,
+    /// The hypothetical calldata pricing to report next to the real one.
+    pub calldata_pricing: Option<CalldataPricing>,
+    /// The compressions to evaluate on the payload.
+    pub compressions: Vec<Compression>,
+    /// The number of blocks before and after the inclusion block whose fees are compared with
+    /// the ones paid.
+    pub timing_window: Option<u64>,
+    /// The price table of the alternative data availability layers to price the payload on.
+    pub da_price_table: Option<Arc<DaPriceTable>>,
+}
+
+/// The block and fee data a transaction analysis depends on.
+#[derive(Debug, Clone, Copy)]
+pub struct TxContext {
+    /// The chain id, used to validate the authorizations.
+    pub chain_id: u64,
+    /// The pricing parameters in effect at the block.
+    pub fork_params: ForkParams,
+    /// The block number.
+    pub block_number: u64,
+    /// The block timestamp.
+    pub timestamp: u64,
+    /// Whether the transaction succeeded.
+    pub success: bool,
+    /// The base fee of the block.
+    pub base_fee_per_gas: Option<u64>,
+    /// The gas used by the transaction.
+    pub gas_used: u64,
+    /// The effective gas price of the transaction.
+    pub gas_price: u128,
+    /// The blob base fee of the block, None before cancun.
+    pub blob_gas_price: Option<u128>,
+}
+
 pub async fn analyze_transaction(
     provider_state: &ProviderState,
     tx_hash_bytes: FixedBytes<32>,
+    options: AnalysisOptions,
 ) -> Result<TxAnalysisResponse, HandlerError> {
     // get tx
     let Some(tx) = provider_state
@@ -56,9 +136,6 @@ pub async fn analyze_transaction(
     else {
         return Err(HandlerError::ReceiptNotFound(tx_hash_bytes.to_string()));
     };
-    // get total gas used
-    let gas_used = receipt.gas_used;
-    let gas_price = receipt.effective_gas_price;
     let Some(block_hash) = receipt.block_hash else {
         return Err(HandlerError::BlockNotFound(tx_hash_bytes.to_string()));
     };
@@ -70,90 +147,1096 @@ pub async fn analyze_transaction(
     else {
         return Err(HandlerError::BlockNotFound(tx_hash_bytes.to_string()));
     };
-    let timestamp = block.header.timestamp;
-    if tx.is_eip4844() {
-        let blob_gas_used = tx.blob_gas_used().unwrap(); // safe unwrap as it's an eip4844 tx
-        let blob_gas_price = receipt.blob_gas_price.unwrap(); // safe unwrap as it's an eip4844 tx
-        // get blob data from blobscan
-        let total_legacy_calldata_gas;
-        let total_eip_7623_calldata_gas;
-        let blob_data = provider_state
+    let fork_params = provider_state
+        .fork_schedule
+        .params_at(block.header.number, block.header.timestamp);
+    let blob_gas_price = if tx.is_eip4844() {
+        receipt.blob_gas_price
+    } else {
+        // the update fraction of the blob base fee depends on the fork
+        block
+            .header
+            .excess_blob_gas
+            .zip(fork_params.blob)
+            .map(|(excess_blob_gas, blob_params)| blob_base_fee(excess_blob_gas, &blob_params))
+    };
+    let context = TxContext {
+        chain_id: provider_state.fork_schedule.chain_id,
+        fork_params,
+        block_number: block.header.number,
+        timestamp: block.header.timestamp,
+        success: receipt.status(),
+        base_fee_per_gas: block.header.base_fee_per_gas,
+        gas_used: receipt.gas_used,
+        gas_price: receipt.effective_gas_price,
+        blob_gas_price,
+    };
+    // get the raw blobs content from blobscan
+    let versioned_hashes = tx.blob_versioned_hashes().unwrap_or_default();
+    let mut blobs = Vec::with_capacity(versioned_hashes.len());
+    for versioned_hash in versioned_hashes {
+        let blob = provider_state
             .blob_provider
-            .get_blob_data(&tx_hash_bytes)
+            .get_blob(versioned_hash)
             .await
             .map_err(|e| HandlerError::ProviderError(format!("Failed to get blob data: {}", e)))?;
-        if block.header.number < ISTANBUL_BLOCK_NUMBER {
-            // pre Pectra, so pre EIP-7623
-            total_legacy_calldata_gas = blob_data.blob_as_calldata_gas_used;
-            total_eip_7623_calldata_gas =
-                total_legacy_calldata_gas + (total_legacy_calldata_gas as f64 * 0.6) as u64;
-        } else {
-            // post Pectra, so post EIP-7623
-            total_eip_7623_calldata_gas = blob_data.blob_as_calldata_gas_used;
-            total_legacy_calldata_gas =
-                total_eip_7623_calldata_gas - (total_eip_7623_calldata_gas as f64 * 0.6) as u64;
+        blobs.push(blob);
+    }
+    // get the authority accounts before the transaction
+    let authority_accounts = match tx.authorization_list() {
+        Some(authorization_list) => {
+            get_authority_accounts(
+                provider_state,
+                authorization_list,
+                block.header.number.saturating_sub(1),
+            )
+            .await?
         }
+        None => HashMap::new(),
+    };
+    let timing_window = options.timing_window;
+    let mut tx_analysis = analyze_transaction_data(
+        &tx,
+        tx.inner.signer(),
+        &blobs,
+        &authority_accounts,
+        &context,
+        options,
+    )?;
+    // compare the fees paid with the ones of the blocks around the inclusion block
+    if let Some(window_blocks) = timing_window {
+        let blocks = get_window_fees(provider_state, block.header.number, window_blocks).await?;
+        let fees = &tx_analysis.fees;
+        let paid = PaidGas {
+            block_number: block.header.number,
+            gas_used: context.gas_used,
+            priority_fee_per_gas: fees.priority_fee_per_gas,
+            blob_gas_used: tx.blob_gas_used().unwrap_or_default(),
+            actual_wei: fees.base_fee_wei_burned
+                + fees.priority_fee_wei_paid
+                + fees.blob_fee_wei_burned,
+        };
+        tx_analysis.timing = timing_efficiency(&paid, window_blocks, &blocks);
+    }
+    Ok(tx_analysis)
+}
+
+/// Analyze a transaction from its blobs, the accounts of its authorities before it and the
+/// context of its block.
+///
+/// The blobs are in the order of the versioned hashes of the transaction.
+pub fn analyze_transaction_data<T: Transaction>(
+    tx: &T,
+    sender: Address,
+    blobs: &[Bytes],
+    authority_accounts: &HashMap<Address, AuthorityAccount>,
+    context: &TxContext,
+    options: AnalysisOptions,
+) -> Result<TxAnalysisResponse, HandlerError> {
+    let fork_params = context.fork_params;
+    let gas_used = context.gas_used;
+    let gas_price = context.gas_price;
+    let timestamp = context.timestamp;
+    // compute standard intrinsic gas and EIP-7623 floor
+    let intrinsic_gas = compute_intrinsic_gas(tx, &fork_params);
+    let execution_gas = gas_used.saturating_sub(intrinsic_gas.standard);
+    let floor_applied = fork_params.is_eip_7623_enabled() && intrinsic_gas.floor >= gas_used;
+    let blob_encoding = options
+        .blob_encoding
+        .unwrap_or_else(|| BlobEncoding::for_inbox(tx.to()));
+    // the EIP-7918 reserve price ties the blob base fee to the execution base fee
+    let base_fee_per_gas = context.base_fee_per_gas.unwrap_or_default();
+    let fees = fee_breakdown(tx, context);
+    let calldata_breakdown = data_breakdown(tx.input(), &fork_params);
+    if tx.is_eip4844() {
+        let blob_gas_used = tx.blob_gas_used().unwrap(); // safe unwrap as it's an eip4844 tx
+        let Some(blob_gas_price) = context.blob_gas_price else {
+            return Err(HandlerError::InvalidQuery(
+                "the blob base fee of a blob transaction is required".to_string(),
+            ));
+        };
+        let versioned_hashes = tx.blob_versioned_hashes().unwrap_or_default();
+        if blobs.len() != versioned_hashes.len() {
+            return Err(HandlerError::BlobDataNotFound(format!(
+                "{} blobs for {} versioned hashes",
+                blobs.len(),
+                versioned_hashes.len()
+            )));
+        }
+        let mut blobs_usage = Vec::with_capacity(blobs.len());
+        let mut blobs_payload = Vec::new();
+        for (versioned_hash, blob) in versioned_hashes.iter().zip(blobs) {
+            blobs_usage.push(blob_usage(
+                *versioned_hash,
+                blob,
+                blob_gas_price,
+                blob_encoding,
+            ));
+            blobs_payload.extend(blob_payload(blob));
+        }
+        // compute the gas the payload of the blobs would have used as calldata
+        let total_legacy_calldata_gas = compute_legacy_calldata_gas(&blobs_payload, &fork_params);
+        let total_eip_7623_calldata_gas = compute_calldata_gas(&blobs_payload, &fork_params);
+        let calldata_bytes = count_calldata_bytes(&blobs_payload);
         // compute wei spent in different configurations
         let blob_data_wei_spent = blob_gas_used as u128 * blob_gas_price;
+        let eip_7918_blob_gas_price = reserve_blob_base_fee(blob_gas_price, base_fee_per_gas);
+        let eip_7918_blob_data_wei_spent = blob_gas_used as u128 * eip_7918_blob_gas_price;
         let legacy_calldata_wei_spent = total_legacy_calldata_gas as u128 * gas_price;
         let eip_7623_calldata_wei_spent = total_eip_7623_calldata_gas as u128 * gas_price;
+        let gas_breakdown =
+            gas_breakdown(context, intrinsic_gas, &calldata_breakdown, blob_gas_used);
+        // compress the payload of the blobs
+        let compression = analyze_compression(
+            &blobs_payload,
+            &options.compressions,
+            &fork_params,
+            gas_price,
+            Some(blob_gas_price),
+            blob_encoding,
+        )?;
         Ok(TxAnalysisResponse {
             timestamp,
+            block_number: context.block_number,
+            success: context.success,
             blob_gas_used,
             gas_used,
             gas_price,
             blob_gas_price: Some(blob_gas_price),
             legacy_calldata_gas: total_legacy_calldata_gas,
             eip_7623_calldata_gas: total_eip_7623_calldata_gas,
+            zero_bytes: calldata_bytes.zero_bytes,
+            non_zero_bytes: calldata_bytes.non_zero_bytes,
+            calldata: calldata_breakdown,
+            blob_payload: Some(data_breakdown(&blobs_payload, &fork_params)),
+            fees,
+            gas_breakdown,
+            regret: Some(mode_regret(
+                PostingMode::Blob,
+                blob_data_wei_spent,
+                eip_7623_calldata_wei_spent,
+            )),
+            break_even_blob_base_fee: Some(break_even_blob_base_fee(
+                base_fee_per_gas,
+                &fork_params,
+            )),
             blob_data_wei_spent: Some(blob_data_wei_spent),
+            eip_7918_blob_gas_price: Some(eip_7918_blob_gas_price),
+            eip_7918_blob_data_wei_spent: Some(eip_7918_blob_data_wei_spent),
             legacy_calldata_wei_spent,
             eip_7623_calldata_wei_spent,
+            alt_da: options
+                .da_price_table
+                .map(|table| table.analysis(blobs_payload.len() as u64, timestamp)),
+            standard_intrinsic_gas: intrinsic_gas.standard,
+            floor_gas: intrinsic_gas.floor,
+            execution_gas,
+            floor_applied,
+            blob_utilization: Some(blob_utilization(blobs_usage)),
+            l2_batch: decode_l2_batch(tx.to(), tx.input(), blobs),
+            authorization: None,
+            timing: None,
+            hypothetical_calldata_cost: options
+                .calldata_pricing
+                .map(|pricing| hypothetical_calldata_cost(pricing, calldata_bytes, gas_price)),
+            compression,
+            fiat: None,
         })
     } else {
-        let blob_gas_price = block.header.blob_fee();
+        let blob_gas_price = context.blob_gas_price;
         // get calldata
         let calldata = tx.input();
         // compute EIP-7623 calldata gas
-        let eip_7623_calldata_gas = compute_calldata_gas(calldata, block.header.number);
+        let eip_7623_calldata_gas = compute_calldata_gas(calldata, &fork_params);
         // compute legacy calldata gas
-        let legacy_calldata_gas = compute_legacy_calldata_gas(calldata, block.header.number);
+        let legacy_calldata_gas = compute_legacy_calldata_gas(calldata, &fork_params);
+        let calldata_bytes = count_calldata_bytes(calldata);
         // compute wei spent in different configurations
-        let blob_data_wei_spent = if let Some(blob_gas_price) = blob_gas_price {
-            // we need to compute the number of blobs needed to store the calldata
-            // and then multiply by the blob gas price and the number of bytes in a blob
-            let number_of_blobs_needed = calldata.len().div_ceil(BYTES_PER_BLOB as usize) as u128;
-            Some(number_of_blobs_needed * BYTES_PER_BLOB as u128 * blob_gas_price)
-        } else {
-            None
-        };
+        // we need to compute the number of blobs needed to store the calldata with the
+        // encoding of the batcher and then multiply by the blob gas price and the gas per blob
+        let blob_gas_needed = blob_encoding.blobs_needed(calldata.len() as u64) * GAS_PER_BLOB;
+        let blob_data_wei_spent =
+            blob_gas_price.map(|blob_gas_price| blob_gas_needed as u128 * blob_gas_price);
+        let eip_7918_blob_gas_price = blob_gas_price
+            .map(|blob_gas_price| reserve_blob_base_fee(blob_gas_price, base_fee_per_gas));
+        let eip_7918_blob_data_wei_spent =
+            eip_7918_blob_gas_price.map(|blob_gas_price| blob_gas_needed as u128 * blob_gas_price);
         let legacy_calldata_wei_spent = legacy_calldata_gas as u128 * gas_price;
         let eip_7623_calldata_wei_spent = eip_7623_calldata_gas as u128 * gas_price;
+        // compress the calldata
+        let compression = analyze_compression(
+            calldata,
+            &options.compressions,
+            &fork_params,
+            gas_price,
+            blob_gas_price,
+            blob_encoding,
+        )?;
+        // analyze the authorization list of EIP-7702 transactions
+        let authorization = tx.authorization_list().map(|authorization_list| {
+            let data_gas = if floor_applied {
+                intrinsic_gas.floor - BASE_STIPEND
+            } else {
+                legacy_calldata_gas
+            };
+            analyze_authorizations(
+                context.chain_id,
+                authorization_list,
+                sender,
+                authority_accounts,
+                gas_used,
+                gas_price,
+                data_gas,
+            )
+        });
         Ok(TxAnalysisResponse {
             timestamp,
+            block_number: context.block_number,
+            success: context.success,
             blob_gas_used: 0,
             gas_used,
             gas_price,
             blob_gas_price,
             eip_7623_calldata_gas,
             legacy_calldata_gas,
+            zero_bytes: calldata_bytes.zero_bytes,
+            non_zero_bytes: calldata_bytes.non_zero_bytes,
+            calldata: calldata_breakdown,
+            blob_payload: None,
+            fees,
+            gas_breakdown: gas_breakdown(context, intrinsic_gas, &calldata_breakdown, 0),
+            regret: blob_data_wei_spent.map(|blob_data_wei_spent| {
+                mode_regret(
+                    PostingMode::Calldata,
+                    eip_7623_calldata_wei_spent,
+                    blob_data_wei_spent,
+                )
+            }),
+            break_even_blob_base_fee: blob_gas_price
+                .map(|_| break_even_blob_base_fee(base_fee_per_gas, &fork_params)),
             blob_data_wei_spent,
+            eip_7918_blob_gas_price,
+            eip_7918_blob_data_wei_spent,
             legacy_calldata_wei_spent,
             eip_7623_calldata_wei_spent,
+            alt_da: options
+                .da_price_table
+                .map(|table| table.analysis(calldata.len() as u64, timestamp)),
+            standard_intrinsic_gas: intrinsic_gas.standard,
+            floor_gas: intrinsic_gas.floor,
+            execution_gas,
+            floor_applied,
+            blob_utilization: None,
+            l2_batch: decode_l2_batch(tx.to(), calldata, &[]),
+            authorization,
+            timing: None,
+            hypothetical_calldata_cost: options
+                .calldata_pricing
+                .map(|pricing| hypothetical_calldata_cost(pricing, calldata_bytes, gas_price)),
+            compression,
+            fiat: None,
         })
     }
 }
 
+/// Split the fees paid by a transaction into base fee, priority fee and blob fee.
+fn fee_breakdown<T: Transaction>(tx: &T, context: &TxContext) -> FeeBreakdown {
+    let base_fee_per_gas = context.base_fee_per_gas.unwrap_or_default();
+    let priority_fee_per_gas = context.gas_price.saturating_sub(base_fee_per_gas as u128);
+    // only blob transactions pay the blob base fee
+    let blob_gas_price = context
+        .blob_gas_price
+        .filter(|_| tx.is_eip4844())
+        .unwrap_or_default();
+    let max_fee_per_blob_gas = tx.max_fee_per_blob_gas();
+    FeeBreakdown {
+        base_fee_per_gas,
+        priority_fee_per_gas,
+        base_fee_wei_burned: context.gas_used as u128 * base_fee_per_gas as u128,
+        priority_fee_wei_paid: context.gas_used as u128 * priority_fee_per_gas,
+        blob_fee_wei_burned: tx.blob_gas_used().unwrap_or_default() as u128 * blob_gas_price,
+        max_fee_per_gas: tx.max_fee_per_gas(),
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas(),
+        max_fee_per_blob_gas,
+        blob_fee_overbid_per_gas: max_fee_per_blob_gas
+            .map(|max_fee_per_blob_gas| max_fee_per_blob_gas.saturating_sub(blob_gas_price)),
+    }
+}
+
+/// The split of the gas of a transaction into intrinsic, calldata, execution and blob gas.
+///
+/// When the EIP-7623 floor sets the gas used, the calldata is charged at the floor on top of the
+/// base stipend, and the rest of the gas used is execution gas.
+fn gas_breakdown(
+    context: &TxContext,
+    intrinsic_gas: IntrinsicGas,
+    calldata: &DataBreakdown,
+    blob_gas_used: u64,
+) -> GasBreakdown {
+    let gas_price = context.gas_price;
+    let (intrinsic_gas, calldata_gas) =
+        if context.fork_params.is_eip_7623_enabled() && intrinsic_gas.floor >= context.gas_used {
+            (BASE_STIPEND, calldata.floor_token_gas)
+        } else {
+            (
+                intrinsic_gas
+                    .standard
+                    .saturating_sub(calldata.standard_token_gas),
+                calldata.standard_token_gas,
+            )
+        };
+    let execution_gas = context
+        .gas_used
+        .saturating_sub(intrinsic_gas + calldata_gas);
+    GasBreakdown {
+        intrinsic_gas,
+        intrinsic_wei: intrinsic_gas as u128 * gas_price,
+        calldata_gas,
+        calldata_wei: calldata_gas as u128 * gas_price,
+        execution_gas,
+        execution_wei: execution_gas as u128 * gas_price,
+        blob_gas: blob_gas_used,
+        blob_wei: blob_gas_used as u128 * context.blob_gas_price.unwrap_or_default(),
+    }
+}
+
+/// The regret of posting data in the chosen mode rather than in the other one.
+fn mode_regret(
+    chosen_mode: PostingMode,
+    chosen_mode_wei: u128,
+    alternative_mode_wei: u128,
+) -> ModeRegret {
+    ModeRegret {
+        chosen_mode,
+        chosen_mode_wei,
+        alternative_mode_wei,
+        regret_wei: chosen_mode_wei as i128 - alternative_mode_wei as i128,
+    }
+}
+
+/// The byte and token breakdown of data priced as calldata under the rules of the fork.
+fn data_breakdown(data: &[u8], fork_params: &ForkParams) -> DataBreakdown {
+    let calldata_bytes = count_calldata_bytes(data);
+    let tokens = count_calldata_tokens(data, fork_params);
+    DataBreakdown {
+        length: data.len() as u64,
+        zero_bytes: calldata_bytes.zero_bytes,
+        non_zero_bytes: calldata_bytes.non_zero_bytes,
+        tokens,
+        standard_token_gas: fork_params.standard_token_cost * tokens,
+        floor_token_gas: fork_params.total_cost_floor_per_token * tokens,
+    }
+}
+
+/// Get the base fees of the blocks within the window around the inclusion block, sorted by
+/// number.
+///
+/// The blocks are read concurrently. The blocks past the latest block are left out.
+async fn get_window_fees(
+    provider_state: &ProviderState,
+    block_number: u64,
+    window_blocks: u64,
+) -> Result<Vec<BlockFees>, HandlerError> {
+    let mut requests = tokio::task::JoinSet::new();
+    for number in block_number.saturating_sub(window_blocks)..=block_number + window_blocks {
+        let ethereum_provider = provider_state.ethereum_provider.clone();
+        requests.spawn(async move {
+            ethereum_provider
+                .get_block_by_number(BlockNumberOrTag::Number(number))
+                .await
+        });
+    }
+    let mut blocks = Vec::new();
+    while let Some(result) = requests.join_next().await {
+        let block = result
+            .map_err(|e| {
+                HandlerError::ProviderError(format!("Failed to join block request: {}", e))
+            })?
+            .map_err(|e| {
+                HandlerError::ProviderError(format!("Failed to get block by number: {}", e))
+            })?;
+        let Some(block) = block else {
+            continue;
+        };
+        let fork_params = provider_state
+            .fork_schedule
+            .params_at(block.header.number, block.header.timestamp);
+        blocks.push(BlockFees {
+            block_number: block.header.number,
+            base_fee_per_gas: block.header.base_fee_per_gas.unwrap_or_default(),
+            blob_base_fee: block
+                .header
+                .excess_blob_gas
+                .zip(fork_params.blob)
+                .map(|(excess_blob_gas, blob_params)| blob_base_fee(excess_blob_gas, &blob_params)),
+        });
+    }
+    blocks.sort_by_key(|block| block.block_number);
+    Ok(blocks)
+}
+
+/// Get the accounts of the authorities of an EIP-7702 transaction at the parent block.
+async fn get_authority_accounts(
+    provider_state: &ProviderState,
+    authorization_list: &[SignedAuthorization],
+    parent_block_number: u64,
+) -> Result<HashMap<Address, AuthorityAccount>, HandlerError> {
+    let block_id = BlockId::number(parent_block_number);
+    let mut accounts = HashMap::new();
+    for authority in authorization_list
+        .iter()
+        .filter_map(|authorization| authorization.recover_authority().ok())
+    {
+        if accounts.contains_key(&authority) {
+            continue;
+        }
+        let provider = &provider_state.ethereum_provider;
+        let nonce = provider
+            .get_transaction_count(authority)
+            .block_id(block_id)
+            .await
+            .map_err(|e| HandlerError::ProviderError(format!("Failed to get nonce: {}", e)))?;
+        let balance = provider
+            .get_balance(authority)
+            .block_id(block_id)
+            .await
+            .map_err(|e| HandlerError::ProviderError(format!("Failed to get balance: {}", e)))?;
+        let code = provider
+            .get_code_at(authority)
+            .block_id(block_id)
+            .await
+            .map_err(|e| HandlerError::ProviderError(format!("Failed to get code: {}", e)))?;
+        accounts.insert(
+            authority,
+            AuthorityAccount {
+                nonce,
+                balance,
+                code,
+            },
+        );
+    }
+    Ok(accounts)
+}
+
+/// Analyze the authorization list of an EIP-7702 transaction against the accounts of the
+/// authorities before the transaction.
+fn analyze_authorizations(
+    chain_id: u64,
+    authorization_list: &[SignedAuthorization],
+    sender: Address,
+    accounts: &HashMap<Address, AuthorityAccount>,
+    gas_used: u64,
+    gas_price: u128,
+    data_gas: u64,
+) -> AuthorizationAnalysis {
+    let authorizations: Vec<_> = authorization_list
+        .iter()
+        .map(|authorization| {
+            (
+                authorization.recover_authority().ok(),
+                authorization.inner().clone(),
+            )
+        })
+        .collect();
+    let authorization_gas = compute_authorization_gas(chain_id, sender, &authorizations, accounts);
+    let delegation_gas = authorization_gas.charged - authorization_gas.refunded;
+    AuthorizationAnalysis {
+        authorization_count: authorizations.len() as u64,
+        valid_authorization_count: authorization_gas.valid_count,
+        authorities: authorizations
+            .iter()
+            .map(|(authority, _)| *authority)
+            .collect(),
+        delegated_addresses: authorizations
+            .iter()
+            .map(|(_, authorization)| authorization.address)
+            .collect(),
+        authorization_gas_charged: authorization_gas.charged,
+        authorization_gas_refunded: authorization_gas.refunded,
+        data_gas,
+        data_wei_spent: data_gas as u128 * gas_price,
+        delegation_gas,
+        delegation_wei_spent: delegation_gas as u128 * gas_price,
+        data_cost_pct: gas_pct(data_gas, gas_used),
+        delegation_cost_pct: gas_pct(delegation_gas, gas_used),
+    }
+}
+
+/// It returns the percentage of the gas used spent on a part of the transaction, 0 if no gas was
+/// used.
+fn gas_pct(gas: u64, gas_used: u64) -> f64 {
+    if gas_used == 0 {
+        return 0.0;
+    }
+    gas as f64 / gas_used as f64 * 100.0
+}
+
+/// Compress the payload of a transaction and price the compressed payload.
+///
+/// None if no compression is requested.
+fn analyze_compression(
+    payload: &[u8],
+    compressions: &[Compression],
+    fork_params: &ForkParams,
+    gas_price: u128,
+    blob_gas_price: Option<u128>,
+    blob_encoding: BlobEncoding,
+) -> Result<Option<CompressionAnalysis>, HandlerError> {
+    if compressions.is_empty() {
+        return Ok(None);
+    }
+    let mut results = Vec::with_capacity(compressions.len());
+    for compression in compressions {
+        let compressed = compression.compress(payload).map_err(|e| {
+            HandlerError::CompressionError(format!(
+                "Failed to compress with {}: {}",
+                compression, e
+            ))
+        })?;
+        let compressed_size = compressed.len() as u64;
+        let legacy_calldata_gas = compute_legacy_calldata_gas(&compressed, fork_params);
+        let eip_7623_calldata_gas = compute_calldata_gas(&compressed, fork_params);
+        let blob_gas_needed = blob_encoding.blobs_needed(compressed_size) * GAS_PER_BLOB;
+        results.push(CompressedPayloadCost {
+            algorithm: compression.algorithm,
+            level: compression.level,
+            compressed_size,
+            compression_ratio: payload.len() as f64 / compressed_size as f64,
+            legacy_calldata_gas,
+            eip_7623_calldata_gas,
+            legacy_calldata_wei_spent: legacy_calldata_gas as u128 * gas_price,
+            eip_7623_calldata_wei_spent: eip_7623_calldata_gas as u128 * gas_price,
+            blob_data_wei_spent: blob_gas_price
+                .map(|blob_gas_price| blob_gas_needed as u128 * blob_gas_price),
+        });
+    }
+    Ok(Some(CompressionAnalysis {
+        payload_size: payload.len() as u64,
+        results,
+    }))
+}
+
+/// The fiat conversion of the query.
+///
+/// None if no currency is requested.
+fn fiat_conversion(
+    provider_state: &ProviderState,
+    query: CurrencyQuery,
+) -> Result<Option<FiatConversion<'_>>, HandlerError> {
+    let Some(currency) = query.currency else {
+        return Ok(None);
+    };
+    let Some(price_source) = provider_state.price_source.as_deref() else {
+        return Err(HandlerError::InvalidQuery(
+            "No price source is configured".to_string(),
+        ));
+    };
+    Ok(Some(FiatConversion {
+        price_source,
+        currency,
+    }))
+}
+
+/// Convert the wei values of a transaction analysis at the ETH price of its block.
+async fn fiat_cost(
+    tx_analysis: &TxAnalysisResponse,
+    fiat: FiatConversion<'_>,
+) -> Result<FiatCost, HandlerError> {
+    let at = PricePoint {
+        block_number: Some(tx_analysis.block_number),
+        timestamp: tx_analysis.timestamp,
+    };
+    let Some(eth_price) = fiat
+        .eth_price(at)
+        .await
+        .map_err(|e| HandlerError::PriceSourceError(format!("Failed to get ETH price: {}", e)))?
+    else {
+        return Err(HandlerError::PriceNotFound(format!(
+            "block {}",
+            tx_analysis.block_number
+        )));
+    };
+    let total_wei_spent = tx_analysis.gas_used as u128 * tx_analysis.gas_price
+        + tx_analysis.blob_gas_used as u128 * tx_analysis.blob_gas_price.unwrap_or(0);
+    Ok(FiatCost {
+        currency: fiat.currency,
+        eth_price,
+        total_spent: wei_to_fiat(total_wei_spent, eth_price),
+        blob_data_spent: tx_analysis
+            .blob_data_wei_spent
+            .map(|wei| wei_to_fiat(wei, eth_price)),
+        eip_7918_blob_data_spent: tx_analysis
+            .eip_7918_blob_data_wei_spent
+            .map(|wei| wei_to_fiat(wei, eth_price)),
+        legacy_calldata_spent: wei_to_fiat(tx_analysis.legacy_calldata_wei_spent, eth_price),
+        eip_7623_calldata_spent: wei_to_fiat(tx_analysis.eip_7623_calldata_wei_spent, eth_price),
+    })
+}
+
+/// Price the calldata bytes under a hypothetical pricing.
+fn hypothetical_calldata_cost(
+    pricing: CalldataPricing,
+    calldata_bytes: CalldataBytes,
+    gas_price: u128,
+) -> HypotheticalCalldataCost {
+    let legacy_calldata_gas =
+        pricing.legacy_calldata_gas(calldata_bytes.zero_bytes, calldata_bytes.non_zero_bytes);
+    let floor_calldata_gas =
+        pricing.floor_calldata_gas(calldata_bytes.zero_bytes, calldata_bytes.non_zero_bytes);
+    HypotheticalCalldataCost {
+        pricing,
+        legacy_calldata_gas,
+        floor_calldata_gas,
+        legacy_calldata_wei_spent: legacy_calldata_gas as u128 * gas_price,
+        floor_calldata_wei_spent: floor_calldata_gas as u128 * gas_price,
+    }
+}
+
+/// Parse the hypothetical calldata pricing of the query, if any.
+fn calldata_pricing(query: CalldataPricingQuery) -> Result<Option<CalldataPricing>, HandlerError> {
+    let mut pricing = match &query.calldata_pricing {
+        Some(name) => CalldataPricing::named(name).ok_or_else(|| {
+            HandlerError::InvalidQuery(format!("unknown calldata pricing: {}", name))
+        })?,
+        None if query.standard_token_cost.is_some()
+            || query.floor_cost_per_token.is_some()
+            || query.zero_byte_tokens.is_some()
+            || query.non_zero_byte_tokens.is_some() =>
+        {
+            CalldataPricing::eip_7623()
+        }
+        None => return Ok(None),
+    };
+    if let Some(standard_token_cost) = query.standard_token_cost {
+        pricing.standard_token_cost = standard_token_cost;
+    }
+    if let Some(floor_cost_per_token) = query.floor_cost_per_token {
+        pricing.total_cost_floor_per_token = floor_cost_per_token;
+    }
+    if let Some(zero_byte_tokens) = query.zero_byte_tokens {
+        pricing.zero_byte_tokens = zero_byte_tokens;
+    }
+    if let Some(non_zero_byte_tokens) = query.non_zero_byte_tokens {
+        pricing.non_zero_byte_tokens = non_zero_byte_tokens;
+    }
+    Ok(Some(pricing))
+}
+
+/// Reject a hypothetical calldata pricing on the endpoints that don't reprice calldata.
+fn reject_calldata_pricing(query: CalldataPricingQuery) -> Result<(), HandlerError> {
+    if calldata_pricing(query)?.is_some() {
+        return Err(HandlerError::InvalidQuery(
+            "hypothetical calldata pricing is only supported by /tx, /all_eth_saved and /all_pectra_data_gas"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Decode the L2 batch posted by the transaction, if it is sent to a known batch inbox.
+fn decode_l2_batch(
+    to: Option<Address>,
+    calldata: &[u8],
+    blobs: &[Bytes],
+) -> Option<L2BatchSummary> {
+    let decoder = batch::decoder_for(to?)?;
+    decoder
+        .decode(calldata, blobs)
+        .inspect_err(|e| warn!("Failed to decode L2 batch: {}", e))
+        .ok()
+}
+
 pub async fn tx_handler(
     State(app_state): State<super::AppState>,
     Query(query): Query<TxHashQuery>,
+    Query(pricing_query): Query<CalldataPricingQuery>,
+    Query(currency_query): Query<CurrencyQuery>,
 ) -> Result<Json<TxAnalysisResponse>, HandlerError> {
+    let fiat = fiat_conversion(&app_state.provider_state, currency_query)?;
     // transform tx hash into a fixed bytes
     let tx_hash_bytes = FixedBytes::from_hex(&query.tx_hash)
         .map_err(|_| HandlerError::InvalidHex(query.tx_hash))?;
-    let tx_analysis = analyze_transaction(&app_state.provider_state, tx_hash_bytes).await?;
+    let blob_encoding = query
+        .blob_encoding
+        .map(|blob_encoding| blob_encoding.parse().map_err(HandlerError::InvalidQuery))
+        .transpose()?;
+    let compressions = query
+        .compression
+        .iter()
+        .flat_map(|compressions| compressions.split(','))
+        .map(|compression| compression.parse().map_err(HandlerError::InvalidQuery))
+        .collect::<Result<_, _>>()?;
+    if let Some(timing_window) = query.timing_window
+        && timing_window > MAX_TIMING_WINDOW_BLOCKS
+    {
+        return Err(HandlerError::InvalidQuery(format!(
+            "the timing window is at most {} blocks",
+            MAX_TIMING_WINDOW_BLOCKS
+        )));
+    }
+    let options = AnalysisOptions {
+        blob_encoding,
+        calldata_pricing: calldata_pricing(pricing_query)?,
+        compressions,
+        timing_window: query.timing_window,
+        da_price_table: app_state.provider_state.da_price_table.clone(),
+    };
+    let mut tx_analysis =
+        analyze_transaction(&app_state.provider_state, tx_hash_bytes, options).await?;
+    if let Some(fiat) = fiat {
+        tx_analysis.fiat = Some(fiat_cost(&tx_analysis, fiat).await?);
+    }
     Ok(Json(tx_analysis))
 }
 
+pub async fn simulate_transaction(
+    provider_state: &ProviderState,
+    tx_hash_bytes: FixedBytes<32>,
+) -> Result<SimulationResponse, HandlerError> {
+    // get tx
+    let Some(tx) = provider_state
+        .ethereum_provider
+        .get_transaction_by_hash(tx_hash_bytes)
+        .await
+        .map_err(|e| {
+            HandlerError::ProviderError(format!("Failed to get transaction by hash: {}", e))
+        })?
+    else {
+        return Err(HandlerError::TransactionNotFound(tx_hash_bytes.to_string()));
+    };
+    let Some(block_hash) = tx.block_hash else {
+        return Err(HandlerError::BlockNotFound(tx_hash_bytes.to_string()));
+    };
+    let Some(block) = provider_state
+        .ethereum_provider
+        .get_block_by_hash(block_hash)
+        .await
+        .map_err(|e| HandlerError::ProviderError(format!("Failed to get block by hash: {}", e)))?
+    else {
+        return Err(HandlerError::BlockNotFound(tx_hash_bytes.to_string()));
+    };
+    let block_env = simulation::block_env(&block.header.inner);
+    let tx_env = simulation::tx_env(&tx, tx.inner.signer());
+    // replay against the state at the parent block
+    let db = RpcDatabase::new(
+        provider_state.ethereum_provider.clone(),
+        block.header.number.saturating_sub(1),
+    );
+    let handle = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        simulation::simulate(
+            WrapDatabaseAsync::with_handle(db, handle),
+            &block_env,
+            &tx_env,
+        )
+    })
+    .await
+    .map_err(|e| HandlerError::SimulationError(e.to_string()))?
+    .map_err(|e| HandlerError::SimulationError(e.to_string()))
+}
+
+pub async fn simulate_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<TxHashQuery>,
+) -> Result<Json<SimulationResponse>, HandlerError> {
+    // transform tx hash into a fixed bytes
+    let tx_hash_bytes = FixedBytes::from_hex(&query.tx_hash)
+        .map_err(|_| HandlerError::InvalidHex(query.tx_hash))?;
+    let simulation = simulate_transaction(&app_state.provider_state, tx_hash_bytes).await?;
+    Ok(Json(simulation))
+}
+
+pub async fn blob_fee_projection_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<BlobFeeProjectionQuery>,
+) -> Result<Json<BlobFeeProjectionResponse>, HandlerError> {
+    if query.blocks > MAX_PROJECTED_BLOCKS {
+        return Err(HandlerError::InvalidQuery(format!(
+            "blocks must be at most {}",
+            MAX_PROJECTED_BLOCKS
+        )));
+    }
+    let Some(latest_block) = app_state
+        .provider_state
+        .ethereum_provider
+        .get_block_by_number(BlockNumberOrTag::Latest)
+        .await
+        .map_err(|e| HandlerError::ProviderError(format!("Failed to get latest block: {}", e)))?
+    else {
+        return Err(HandlerError::BlockNotFound("latest".to_string()));
+    };
+    let fork_params = app_state
+        .provider_state
+        .fork_schedule
+        .params_at(latest_block.header.number, latest_block.header.timestamp);
+    let Some(blob_params) = fork_params.blob else {
+        return Err(HandlerError::InvalidQuery(
+            "blobs are not active on this chain".to_string(),
+        ));
+    };
+    if query.blobs_per_block > blob_params.max_blobs_per_block {
+        return Err(HandlerError::InvalidQuery(format!(
+            "blobs_per_block must be at most {}",
+            blob_params.max_blobs_per_block
+        )));
+    }
+    let max_excess_blob_gas =
+        MAX_EXCESS_BLOB_GAS_FRACTIONS.saturating_mul(blob_params.base_fee_update_fraction);
+    if query
+        .excess_blob_gas
+        .is_some_and(|excess_blob_gas| excess_blob_gas > max_excess_blob_gas)
+    {
+        return Err(HandlerError::InvalidQuery(format!(
+            "excess_blob_gas must be at most {}",
+            max_excess_blob_gas
+        )));
+    }
+    // start from the block after the latest one, unless the excess blob gas is given
+    let excess_blob_gas = match query.excess_blob_gas {
+        Some(excess_blob_gas) => excess_blob_gas,
+        None => next_excess_blob_gas(
+            latest_block.header.excess_blob_gas.unwrap_or_default(),
+            latest_block.header.blob_gas_used.unwrap_or_default(),
+            &blob_params,
+        ),
+    };
+    let blob_counts = vec![query.blobs_per_block; query.blocks as usize];
+    Ok(Json(BlobFeeProjectionResponse {
+        fork: fork_params.fork,
+        base_fee_update_fraction: blob_params.base_fee_update_fraction,
+        projections: project_blob_base_fee(excess_blob_gas, &blob_counts, &blob_params),
+    }))
+}
+
+/// The pricing parameters of the requested fork, or of the fork active at the requested block
+/// number and timestamp.
+///
+/// The latest fork if nothing is requested.
+fn requested_fork_params(
+    fork_schedule: &ForkSchedule,
+    fork: Option<Fork>,
+    block_number: Option<u64>,
+    timestamp: Option<u64>,
+) -> ForkParams {
+    match (fork, block_number, timestamp) {
+        (Some(fork), _, _) => fork_schedule.fork_params(fork),
+        // the rules in effect now
+        (None, None, None) => {
+            fork_schedule.params_at(u64::MAX, chrono::Utc::now().timestamp() as u64)
+        }
+        // forks activated by a missing block number or timestamp are not active
+        (None, block_number, timestamp) => {
+            fork_schedule.params_at(block_number.unwrap_or(0), timestamp.unwrap_or(0))
+        }
+    }
+}
+
+/// Analyze a signed raw transaction, without chain access.
+pub async fn raw_tx_handler(
+    State(app_state): State<super::AppState>,
+    Query(pricing_query): Query<CalldataPricingQuery>,
+    Json(request): Json<RawTxAnalysisRequest>,
+) -> Result<Json<TxAnalysisResponse>, HandlerError> {
+    let blob_encoding = request
+        .blob_encoding
+        .map(|blob_encoding| blob_encoding.parse().map_err(HandlerError::InvalidQuery))
+        .transpose()?;
+    let compressions = request
+        .compression
+        .iter()
+        .flat_map(|compressions| compressions.split(','))
+        .map(|compression| compression.parse().map_err(HandlerError::InvalidQuery))
+        .collect::<Result<_, _>>()?;
+    let options = AnalysisOptions {
+        blob_encoding,
+        calldata_pricing: calldata_pricing(pricing_query)?,
+        compressions,
+        // the fees of the other blocks are not known offline
+        timing_window: None,
+        da_price_table: app_state.provider_state.da_price_table.clone(),
+    };
+    let tx_analysis = analyze_raw_transaction(
+        &request.raw_tx,
+        &request.context,
+        &app_state.provider_state.fork_schedule,
+        options,
+    )?;
+    Ok(Json(tx_analysis))
+}
+
+/// Analyze an EIP-2718 encoded signed transaction with the block and fee context given by the
+/// caller.
+///
+/// Blob transactions must carry their sidecar. Without state, every authority of an EIP-7702
+/// transaction is assumed to be an EOA without balance at the nonce of its first authorization,
+/// or at the nonce of the transaction if it's the sender. So only authorizations for another
+/// chain or with a reused nonce are invalid, and only authorities at nonce 0 are empty.
+pub fn analyze_raw_transaction(
+    raw_tx: &[u8],
+    context: &RawTxContext,
+    fork_schedule: &ForkSchedule,
+    options: AnalysisOptions,
+) -> Result<TxAnalysisResponse, HandlerError> {
+    let tx = TxEnvelope::decode_2718(&mut &raw_tx[..])
+        .map_err(|e| HandlerError::InvalidTransaction(format!("Failed to decode: {}", e)))?;
+    let sender = tx.recover_signer().map_err(|e| {
+        HandlerError::InvalidTransaction(format!("Failed to recover the signer: {}", e))
+    })?;
+    let fork_params = requested_fork_params(
+        fork_schedule,
+        context.fork,
+        context.block_number,
+        context.timestamp,
+    );
+    let blob_gas_price = fork_params.blob.and_then(|blob_params| {
+        context.blob_base_fee.or_else(|| {
+            context
+                .excess_blob_gas
+                .map(|excess_blob_gas| blob_base_fee(excess_blob_gas, &blob_params))
+        })
+    });
+    // a transaction that doesn't execute uses its intrinsic gas
+    let gas_used = context.gas_used.unwrap_or_else(|| {
+        let intrinsic_gas = compute_intrinsic_gas(&tx, &fork_params);
+        if fork_params.is_eip_7623_enabled() {
+            intrinsic_gas.standard.max(intrinsic_gas.floor)
+        } else {
+            intrinsic_gas.standard
+        }
+    });
+    let blobs = match &tx {
+        TxEnvelope::Eip4844(signed) => match signed.tx() {
+            TxEip4844Variant::TxEip4844WithSidecar(tx) => tx
+                .sidecar
+                .blobs
+                .iter()
+                .map(|blob| Bytes::copy_from_slice(blob.as_slice()))
+                .collect(),
+            TxEip4844Variant::TxEip4844(_) => {
+                return Err(HandlerError::BlobDataNotFound(
+                    "the blob transaction has no sidecar".to_string(),
+                ));
+            }
+        },
+        _ => Vec::new(),
+    };
+    let mut authority_accounts = HashMap::new();
+    for authorization in tx.authorization_list().unwrap_or_default() {
+        let Ok(authority) = authorization.recover_authority() else {
+            continue;
+        };
+        authority_accounts
+            .entry(authority)
+            .or_insert_with(|| AuthorityAccount {
+                nonce: authorization.nonce,
+                ..Default::default()
+            });
+    }
+    // the nonce of the sender is known from the transaction
+    if let Some(sender_account) = authority_accounts.get_mut(&sender) {
+        sender_account.nonce = tx.nonce();
+    }
+    let tx_context = TxContext {
+        chain_id: fork_schedule.chain_id,
+        fork_params,
+        block_number: context.block_number.unwrap_or_default(),
+        timestamp: context.timestamp.unwrap_or_default(),
+        success: context.success.unwrap_or(true),
+        base_fee_per_gas: context.base_fee_per_gas,
+        gas_used,
+        gas_price: tx.effective_gas_price(context.base_fee_per_gas),
+        blob_gas_price,
+    };
+    analyze_transaction_data(
+        &tx,
+        sender,
+        &blobs,
+        &authority_accounts,
+        &tx_context,
+        options,
+    )
+}
+
+/// Estimate the cost of posting data as calldata or in blobs, without chain access.
+pub async fn estimate_handler(
+    State(app_state): State<super::AppState>,
+    Json(request): Json<EstimateRequest>,
+) -> Result<Json<EstimateResponse>, HandlerError> {
+    let estimate = estimate_cost(request, &app_state.provider_state.fork_schedule)?;
+    Ok(Json(estimate))
+}
+
+/// Estimate the cost of posting data under the rules of a fork of the schedule.
+pub fn estimate_cost(
+    request: EstimateRequest,
+    fork_schedule: &ForkSchedule,
+) -> Result<EstimateResponse, HandlerError> {
+    let data = match (request.calldata, request.blob_payload) {
+        (Some(data), None) | (None, Some(data)) => data,
+        _ => {
+            return Err(HandlerError::InvalidQuery(
+                "exactly one of calldata and blob_payload must be given".to_string(),
+            ));
+        }
+    };
+    let fork_params = requested_fork_params(
+        fork_schedule,
+        request.fork,
+        request.block_number,
+        request.timestamp,
+    );
+    let blob_encoding: BlobEncoding = request
+        .blob_encoding
+        .map(|blob_encoding| blob_encoding.parse().map_err(HandlerError::InvalidQuery))
+        .transpose()?
+        .unwrap_or_default();
+    // calldata
+    let calldata_bytes = count_calldata_bytes(&data);
+    let legacy_calldata_gas = compute_legacy_calldata_gas(&data, &fork_params);
+    let eip_7623_calldata_gas = compute_calldata_gas(&data, &fork_params);
+    let calldata_tx_gas = if fork_params.is_eip_7623_enabled() {
+        BASE_STIPEND + legacy_calldata_gas.max(eip_7623_calldata_gas)
+    } else {
+        BASE_STIPEND + legacy_calldata_gas
+    };
+    // blobs
+    let blob_count = blob_encoding.blobs_needed(data.len() as u64);
+    let blob_gas_used = blob_count * GAS_PER_BLOB;
+    let blob_gas_price = fork_params.blob.and_then(|blob_params| {
+        request.blob_base_fee.or_else(|| {
+            request
+                .excess_blob_gas
+                .map(|excess_blob_gas| blob_base_fee(excess_blob_gas, &blob_params))
+        })
+    });
+    let blob_data_wei_spent =
+        blob_gas_price.map(|blob_gas_price| blob_gas_used as u128 * blob_gas_price);
+    let gas_price = request.gas_price;
+    Ok(EstimateResponse {
+        fork: fork_params.fork,
+        data_size: data.len() as u64,
+        zero_bytes: calldata_bytes.zero_bytes,
+        non_zero_bytes: calldata_bytes.non_zero_bytes,
+        legacy_calldata_gas,
+        eip_7623_calldata_gas,
+        calldata_tx_gas,
+        legacy_calldata_wei_spent: gas_price
+            .map(|gas_price| legacy_calldata_gas as u128 * gas_price),
+        eip_7623_calldata_wei_spent: gas_price
+            .map(|gas_price| eip_7623_calldata_gas as u128 * gas_price),
+        calldata_tx_wei_spent: gas_price.map(|gas_price| calldata_tx_gas as u128 * gas_price),
+        blob_count,
+        blob_gas_used,
+        blob_gas_price,
+        blob_data_wei_spent,
+        blob_tx_wei_spent: gas_price.zip(blob_data_wei_spent).map(
+            |(gas_price, blob_data_wei_spent)| {
+                BASE_STIPEND as u128 * gas_price + blob_data_wei_spent
+            },
+        ),
+    })
+}
+
 pub async fn contract_handler(
     State(app_state): State<super::AppState>,
     Query(query): Query<ContractQuery>,
@@ -202,8 +1285,13 @@ pub async fn contract_handler(
     // deduplicate tx list
     let unique_tx_list: FxHashSet<_> = tx_list.into_iter().collect();
     for tx_hash in &unique_tx_list {
-        let tx_analysis = analyze_transaction(&app_state.provider_state, *tx_hash).await?;
-        if tx_analysis.gas_used == tx_analysis.eip_7623_calldata_gas + BASE_STIPEND {
+        let tx_analysis = analyze_transaction(
+            &app_state.provider_state,
+            *tx_hash,
+            AnalysisOptions::default(),
+        )
+        .await?;
+        if tx_analysis.floor_applied {
             // tx is influenced by eip7623
             influenced += 1;
             influenced_tx_list.push(*tx_hash);
@@ -243,7 +1331,9 @@ pub async fn daily_txs_handler(
 pub async fn eth_saved_handler(
     State(app_state): State<super::AppState>,
     Query(query): Query<EthSavedQuery>,
+    Query(pricing_query): Query<CalldataPricingQuery>,
 ) -> Result<Json<EthSavedResponse>, HandlerError> {
+    reject_calldata_pricing(pricing_query)?;
     let total_eth_saved_wei = app_state
         .db
         .get_eth_saved_data(
@@ -285,7 +1375,9 @@ pub async fn blob_data_gas_handler(
 pub async fn pectra_data_gas_handler(
     State(app_state): State<super::AppState>,
     Query(query): Query<GasUsageQuery>,
+    Query(pricing_query): Query<CalldataPricingQuery>,
 ) -> Result<Json<PectraDataGasResponse>, HandlerError> {
+    reject_calldata_pricing(pricing_query)?;
     let total_pectra_data_gas = app_state
         .db
         .get_total_pectra_data_gas(
@@ -324,16 +1416,23 @@ pub async fn all_daily_txs_handler(
 pub async fn all_eth_saved_handler(
     State(app_state): State<super::AppState>,
     Query(query): Query<AggregatedQuery>,
+    Query(pricing_query): Query<CalldataPricingQuery>,
+    Query(currency_query): Query<CurrencyQuery>,
 ) -> Result<Json<AllEthSavedResponse>, HandlerError> {
+    let pricing = calldata_pricing(pricing_query)?;
+    let fiat = fiat_conversion(&app_state.provider_state, currency_query)?;
     let batchers = app_state
         .db
-        .get_all_eth_saved_data(query.start_timestamp, query.end_timestamp)
+        .get_all_eth_saved_data(query.start_timestamp, query.end_timestamp, pricing, fiat)
         .await
         .map_err(|e| {
             HandlerError::DatabaseError(format!("Failed to get all ETH saved data: {}", e))
         })?;
 
-    Ok(Json(AllEthSavedResponse { batchers }))
+    Ok(Json(AllEthSavedResponse {
+        currency: fiat.map(|fiat| fiat.currency),
+        batchers,
+    }))
 }
 
 /// Handler for aggregated blob data gas endpoint (all batchers)
@@ -356,10 +1455,12 @@ pub async fn all_blob_data_gas_handler(
 pub async fn all_pectra_data_gas_handler(
     State(app_state): State<super::AppState>,
     Query(query): Query<AggregatedQuery>,
+    Query(pricing_query): Query<CalldataPricingQuery>,
 ) -> Result<Json<AllPectraDataGasResponse>, HandlerError> {
+    let pricing = calldata_pricing(pricing_query)?;
     let batchers = app_state
         .db
-        .get_all_total_pectra_data_gas(query.start_timestamp, query.end_timestamp)
+        .get_all_total_pectra_data_gas(query.start_timestamp, query.end_timestamp, pricing)
         .await
         .map_err(|e| {
             HandlerError::DatabaseError(format!("Failed to get all Pectra data gas: {}", e))
@@ -368,9 +1469,155 @@ pub async fn all_pectra_data_gas_handler(
     Ok(Json(AllPectraDataGasResponse { batchers }))
 }
 
+/// Handler for aggregated compression endpoint (all batchers)
+pub async fn all_compression_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+    Query(currency_query): Query<CurrencyQuery>,
+) -> Result<Json<AllCompressionResponse>, HandlerError> {
+    let fiat = fiat_conversion(&app_state.provider_state, currency_query)?;
+    let batchers = app_state
+        .db
+        .get_all_compression(query.start_timestamp, query.end_timestamp, fiat)
+        .await
+        .map_err(|e| {
+            HandlerError::DatabaseError(format!("Failed to get all compression data: {}", e))
+        })?;
+
+    Ok(Json(AllCompressionResponse {
+        currency: fiat.map(|fiat| fiat.currency),
+        batchers,
+    }))
+}
+
+/// Handler for the blob sharing simulation endpoint (all batchers)
+pub async fn blob_sharing_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+    Query(sharing_query): Query<BlobSharingQuery>,
+) -> Result<Json<BlobSharingSimulation>, HandlerError> {
+    let window_blocks = sharing_query
+        .window_blocks
+        .unwrap_or(DEFAULT_SHARING_WINDOW_BLOCKS);
+    if window_blocks == 0 {
+        return Err(HandlerError::InvalidQuery(
+            "the sharing window is at least 1 block".to_string(),
+        ));
+    }
+    let simulation = app_state
+        .db
+        .get_blob_sharing_simulation(query.start_timestamp, query.end_timestamp, window_blocks)
+        .await
+        .map_err(|e| {
+            HandlerError::DatabaseError(format!("Failed to simulate blob sharing: {}", e))
+        })?;
+
+    Ok(Json(simulation))
+}
+
+/// Handler for aggregated alternative data availability cost endpoint (all batchers)
+pub async fn all_alt_da_cost_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+) -> Result<Json<AllAltDaCostResponse>, HandlerError> {
+    let batchers = app_state
+        .db
+        .get_all_alt_da_cost(query.start_timestamp, query.end_timestamp)
+        .await
+        .map_err(|e| {
+            HandlerError::DatabaseError(format!("Failed to get all alt-DA cost: {}", e))
+        })?;
+
+    Ok(Json(AllAltDaCostResponse { batchers }))
+}
+
+/// Handler for the wrong posting mode endpoint (all batchers)
+pub async fn all_wrong_mode_batches_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+) -> Result<Json<AllWrongModeBatchesResponse>, HandlerError> {
+    let batches = app_state
+        .db
+        .get_wrong_mode_batches(query.start_timestamp, query.end_timestamp)
+        .await
+        .map_err(|e| {
+            HandlerError::DatabaseError(format!("Failed to get wrong mode batches: {}", e))
+        })?;
+
+    Ok(Json(AllWrongModeBatchesResponse { batches }))
+}
+
+/// Handler for aggregated wasted spend endpoint (all batchers)
+pub async fn all_wasted_spend_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+) -> Result<Json<AllWastedSpendResponse>, HandlerError> {
+    let batchers = app_state
+        .db
+        .get_all_wasted_spend(query.start_timestamp, query.end_timestamp)
+        .await
+        .map_err(|e| {
+            HandlerError::DatabaseError(format!("Failed to get all wasted spend: {}", e))
+        })?;
+
+    Ok(Json(AllWastedSpendResponse { batchers }))
+}
+
+/// Handler for aggregated fee breakdown endpoint (all batchers)
+pub async fn all_fee_breakdown_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+) -> Result<Json<AllFeeBreakdownResponse>, HandlerError> {
+    let batchers = app_state
+        .db
+        .get_all_fee_breakdown(query.start_timestamp, query.end_timestamp)
+        .await
+        .map_err(|e| {
+            HandlerError::DatabaseError(format!("Failed to get all fee breakdown: {}", e))
+        })?;
+
+    Ok(Json(AllFeeBreakdownResponse { batchers }))
+}
+
+/// Handler for aggregated blob utilization endpoint (all batchers)
+pub async fn all_blob_utilization_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+) -> Result<Json<AllBlobUtilizationResponse>, HandlerError> {
+    let batchers = app_state
+        .db
+        .get_all_blob_utilization(query.start_timestamp, query.end_timestamp)
+        .await
+        .map_err(|e| {
+            HandlerError::DatabaseError(format!("Failed to get all blob utilization: {}", e))
+        })?;
+
+    Ok(Json(AllBlobUtilizationResponse { batchers }))
+}
+
+pub async fn all_l2_tx_cost_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+    Query(currency_query): Query<CurrencyQuery>,
+) -> Result<Json<AllL2TxCostResponse>, HandlerError> {
+    let fiat = fiat_conversion(&app_state.provider_state, currency_query)?;
+    let batchers = app_state
+        .db
+        .get_all_l2_tx_cost(query.start_timestamp, query.end_timestamp, fiat)
+        .await
+        .map_err(|e| HandlerError::DatabaseError(format!("Failed to get all L2 tx cost: {}", e)))?;
+
+    Ok(Json(AllL2TxCostResponse {
+        currency: fiat.map(|fiat| fiat.currency),
+        batchers,
+    }))
+}
+
 pub async fn seven_day_stats_handler(
     State(app_state): State<super::AppState>,
+    Query(pricing_query): Query<CalldataPricingQuery>,
 ) -> Result<Json<AllBatchersSevenDayStatsResponse>, HandlerError> {
+    reject_calldata_pricing(pricing_query)?;
     let rows = app_state.db.get_recent_daily_stats(7).await.map_err(|e| {
         HandlerError::DatabaseError(format!("Failed to get recent daily stats: {}", e))
     })?;
@@ -401,3 +1648,176 @@ pub async fn seven_day_stats_handler(
 
     Ok(Json(AllBatchersSevenDayStatsResponse { batchers }))
 }
+
+/// It reads the daily snapshot rows of the range and groups them into one series per batcher,
+/// sorted by batcher address.
+///
+/// `new_series` creates the empty series of a batcher and `push` appends a snapshot to it. The
+/// rows are sorted by timestamp, so are the series.
+async fn daily_series<S>(
+    app_state: &super::AppState,
+    query: &AggregatedQuery,
+    new_series: impl Fn(String) -> S,
+    push: impl Fn(&mut S, DailyBatcherStats),
+) -> Result<Vec<S>, HandlerError> {
+    let rows = app_state
+        .db
+        .get_daily_stats(query.start_timestamp, query.end_timestamp)
+        .await
+        .map_err(|e| HandlerError::DatabaseError(format!("Failed to get daily stats: {}", e)))?;
+
+    let mut series: BTreeMap<String, S> = BTreeMap::new();
+    for r in rows {
+        let entry = series
+            .entry(r.batcher_address.clone())
+            .or_insert_with(|| new_series(r.batcher_address.clone()));
+        push(entry, r);
+    }
+    Ok(series.into_values().collect())
+}
+
+/// Handler for the daily EIP-7918 extra cost endpoint (all batchers)
+pub async fn all_daily_eip_7918_extra_cost_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+) -> Result<Json<AllDailyEip7918ExtraCostResponse>, HandlerError> {
+    let batchers = daily_series(
+        &app_state,
+        &query,
+        |batcher_address| BatcherDailyEip7918ExtraCost {
+            batcher_address,
+            timestamps: Vec::new(),
+            total_eip_7918_extra_wei: Vec::new(),
+        },
+        |series, r| {
+            series.timestamps.push(r.snapshot_timestamp);
+            series
+                .total_eip_7918_extra_wei
+                .push(r.total_eip_7918_extra_wei);
+        },
+    )
+    .await?;
+
+    Ok(Json(AllDailyEip7918ExtraCostResponse { batchers }))
+}
+
+/// Handler for the daily byte profile endpoint (all batchers)
+pub async fn all_daily_byte_profile_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+    Query(pricing_query): Query<CalldataPricingQuery>,
+) -> Result<Json<AllDailyByteProfileResponse>, HandlerError> {
+    reject_calldata_pricing(pricing_query)?;
+    let batchers = daily_series(
+        &app_state,
+        &query,
+        |batcher_address| BatcherDailyByteProfile {
+            batcher_address,
+            timestamps: Vec::new(),
+            total_data_bytes: Vec::new(),
+            total_zero_bytes: Vec::new(),
+            total_data_tokens: Vec::new(),
+            floor_applied_txs: Vec::new(),
+        },
+        |series, r| {
+            series.timestamps.push(r.snapshot_timestamp);
+            series.total_data_bytes.push(r.total_data_bytes);
+            series.total_zero_bytes.push(r.total_zero_bytes);
+            series.total_data_tokens.push(r.total_data_tokens);
+            series.floor_applied_txs.push(r.floor_applied_txs);
+        },
+    )
+    .await?;
+
+    Ok(Json(AllDailyByteProfileResponse { batchers }))
+}
+
+/// Handler for the daily regret endpoint (all batchers)
+pub async fn all_daily_regret_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+    Query(pricing_query): Query<CalldataPricingQuery>,
+) -> Result<Json<AllDailyRegretResponse>, HandlerError> {
+    reject_calldata_pricing(pricing_query)?;
+    let batchers = daily_series(
+        &app_state,
+        &query,
+        |batcher_address| BatcherDailyRegret {
+            batcher_address,
+            timestamps: Vec::new(),
+            wrong_mode_txs: Vec::new(),
+            total_regret_wei: Vec::new(),
+            net_regret_wei: Vec::new(),
+        },
+        |series, r| {
+            series.timestamps.push(r.snapshot_timestamp);
+            series.wrong_mode_txs.push(r.wrong_mode_txs);
+            series.total_regret_wei.push(r.total_regret_wei);
+            series.net_regret_wei.push(r.net_regret_wei);
+        },
+    )
+    .await?;
+
+    Ok(Json(AllDailyRegretResponse { batchers }))
+}
+
+/// Handler for aggregated timing efficiency endpoint (all batchers)
+pub async fn all_timing_efficiency_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+) -> Result<Json<AllTimingEfficiencyResponse>, HandlerError> {
+    let batchers = app_state
+        .db
+        .get_all_timing_efficiency(query.start_timestamp, query.end_timestamp)
+        .await
+        .map_err(|e| {
+            HandlerError::DatabaseError(format!("Failed to get all timing efficiency: {}", e))
+        })?;
+
+    Ok(Json(AllTimingEfficiencyResponse { batchers }))
+}
+
+/// Handler for the daily timing endpoint (all batchers)
+pub async fn all_daily_timing_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+) -> Result<Json<AllDailyTimingResponse>, HandlerError> {
+    let batchers = daily_series(
+        &app_state,
+        &query,
+        |batcher_address| BatcherDailyTiming {
+            batcher_address,
+            timestamps: Vec::new(),
+            timed_txs: Vec::new(),
+            optimal_timing_txs: Vec::new(),
+            total_timing_premium_wei: Vec::new(),
+        },
+        |series, r| {
+            series.timestamps.push(r.snapshot_timestamp);
+            series.timed_txs.push(r.timed_txs);
+            series.optimal_timing_txs.push(r.optimal_timing_txs);
+            series
+                .total_timing_premium_wei
+                .push(r.total_timing_premium_wei);
+        },
+    )
+    .await?;
+
+    Ok(Json(AllDailyTimingResponse { batchers }))
+}
+
+/// Handler for the hourly break-even blob base fee endpoint
+pub async fn break_even_fees_handler(
+    State(app_state): State<super::AppState>,
+    Query(query): Query<AggregatedQuery>,
+) -> Result<Json<BreakEvenFeesResponse>, HandlerError> {
+    let points = app_state
+        .db
+        .get_hourly_break_even_fees(query.start_timestamp, query.end_timestamp)
+        .await
+        .map_err(|e| {
+            HandlerError::DatabaseError(format!("Failed to get break-even fees: {}", e))
+        })?;
+
+    Ok(Json(BreakEvenFeesResponse { points }))
+}
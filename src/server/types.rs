@@ -1,4 +1,10 @@
-use alloy_primitives::TxHash;
+use crate::{
+    compression::CompressionAlgorithm,
+    da::DaPriceUnit,
+    fork::{CalldataPricing, Fork},
+    price::Currency,
+};
+use alloy_primitives::{Address, B256, Bytes, TxHash};
 use rustc_hash::FxHashSet;
 use serde::{Deserialize, Serialize};
 
@@ -7,13 +13,397 @@ use serde::{Deserialize, Serialize};
 pub struct TxHashQuery {
     /// The transaction hash to analyze.
     pub tx_hash: String,
+    /// The blob encoding used to count the blobs a calldata transaction would need, as
+    /// `raw`, `op_stack` or `custom:<usable bytes per blob>`.
+    ///
+    /// If missing, the encoding of the batcher is guessed from the recipient.
+    pub blob_encoding: Option<String>,
+    /// The comma separated compressions to evaluate on the payload, each as `<algorithm>` or
+    /// `<algorithm>:<level>` with `zlib`, `brotli` or `zstd`.
+    pub compression: Option<String>,
+    /// The number of blocks before and after the inclusion block whose fees are compared with the
+    /// ones paid.
+    pub timing_window: Option<u64>,
 }
 
-/// Response structure for the tx handler.
+/// Query parameters of a hypothetical calldata pricing.
+///
+/// The named pricing is the base and each parameter overrides it. If only parameters are given,
+/// the base is the EIP-7623 pricing. If nothing is given, no hypothetical cost is computed.
+#[derive(Deserialize, Debug, Default)]
+pub struct CalldataPricingQuery {
+    /// The name of the pricing, `eip7623` or `eip7976`.
+    pub calldata_pricing: Option<String>,
+    /// The standard cost of a calldata token.
+    pub standard_token_cost: Option<u64>,
+    /// The floor cost of a calldata token.
+    pub floor_cost_per_token: Option<u64>,
+    /// The number of tokens of a zero byte.
+    pub zero_byte_tokens: Option<u64>,
+    /// The number of tokens of a non-zero byte.
+    pub non_zero_byte_tokens: Option<u64>,
+}
+
+/// Query parameter of the currency wei values are converted to.
+#[derive(Deserialize, Debug, Default)]
+pub struct CurrencyQuery {
+    /// The fiat currency, `usd`.
+    ///
+    /// If missing, only wei values are returned.
+    pub currency: Option<Currency>,
+}
+
+/// Fiat value of the costs of a transaction, at the ETH price of its block.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct FiatCost {
+    /// The fiat currency.
+    pub currency: Currency,
+    /// The price of one ETH.
+    pub eth_price: f64,
+    /// Fiat value of the gas and blob gas paid by the transaction.
+    pub total_spent: f64,
+    /// Fiat value of the blob data wei spent.
+    ///
+    /// None if the transaction happened before Cancun hard fork.
+    pub blob_data_spent: Option<f64>,
+    /// Fiat value of the blob data wei spent under the EIP-7918 reserve price.
+    ///
+    /// None if the transaction happened before Cancun hard fork.
+    pub eip_7918_blob_data_spent: Option<f64>,
+    /// Fiat value of the legacy calldata wei spent.
+    pub legacy_calldata_spent: f64,
+    /// Fiat value of the EIP-7623 calldata wei spent.
+    pub eip_7623_calldata_spent: f64,
+}
+
+/// Costs of the payload of a transaction after compression.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct CompressionAnalysis {
+    /// Size of the uncompressed payload: the calldata, or the blob payload of blob transactions.
+    pub payload_size: u64,
+    /// Costs of the compressed payload for each requested compression.
+    pub results: Vec<CompressedPayloadCost>,
+}
+
+/// Costs of a payload compressed with a given algorithm and level.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct CompressedPayloadCost {
+    /// The compression algorithm.
+    pub algorithm: CompressionAlgorithm,
+    /// The compression level.
+    pub level: u32,
+    /// Size of the compressed payload.
+    pub compressed_size: u64,
+    /// Size of the uncompressed payload divided by the size of the compressed one.
+    pub compression_ratio: f64,
+    /// Legacy calldata gas of the compressed payload.
+    pub legacy_calldata_gas: u64,
+    /// EIP-7623 calldata gas of the compressed payload.
+    pub eip_7623_calldata_gas: u64,
+    /// Legacy calldata wei spent on the compressed payload.
+    pub legacy_calldata_wei_spent: u128,
+    /// EIP-7623 calldata wei spent on the compressed payload.
+    pub eip_7623_calldata_wei_spent: u128,
+    /// Blob data wei spent on the compressed payload.
+    ///
+    /// None if the transaction happened before Cancun hard fork.
+    pub blob_data_wei_spent: Option<u128>,
+}
+
+/// Costs of the payload of a transaction on alternative data availability layers.
 #[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AltDaAnalysis {
+    /// Size of the payload: the calldata, or the blob payload of blob transactions.
+    pub payload_size: u64,
+    /// Costs of the payload on each layer with a rate at the timestamp of the transaction.
+    pub costs: Vec<AltDaCost>,
+}
+
+/// Cost of a payload on an alternative data availability layer.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AltDaCost {
+    /// The name of the layer.
+    pub layer: String,
+    /// The unit the layer charges for.
+    pub unit: DaPriceUnit,
+    /// The price of a unit at the timestamp of the transaction, in wei.
+    pub price_wei: u128,
+    /// Wei spent on the payload.
+    pub wei_spent: u128,
+}
+
+/// Calldata costs under a hypothetical pricing.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct HypotheticalCalldataCost {
+    /// The hypothetical pricing.
+    pub pricing: CalldataPricing,
+    /// Calldata gas at the standard token cost.
+    pub legacy_calldata_gas: u64,
+    /// Calldata gas at the floor token cost.
+    pub floor_calldata_gas: u64,
+    /// Calldata wei spent at the standard token cost.
+    pub legacy_calldata_wei_spent: u128,
+    /// Calldata wei spent at the floor token cost.
+    pub floor_calldata_wei_spent: u128,
+}
+
+/// Usage of a single blob.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BlobUsage {
+    /// The versioned hash of the blob.
+    pub versioned_hash: B256,
+    /// Payload bytes in the blob, without the trailing zero padding.
+    pub used_bytes: u64,
+    /// Payload bytes that fit in the blob.
+    pub capacity_bytes: u64,
+    /// Percentage of the capacity that is used.
+    pub utilization_pct: f64,
+    /// Wei paid for the unused capacity of the blob.
+    pub empty_space_wei_spent: u128,
+}
+
+/// Usage of the blobs of a transaction.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BlobUtilization {
+    /// Payload bytes in the blobs, without the trailing zero padding.
+    pub used_bytes: u64,
+    /// Payload bytes that fit in the blobs.
+    pub capacity_bytes: u64,
+    /// Percentage of the capacity that is used.
+    pub utilization_pct: f64,
+    /// Wei paid for the unused capacity of the blobs.
+    pub empty_space_wei_spent: u128,
+    /// Usage of each blob.
+    pub blobs: Vec<BlobUsage>,
+}
+
+/// Authorization list of an EIP-7702 transaction.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct AuthorizationAnalysis {
+    /// The number of authorizations in the list.
+    pub authorization_count: u64,
+    /// The number of authorizations that are valid and applied.
+    pub valid_authorization_count: u64,
+    /// The accounts signing the authorizations.
+    ///
+    /// None if the signature can't be recovered.
+    pub authorities: Vec<Option<Address>>,
+    /// The addresses the authorities delegate their code to.
+    pub delegated_addresses: Vec<Address>,
+    /// Gas charged for the authorizations in the intrinsic gas.
+    pub authorization_gas_charged: u64,
+    /// Gas refunded for the authorizations whose authority already exists.
+    ///
+    /// The refund is subject to the refund cap of the transaction.
+    pub authorization_gas_refunded: u64,
+    /// Gas spent on the calldata, at the floor price if the floor applies.
+    pub data_gas: u64,
+    /// Wei spent on the calldata.
+    pub data_wei_spent: u128,
+    /// Gas spent on the authorizations, net of the refund.
+    pub delegation_gas: u64,
+    /// Wei spent on the authorizations, net of the refund.
+    pub delegation_wei_spent: u128,
+    /// Percentage of the gas used spent on the calldata, 0 if no gas was used.
+    pub data_cost_pct: f64,
+    /// Percentage of the gas used spent on the authorizations, 0 if no gas was used.
+    pub delegation_cost_pct: f64,
+}
+
+/// Frames and channels of an OP Stack batch.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct OpStackBatch {
+    /// The number of frames in the transaction.
+    pub frame_count: u64,
+    /// The number of channels fully contained in the transaction.
+    pub channel_count: u64,
+    /// The number of channels with frames in other transactions, which are not decoded.
+    pub incomplete_channel_count: u64,
+}
+
+/// Header of an Arbitrum batch posted to the sequencer inbox.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ArbitrumBatch {
+    /// The sequence number of the batch in the sequencer inbox.
+    pub sequence_number: u64,
+    /// The number of L2 messages before the batch.
+    pub prev_message_count: u64,
+    /// The number of L2 messages after the batch.
+    pub new_message_count: u64,
+    /// The header byte of the batch data.
+    pub header_flag: u8,
+    /// Whether the batch content is brotli compressed.
+    pub brotli: bool,
+    /// Whether the batch content is kept by a data availability committee.
+    pub das: bool,
+    /// Whether the batch content is posted in blobs.
+    pub blob: bool,
+}
+
+/// Content of the L2 batch posted by a transaction.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct L2BatchSummary {
+    /// The rollup the batch belongs to.
+    pub rollup: String,
+    /// The number of L2 blocks in the batch.
+    ///
+    /// None if the batch content is not decoded.
+    pub l2_block_count: Option<u64>,
+    /// The number of L2 transactions in the batch.
+    ///
+    /// None if the batch content is not decoded.
+    pub l2_tx_count: Option<u64>,
+    /// Frames and channels of the batch.
+    ///
+    /// None if the batch is not an OP Stack batch.
+    pub op_stack: Option<OpStackBatch>,
+    /// Header of the batch.
+    ///
+    /// None if the batch is not an Arbitrum batch.
+    pub arbitrum: Option<ArbitrumBatch>,
+}
+
+/// Byte and token breakdown of some data priced as calldata.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataBreakdown {
+    /// Length of the data in bytes.
+    pub length: u64,
+    /// Number of zero bytes.
+    pub zero_bytes: u64,
+    /// Number of non-zero bytes.
+    pub non_zero_bytes: u64,
+    /// Number of calldata tokens: one per zero byte, 4 per non-zero byte.
+    pub tokens: u64,
+    /// Gas of the tokens at the standard token cost.
+    pub standard_token_gas: u64,
+    /// Gas of the tokens at the EIP-7623 floor cost.
+    ///
+    /// The floor applies when it exceeds the standard token gas plus the rest of the
+    /// transaction's gas.
+    pub floor_token_gas: u64,
+}
+
+/// Split of the fees paid by a transaction, and the max fees it bid.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    /// The base fee of the block, 0 before London.
+    pub base_fee_per_gas: u64,
+    /// The priority fee per gas paid to the builder: the effective gas price above the base fee.
+    pub priority_fee_per_gas: u128,
+    /// Wei burned by the base fee.
+    pub base_fee_wei_burned: u128,
+    /// Wei paid to the builder as priority fee.
+    pub priority_fee_wei_paid: u128,
+    /// Wei burned by the blob base fee, 0 for non-blob transactions.
+    pub blob_fee_wei_burned: u128,
+    /// The max fee per gas bid by the sender.
+    pub max_fee_per_gas: u128,
+    /// The max priority fee per gas bid by the sender.
+    ///
+    /// None for legacy and EIP-2930 transactions, whose gas price is entirely bid.
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// The max fee per blob gas bid by the sender.
+    ///
+    /// None if the transaction is not a blob transaction.
+    pub max_fee_per_blob_gas: Option<u128>,
+    /// The max fee per blob gas above the blob base fee charged.
+    ///
+    /// None if the transaction is not a blob transaction.
+    pub blob_fee_overbid_per_gas: Option<u128>,
+}
+
+/// The way a transaction posts its data.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PostingMode {
+    /// In blobs.
+    Blob,
+    /// In the calldata.
+    Calldata,
+}
+
+/// Split of the gas used by a transaction, and of its blob gas, with the wei paid for each part.
+///
+/// The intrinsic, calldata and execution gas add up to the gas used.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasBreakdown {
+    /// Intrinsic gas other than the calldata: base stipend, contract creation, access list and
+    /// authorizations.
+    pub intrinsic_gas: u64,
+    /// Wei paid for the intrinsic gas.
+    pub intrinsic_wei: u128,
+    /// Gas paid for the calldata of the transaction, at the standard token cost or at the floor
+    /// when the EIP-7623 floor sets the gas used.
+    pub calldata_gas: u64,
+    /// Wei paid for the calldata gas.
+    pub calldata_wei: u128,
+    /// Gas used by the execution, net of refunds. Zero when the floor sets the gas used.
+    pub execution_gas: u64,
+    /// Wei paid for the execution gas.
+    pub execution_wei: u128,
+    /// Blob gas used by the transaction.
+    pub blob_gas: u64,
+    /// Wei paid for the blob gas.
+    pub blob_wei: u128,
+}
+
+/// Cost of a transaction against the cheapest block of a window around its inclusion.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimingEfficiency {
+    /// Number of blocks before and after the inclusion block in the window.
+    pub window_blocks: u64,
+    /// Number of blocks of the window whose fees were read, fewer when the window reaches past
+    /// the latest block.
+    pub blocks_sampled: u64,
+    /// The first block of the window whose fees were read.
+    pub first_block_number: u64,
+    /// The last block of the window whose fees were read.
+    pub last_block_number: u64,
+    /// Whether all the blocks of the window were read, on both sides of the inclusion block.
+    pub complete: bool,
+    /// Wei paid for the gas and the blob gas at the inclusion block.
+    pub actual_wei: u128,
+    /// The block of the window where the same gas and blob gas would have cost the least.
+    pub cheapest_block_number: u64,
+    /// Execution base fee of the cheapest block.
+    pub cheapest_base_fee_per_gas: u64,
+    /// Blob base fee of the cheapest block, None before Cancun hard fork.
+    pub cheapest_blob_base_fee: Option<u128>,
+    /// Wei the transaction would have paid at the cheapest block, with the same priority fee.
+    pub cheapest_wei: u128,
+    /// Wei paid above the cost at the cheapest block.
+    pub timing_premium_wei: u128,
+}
+
+/// Cost of the posting mode chosen by a transaction against the other mode.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeRegret {
+    /// The posting mode of the transaction.
+    pub chosen_mode: PostingMode,
+    /// Wei spent on the data in the chosen mode: the blob data and the calldata paid next to it,
+    /// or the EIP-7623 calldata.
+    pub chosen_mode_wei: u128,
+    /// Wei the data would have cost in the other mode.
+    ///
+    /// A blob transaction would post its calldata and its blob data as calldata, at the floor.
+    pub alternative_mode_wei: u128,
+    /// The chosen mode cost minus the alternative mode cost.
+    ///
+    /// Positive when the other mode was cheaper, negative when the chosen one was.
+    pub regret_wei: i128,
+}
+
+/// Response structure for the tx handler.
+#[derive(Serialize, Debug, PartialEq)]
 pub struct TxAnalysisResponse {
     /// The timestamp of the transaction.
     pub timestamp: u64,
+    /// The block number of the transaction.
+    pub block_number: u64,
+    /// Whether the transaction succeeded, as reported by its receipt.
+    ///
+    /// The gas and blob gas of a reverted transaction are paid, but its calldata is not processed.
+    pub success: bool,
     /// Total gas used by the transaction.
     pub gas_used: u64,
     /// Gas price used by the transaction.
@@ -28,14 +418,275 @@ pub struct TxAnalysisResponse {
     pub eip_7623_calldata_gas: u64,
     /// Legacy calldata gas.
     pub legacy_calldata_gas: u64,
+    /// Zero bytes of the data priced as calldata: the calldata, or the blobs of blob transactions.
+    pub zero_bytes: u64,
+    /// Non-zero bytes of the data priced as calldata.
+    pub non_zero_bytes: u64,
+    /// Byte and token breakdown of the calldata of the transaction.
+    pub calldata: DataBreakdown,
+    /// Byte and token breakdown of the payload of the blobs.
+    ///
+    /// None if the transaction is not a blob transaction.
+    pub blob_payload: Option<DataBreakdown>,
+    /// Split of the fees paid into base fee, priority fee and blob fee, with the max fees bid.
+    pub fees: FeeBreakdown,
+    /// Split of the gas into intrinsic, calldata, execution and blob gas, with their wei cost.
+    pub gas_breakdown: GasBreakdown,
+    /// Cost of the posting mode against the other one.
+    ///
+    /// None if the transaction happened before Cancun hard fork.
+    pub regret: Option<ModeRegret>,
+    /// Blob base fee above which a typical batch costs less as EIP-7623 calldata than as a blob,
+    /// at the base fee of the block.
+    ///
+    /// None if the transaction happened before Cancun hard fork.
+    pub break_even_blob_base_fee: Option<u128>,
+    /// Cost against the cheapest block of a window around the inclusion block.
+    ///
+    /// None if no timing window is requested.
+    pub timing: Option<TimingEfficiency>,
     /// Blob data wei spent.
     ///
     /// None if the transaction happened before Cancun hard fork.
     pub blob_data_wei_spent: Option<u128>,
+    /// Blob gas price under the EIP-7918 reserve price.
+    ///
+    /// None if the transaction happened before Cancun hard fork.
+    pub eip_7918_blob_gas_price: Option<u128>,
+    /// Blob data wei spent under the EIP-7918 reserve price.
+    ///
+    /// None if the transaction happened before Cancun hard fork.
+    pub eip_7918_blob_data_wei_spent: Option<u128>,
     /// Legacy calldata wei spent.
     pub legacy_calldata_wei_spent: u128,
     /// EIP-7623 calldata wei spent.
     pub eip_7623_calldata_wei_spent: u128,
+    /// Costs of the payload on alternative data availability layers.
+    ///
+    /// None if no price table is configured.
+    pub alt_da: Option<AltDaAnalysis>,
+    /// Standard intrinsic gas: base stipend, calldata, access list, initcode and authorizations.
+    pub standard_intrinsic_gas: u64,
+    /// EIP-7623 floor gas: base stipend plus floor-priced calldata tokens.
+    pub floor_gas: u64,
+    /// Gas used on top of the standard intrinsic gas, net of refunds.
+    pub execution_gas: u64,
+    /// Whether the EIP-7623 floor determines the gas used, or would have before Prague.
+    pub floor_applied: bool,
+    /// Usage of the blobs.
+    ///
+    /// None if the transaction is not a blob transaction.
+    pub blob_utilization: Option<BlobUtilization>,
+    /// Content of the L2 batch.
+    ///
+    /// None if the transaction is not sent to a known batch inbox or the batch can't be decoded.
+    pub l2_batch: Option<L2BatchSummary>,
+    /// Authorization list.
+    ///
+    /// None if the transaction is not an EIP-7702 transaction.
+    pub authorization: Option<AuthorizationAnalysis>,
+    /// Calldata costs under the hypothetical pricing of the query.
+    ///
+    /// None if no hypothetical pricing is requested.
+    pub hypothetical_calldata_cost: Option<HypotheticalCalldataCost>,
+    /// Costs of the compressed payload.
+    ///
+    /// None if no compression is requested.
+    pub compression: Option<CompressionAnalysis>,
+    /// Fiat value of the costs.
+    ///
+    /// None if no currency is requested.
+    pub fiat: Option<FiatCost>,
+}
+
+/// Execution of a transaction under the rules of a specific fork.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct SimulatedExecution {
+    /// Whether the transaction succeeded.
+    pub success: bool,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+    /// Effective gas price paid by the transaction.
+    pub gas_price: u128,
+    /// Blob gas used by the transaction.
+    pub blob_gas_used: u64,
+    /// Blob gas price under the fork's update fraction.
+    ///
+    /// None if the block has no excess blob gas.
+    pub blob_gas_price: Option<u128>,
+    /// Total wei spent on gas and blob gas.
+    pub wei_spent: u128,
+}
+
+/// Response structure for the tx simulation handler.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct SimulationResponse {
+    /// The timestamp of the transaction.
+    pub timestamp: u64,
+    /// Execution under Cancun rules.
+    ///
+    /// None for the transaction types Cancun doesn't know, e.g. EIP-7702 transactions.
+    pub cancun: Option<SimulatedExecution>,
+    /// Execution under Prague rules.
+    pub prague: SimulatedExecution,
+    /// Gas used under Prague minus gas used under Cancun, None without Cancun execution.
+    pub gas_difference: Option<i64>,
+    /// Wei spent under Prague minus wei spent under Cancun, None without Cancun execution.
+    pub wei_difference: Option<i128>,
+}
+
+/// Query parameters for the blob fee projection handler.
+#[derive(Deserialize, Debug)]
+pub struct BlobFeeProjectionQuery {
+    /// The number of blobs in each projected block.
+    pub blobs_per_block: u64,
+    /// The number of blocks to project.
+    pub blocks: u64,
+    /// The excess blob gas of the first projected block.
+    ///
+    /// If missing, the block after the latest one is projected first.
+    pub excess_blob_gas: Option<u64>,
+}
+
+/// Blob base fee of a projected block.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BlobFeeProjection {
+    /// The position of the block in the projection.
+    pub block_offset: u64,
+    /// The excess blob gas of the block.
+    pub excess_blob_gas: u64,
+    /// The number of blobs in the block.
+    pub blob_count: u64,
+    /// The blob base fee of the block.
+    pub blob_base_fee: u128,
+}
+
+/// Request body of the estimate handler.
+///
+/// Exactly one of `calldata` and `blob_payload` must be given.
+#[derive(Deserialize, Debug, Default)]
+pub struct EstimateRequest {
+    /// The calldata to price.
+    pub calldata: Option<Bytes>,
+    /// The payload to post in blobs.
+    pub blob_payload: Option<Bytes>,
+    /// The gas price. If missing, no gas is priced in wei.
+    pub gas_price: Option<u128>,
+    /// The blob base fee. If missing, it's derived from `excess_blob_gas`.
+    pub blob_base_fee: Option<u128>,
+    /// The excess blob gas of the block, used if `blob_base_fee` is missing.
+    pub excess_blob_gas: Option<u64>,
+    /// The fork whose rules are used.
+    ///
+    /// If missing, it's the fork active at `block_number` and `timestamp`, or the latest fork.
+    pub fork: Option<Fork>,
+    /// The block number used to select the fork.
+    pub block_number: Option<u64>,
+    /// The block timestamp used to select the fork.
+    pub timestamp: Option<u64>,
+    /// The encoding used to count the blobs, `raw` if missing.
+    pub blob_encoding: Option<String>,
+}
+
+/// The block and fee context of a raw transaction, given by the caller instead of read on chain.
+#[derive(Deserialize, Debug, Default)]
+pub struct RawTxContext {
+    /// The gas used by the transaction.
+    ///
+    /// If missing, the transaction is assumed not to execute: it uses its intrinsic gas, at least
+    /// the EIP-7623 floor from prague.
+    pub gas_used: Option<u64>,
+    /// The base fee of the block, used for the effective gas price and the EIP-7918 reserve
+    /// price. If missing, the gas price is the maximum fee of the transaction.
+    pub base_fee_per_gas: Option<u64>,
+    /// The blob base fee. If missing, it's derived from `excess_blob_gas`.
+    ///
+    /// Required for blob transactions.
+    pub blob_base_fee: Option<u128>,
+    /// The excess blob gas of the block, used if `blob_base_fee` is missing.
+    pub excess_blob_gas: Option<u64>,
+    /// The fork whose rules are used.
+    ///
+    /// If missing, it's the fork active at `block_number` and `timestamp`, or the latest fork.
+    pub fork: Option<Fork>,
+    /// The block number, used to select the fork. 0 if missing.
+    pub block_number: Option<u64>,
+    /// Whether the transaction succeeded. True if missing.
+    pub success: Option<bool>,
+    /// The block timestamp, used to select the fork. 0 if missing.
+    pub timestamp: Option<u64>,
+}
+
+/// Request body of the raw tx handler.
+#[derive(Deserialize, Debug)]
+pub struct RawTxAnalysisRequest {
+    /// The EIP-2718 encoded signed transaction, with the sidecar for blob transactions.
+    pub raw_tx: Bytes,
+    /// The block and fee context of the transaction.
+    #[serde(flatten)]
+    pub context: RawTxContext,
+    /// The encoding used to count the blobs a calldata transaction would need.
+    pub blob_encoding: Option<String>,
+    /// Comma-separated compressions to apply to the payload.
+    pub compression: Option<String>,
+}
+
+/// Response structure for the estimate handler.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct EstimateResponse {
+    /// The fork whose rules are used.
+    pub fork: Fork,
+    /// Size of the data.
+    pub data_size: u64,
+    /// Zero bytes of the data.
+    pub zero_bytes: u64,
+    /// Non-zero bytes of the data.
+    pub non_zero_bytes: u64,
+    /// Legacy calldata gas.
+    pub legacy_calldata_gas: u64,
+    /// EIP-7623 calldata gas.
+    pub eip_7623_calldata_gas: u64,
+    /// Gas used by a transaction carrying the data as calldata, without execution.
+    pub calldata_tx_gas: u64,
+    /// Legacy calldata wei spent.
+    ///
+    /// None if no gas price is given.
+    pub legacy_calldata_wei_spent: Option<u128>,
+    /// EIP-7623 calldata wei spent.
+    ///
+    /// None if no gas price is given.
+    pub eip_7623_calldata_wei_spent: Option<u128>,
+    /// Wei spent by a transaction carrying the data as calldata.
+    ///
+    /// None if no gas price is given.
+    pub calldata_tx_wei_spent: Option<u128>,
+    /// The number of blobs needed to carry the data.
+    pub blob_count: u64,
+    /// Blob gas used by the blobs.
+    pub blob_gas_used: u64,
+    /// The blob base fee.
+    ///
+    /// None if blobs are not active at the fork or no blob base fee can be derived.
+    pub blob_gas_price: Option<u128>,
+    /// Blob data wei spent.
+    ///
+    /// None if no blob base fee is known.
+    pub blob_data_wei_spent: Option<u128>,
+    /// Wei spent by a blob transaction carrying the data, including its base gas.
+    ///
+    /// None if no gas price or blob base fee is known.
+    pub blob_tx_wei_spent: Option<u128>,
+}
+
+/// Response structure for the blob fee projection handler.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BlobFeeProjectionResponse {
+    /// The fork whose blob parameters are used.
+    pub fork: Fork,
+    /// The blob base fee update fraction of the fork.
+    pub base_fee_update_fraction: u64,
+    /// The projected blocks.
+    pub projections: Vec<BlobFeeProjection>,
 }
 
 /// Query parameters for the contract handler.
@@ -151,17 +802,27 @@ pub struct AllDailyTxsResponse {
 }
 
 /// Individual batcher data for ETH saved.
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Debug, PartialEq)]
 pub struct BatcherEthSaved {
     /// The batcher address.
     pub batcher_address: String,
     /// Total ETH saved in wei.
     pub total_eth_saved_wei: u128,
+    /// Total ETH saved in wei if calldata followed the hypothetical pricing of the query.
+    ///
+    /// None if no hypothetical pricing is requested.
+    pub hypothetical_eth_saved_wei: Option<u128>,
+    /// Total fiat value of the ETH saved, at the ETH price of each batch.
+    ///
+    /// None if no currency is requested or a batch has no price.
+    pub total_eth_saved_fiat: Option<f64>,
 }
 
 /// Response structure for aggregated ETH saved endpoint.
-#[derive(Serialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Debug, PartialEq)]
 pub struct AllEthSavedResponse {
+    /// The currency of the fiat values, if requested.
+    pub currency: Option<Currency>,
     /// List of batcher ETH saved data.
     pub batchers: Vec<BatcherEthSaved>,
 }
@@ -189,6 +850,10 @@ pub struct BatcherPectraDataGas {
     pub batcher_address: String,
     /// Total Pectra (EIP-7623) calldata gas used.
     pub total_pectra_data_gas: u64,
+    /// Total calldata gas at the floor token cost of the hypothetical pricing of the query.
+    ///
+    /// None if no hypothetical pricing is requested.
+    pub hypothetical_pectra_data_gas: Option<u64>,
 }
 
 /// Response structure for aggregated Pectra data gas endpoint.
@@ -198,6 +863,479 @@ pub struct AllPectraDataGasResponse {
     pub batchers: Vec<BatcherPectraDataGas>,
 }
 
+/// Individual batcher data for blob utilization.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct BatcherBlobUtilization {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Total payload bytes posted in blobs.
+    pub total_used_bytes: u64,
+    /// Total payload bytes that fit in the posted blobs.
+    pub total_capacity_bytes: u64,
+    /// Average fill rate of the posted blobs.
+    pub avg_utilization_pct: f64,
+    /// Total wei paid for the unused capacity of the blobs.
+    pub total_empty_space_wei_spent: u128,
+}
+
+/// Response structure for aggregated blob utilization endpoint.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AllBlobUtilizationResponse {
+    /// List of batcher blob utilization.
+    pub batchers: Vec<BatcherBlobUtilization>,
+}
+
+/// Individual batcher data for L1 cost per L2 transaction.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct BatcherL2TxCost {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Total L2 transactions in the decoded batches.
+    pub total_l2_tx_count: u64,
+    /// Total wei spent on gas and blob gas by the decoded batches.
+    pub total_l1_wei_spent: u128,
+    /// Average wei spent on L1 per L2 transaction.
+    pub avg_l1_wei_per_l2_tx: u128,
+    /// Total fiat value spent on gas and blob gas, at the ETH price of each batch.
+    ///
+    /// None if no currency is requested or a batch has no price.
+    pub total_l1_fiat_spent: Option<f64>,
+    /// Average fiat value spent on L1 per L2 transaction.
+    ///
+    /// None if no currency is requested or a batch has no price.
+    pub avg_l1_fiat_per_l2_tx: Option<f64>,
+}
+
+/// Response structure for aggregated L1 cost per L2 transaction endpoint.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AllL2TxCostResponse {
+    /// The currency of the fiat values, if requested.
+    pub currency: Option<Currency>,
+    /// List of batcher L1 cost per L2 transaction.
+    pub batchers: Vec<BatcherL2TxCost>,
+}
+
+/// Individual batcher data for a compression of the payload.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct BatcherCompression {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// The compression algorithm.
+    pub algorithm: CompressionAlgorithm,
+    /// The compression level.
+    pub level: u32,
+    /// The number of batches whose payload was compressed.
+    pub batch_count: u64,
+    /// Total size of the uncompressed payloads.
+    pub total_payload_size: u64,
+    /// Total size of the compressed payloads.
+    pub total_compressed_size: u64,
+    /// Total size of the uncompressed payloads divided by the total size of the compressed ones.
+    pub compression_ratio: f64,
+    /// Total EIP-7623 calldata wei spent on the compressed payloads.
+    pub total_eip_7623_calldata_wei_spent: u128,
+    /// Total blob data wei spent on the compressed payloads.
+    pub total_blob_data_wei_spent: u128,
+    /// Total fiat value of the EIP-7623 calldata wei spent, at the ETH price of each batch.
+    ///
+    /// None if no currency is requested or a batch has no price.
+    pub total_eip_7623_calldata_fiat_spent: Option<f64>,
+    /// Total fiat value of the blob data wei spent, at the ETH price of each batch.
+    ///
+    /// None if no currency is requested or a batch has no price.
+    pub total_blob_data_fiat_spent: Option<f64>,
+}
+
+/// Individual batcher data for an alternative data availability layer.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherAltDaCost {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// The name of the layer.
+    pub layer: String,
+    /// The number of batches priced on the layer.
+    pub batch_count: u64,
+    /// Total size of the payloads.
+    pub total_payload_size: u64,
+    /// Total wei the payloads would have cost on the layer.
+    pub total_alt_da_wei_spent: u128,
+    /// Total blob data wei spent on the data of the same batches.
+    pub total_blob_data_wei_spent: u128,
+    /// Total EIP-7623 calldata wei spent on the data of the same batches.
+    pub total_eip_7623_calldata_wei_spent: u128,
+}
+
+/// Response structure for aggregated alternative data availability cost endpoint.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AllAltDaCostResponse {
+    /// List of batcher costs per layer.
+    pub batchers: Vec<BatcherAltDaCost>,
+}
+
+/// Query parameter of the blob sharing simulation.
+#[derive(Deserialize, Debug, Default)]
+pub struct BlobSharingQuery {
+    /// The number of blocks whose batches are regrouped together.
+    ///
+    /// If missing, the batches of the same block are regrouped.
+    pub window_blocks: Option<u64>,
+}
+
+/// Individual batcher data for the blob sharing simulation.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherBlobSharing {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// The number of blob batches.
+    pub batch_count: u64,
+    /// The number of batches regrouped with the batches of other batchers.
+    pub shared_batch_count: u64,
+    /// Payload bytes in the blobs, without the trailing zero padding.
+    pub used_bytes: u64,
+    /// The number of blobs posted.
+    pub blob_count: u64,
+    /// Wei paid for the posted blobs.
+    pub blob_wei_spent: u128,
+    /// Share of the cost of the shared blobs, pro rata of the payload bytes, and the cost of the
+    /// blobs posted alone in their window.
+    pub shared_blob_wei_spent: u128,
+    /// Wei paid for the posted blobs minus the share of the shared blobs, negative if sharing
+    /// would have cost more.
+    pub savings_wei: i128,
+    /// Wei the batcher would have saved by packing its own batches of the windows it posts
+    /// alone, not counted in the savings.
+    pub self_aggregation_savings_wei: i128,
+}
+
+/// Response structure for the blob sharing simulation endpoint.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BlobSharingSimulation {
+    /// The number of blocks whose batches are regrouped together.
+    pub window_blocks: u64,
+    /// The number of windows with blob batches.
+    pub window_count: u64,
+    /// The number of windows with the batches of more than one batcher.
+    pub shared_window_count: u64,
+    /// The number of blobs posted.
+    pub blob_count: u64,
+    /// The number of shared blobs the payloads fill, with the blobs posted alone in their window.
+    pub shared_blob_count: u64,
+    /// Wei paid for the posted blobs.
+    pub total_blob_wei_spent: u128,
+    /// Wei the shared blobs would have cost, with the blobs posted alone in their window.
+    pub total_shared_blob_wei_spent: u128,
+    /// Wei paid for the posted blobs minus the cost of the shared blobs.
+    pub total_savings_wei: i128,
+    /// Wei the batchers would have saved by packing their own batches of the windows they post
+    /// alone, not counted in the savings.
+    pub total_self_aggregation_savings_wei: i128,
+    /// List of batcher savings.
+    pub batchers: Vec<BatcherBlobSharing>,
+}
+
+/// Response structure for aggregated compression endpoint.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AllCompressionResponse {
+    /// The currency of the fiat values, if requested.
+    pub currency: Option<Currency>,
+    /// List of batcher compression data.
+    pub batchers: Vec<BatcherCompression>,
+}
+
+/// Individual batcher data for the EIP-7918 reserve price counterfactual.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherEip7918ExtraCost {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Extra wei the blob batches would have paid under the EIP-7918 reserve price.
+    pub total_eip_7918_extra_wei: u128,
+}
+
+/// Daily series of the EIP-7918 reserve price counterfactual for a batcher.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherDailyEip7918ExtraCost {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Timestamps of the snapshots (Unix timestamp, UTC, aligned at midnight).
+    pub timestamps: Vec<i64>,
+    /// Extra wei the blob batches would have paid under the EIP-7918 reserve price each day.
+    ///
+    /// None for the snapshots taken before the counterfactual was computed.
+    pub total_eip_7918_extra_wei: Vec<Option<u128>>,
+}
+
+/// Response structure for the daily EIP-7918 extra cost endpoint.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AllDailyEip7918ExtraCostResponse {
+    /// List of batcher daily EIP-7918 extra cost.
+    pub batchers: Vec<BatcherDailyEip7918ExtraCost>,
+}
+
+/// Individual batcher data for the regret of the posting mode.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherRegret {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Number of batches with both a blob and a calldata cost.
+    pub batch_count: u64,
+    /// Number of batches posted in the more expensive mode.
+    pub wrong_mode_txs: u64,
+    /// Total wei overpaid by the batches posted in the more expensive mode.
+    pub total_regret_wei: u128,
+    /// Sum of the signed regrets: negative when the choices saved more than they overpaid.
+    pub net_regret_wei: i128,
+}
+
+/// A batch posted in the more expensive mode.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct WrongModeBatch {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// The transaction hash of the batch.
+    pub tx_hash: String,
+    /// The timestamp of the batch.
+    pub timestamp: i64,
+    /// The posting mode of the batch.
+    pub chosen_mode: PostingMode,
+    /// Wei spent on the data in the chosen mode.
+    pub chosen_mode_wei: u128,
+    /// Wei the data would have cost in the other mode.
+    pub alternative_mode_wei: u128,
+    /// Wei overpaid: the chosen mode cost minus the alternative mode cost.
+    pub regret_wei: u128,
+}
+
+/// Response structure for the wrong mode batches endpoint.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AllWrongModeBatchesResponse {
+    /// Batches posted in the more expensive mode, the most expensive mistakes first.
+    pub batches: Vec<WrongModeBatch>,
+}
+
+/// Daily series of the regret of the posting mode for a batcher.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherDailyRegret {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Timestamps of the snapshots (Unix timestamp, UTC, aligned at midnight).
+    pub timestamps: Vec<i64>,
+    /// Number of batches posted in the more expensive mode each day.
+    ///
+    /// None for the snapshots taken before the regret was computed, as for the other series.
+    pub wrong_mode_txs: Vec<Option<u64>>,
+    /// Total wei overpaid by the batches posted in the more expensive mode each day.
+    pub total_regret_wei: Vec<Option<u128>>,
+    /// Sum of the signed regrets each day.
+    pub net_regret_wei: Vec<Option<i128>>,
+}
+
+/// Response structure for the daily regret endpoint.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AllDailyRegretResponse {
+    /// List of batcher daily regrets.
+    pub batchers: Vec<BatcherDailyRegret>,
+}
+
+/// Individual batcher data for the timing of the batches.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherTimingEfficiency {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Number of batches compared with the blocks of their timing window.
+    pub timed_txs: u64,
+    /// Number of batches included at the cheapest block of their window.
+    pub optimal_timing_txs: u64,
+    /// Total wei paid for the gas and blob gas of the batches.
+    pub total_actual_wei: u128,
+    /// Total wei the batches would have paid at the cheapest block of their window.
+    pub total_cheapest_wei: u128,
+    /// Total wei paid above the cost at the cheapest block of the window.
+    pub total_timing_premium_wei: u128,
+}
+
+/// Response structure for the aggregated timing efficiency endpoint.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AllTimingEfficiencyResponse {
+    /// List of batcher timing efficiencies.
+    pub batchers: Vec<BatcherTimingEfficiency>,
+}
+
+/// Daily series of the timing of the batches for a batcher.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherDailyTiming {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Timestamps of the snapshots (Unix timestamp, UTC, aligned at midnight).
+    pub timestamps: Vec<i64>,
+    /// Number of batches compared with the blocks of their timing window each day.
+    ///
+    /// None for the snapshots taken before the timing was analyzed, as for the other series.
+    pub timed_txs: Vec<Option<u64>>,
+    /// Number of batches included at the cheapest block of their window each day.
+    pub optimal_timing_txs: Vec<Option<u64>>,
+    /// Total wei paid above the cost at the cheapest block of the window each day.
+    pub total_timing_premium_wei: Vec<Option<u128>>,
+}
+
+/// Response structure for the daily timing endpoint.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AllDailyTimingResponse {
+    /// List of batcher daily timings.
+    pub batchers: Vec<BatcherDailyTiming>,
+}
+
+/// The break-even blob base fee sampled at a block.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct BreakEvenFee {
+    /// The block number.
+    pub block_number: u64,
+    /// The block timestamp.
+    pub timestamp: i64,
+    /// The execution base fee of the block.
+    pub base_fee_per_gas: u64,
+    /// The blob base fee of the block.
+    pub blob_base_fee: u128,
+    /// Blob base fee above which a typical batch costs less as EIP-7623 calldata than as a blob.
+    pub break_even_blob_base_fee: u128,
+}
+
+/// A batch posted in the mode that was more expensive for a typical batch at its block.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct WrongSideBatch {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// The transaction hash of the batch.
+    pub tx_hash: String,
+    /// The block timestamp of the batch.
+    pub timestamp: i64,
+    /// The posting mode of the batch.
+    pub chosen_mode: PostingMode,
+    /// The blob base fee of the block of the batch.
+    pub blob_base_fee: u128,
+    /// The break-even blob base fee at the block of the batch.
+    pub break_even_blob_base_fee: u128,
+}
+
+/// The break-even blob base fee over an hour.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct HourlyBreakEvenFee {
+    /// The start of the hour (Unix timestamp, UTC).
+    pub timestamp: i64,
+    /// Number of blocks sampled in the hour.
+    pub samples: u64,
+    /// Average execution base fee of the sampled blocks.
+    pub base_fee_per_gas: u64,
+    /// Average blob base fee of the sampled blocks.
+    pub blob_base_fee: u128,
+    /// Average break-even blob base fee of the sampled blocks.
+    pub break_even_blob_base_fee: u128,
+    /// The mode a typical batch was cheaper in, on average over the hour.
+    pub cheaper_mode: PostingMode,
+    /// Tracked batches of the hour posted on the wrong side of the break-even blob base fee.
+    pub wrong_side_batches: Vec<WrongSideBatch>,
+}
+
+/// Response structure for the break-even fee endpoint.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BreakEvenFeesResponse {
+    /// The hourly points, in chronological order.
+    pub points: Vec<HourlyBreakEvenFee>,
+}
+
+/// Individual batcher data for the spend on reverted batch transactions.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherWastedSpend {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Number of reverted batch transactions.
+    pub reverted_tx_count: u64,
+    /// Total wei burned on gas and blob gas by the reverted batch transactions.
+    pub total_wei_burned: u128,
+}
+
+/// Response structure for the aggregated wasted spend endpoint.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AllWastedSpendResponse {
+    /// List of batcher wasted spend.
+    pub batchers: Vec<BatcherWastedSpend>,
+}
+
+/// Individual batcher data for the split of the fees paid.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct BatcherFeeBreakdown {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Number of batches with a fee breakdown.
+    pub batch_count: u64,
+    /// Total wei burned by the base fee.
+    pub total_base_fee_wei_burned: u128,
+    /// Total wei paid to the builders as priority fee.
+    pub total_priority_fee_wei_paid: u128,
+    /// Total wei burned by the blob base fee.
+    pub total_blob_fee_wei_burned: u128,
+    /// Share of the total fees paid as priority fee, in percent.
+    pub priority_fee_pct: f64,
+    /// Average priority fee per gas, weighted by the gas used.
+    pub avg_priority_fee_per_gas: u128,
+    /// Total max fee per blob gas bid over the total blob base fee charged, weighted by the blob
+    /// gas used.
+    ///
+    /// None if the batcher posted no blob batch.
+    pub blob_fee_bid_ratio: Option<f64>,
+}
+
+/// Response structure for the aggregated fee breakdown endpoint.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AllFeeBreakdownResponse {
+    /// List of batcher fee breakdowns.
+    pub batchers: Vec<BatcherFeeBreakdown>,
+}
+
+/// Individual batcher data for the byte profile of the posted data.
+///
+/// The posted data is the payload of the blobs for blob batches and the calldata otherwise.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherByteProfile {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Number of batches with a byte breakdown.
+    pub batch_count: u64,
+    /// Total length of the posted data.
+    pub total_data_bytes: u64,
+    /// Total zero bytes of the posted data.
+    pub total_zero_bytes: u64,
+    /// Total calldata tokens of the posted data.
+    pub total_data_tokens: u64,
+    /// Number of batches whose gas used is set by the EIP-7623 floor.
+    pub floor_applied_txs: u64,
+}
+
+/// Daily series of the byte profile of the posted data for a batcher.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct BatcherDailyByteProfile {
+    /// The batcher address.
+    pub batcher_address: String,
+    /// Timestamps of the snapshots (Unix timestamp, UTC, aligned at midnight).
+    pub timestamps: Vec<i64>,
+    /// Total length of the posted data each day.
+    ///
+    /// None for the snapshots taken before the byte profile was recorded, as for the other series.
+    pub total_data_bytes: Vec<Option<u64>>,
+    /// Total zero bytes of the posted data each day.
+    pub total_zero_bytes: Vec<Option<u64>>,
+    /// Total calldata tokens of the posted data each day.
+    pub total_data_tokens: Vec<Option<u64>>,
+    /// Number of batches whose gas used is set by the EIP-7623 floor each day.
+    pub floor_applied_txs: Vec<Option<u64>>,
+}
+
+/// Response structure for the daily byte profile endpoint.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct AllDailyByteProfileResponse {
+    /// List of batcher daily byte profiles.
+    pub batchers: Vec<BatcherDailyByteProfile>,
+}
+
 /// Snapshot of daily aggregated metrics per batcher (previous 24-hour window).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct DailyBatcherStats {
@@ -213,6 +1351,36 @@ pub struct DailyBatcherStats {
     pub total_blob_data_gas: u64,
     /// Total Pectra (EIP-7623) calldata gas used.
     pub total_pectra_data_gas: u64,
+    /// Extra wei the blob batches would have paid under the EIP-7918 reserve price.
+    ///
+    /// None for the snapshots taken before the counterfactual was computed.
+    pub total_eip_7918_extra_wei: Option<u128>,
+    /// Total length of the posted data.
+    ///
+    /// None for the snapshots taken before the byte profile was recorded, as for the fields below.
+    pub total_data_bytes: Option<u64>,
+    /// Total zero bytes of the posted data.
+    pub total_zero_bytes: Option<u64>,
+    /// Total calldata tokens of the posted data.
+    pub total_data_tokens: Option<u64>,
+    /// Number of batches whose gas used is set by the EIP-7623 floor.
+    pub floor_applied_txs: Option<u64>,
+    /// Number of batches posted in the more expensive mode.
+    ///
+    /// None for the snapshots taken before the regret was computed, as for the fields below.
+    pub wrong_mode_txs: Option<u64>,
+    /// Total wei overpaid by the batches posted in the more expensive mode.
+    pub total_regret_wei: Option<u128>,
+    /// Sum of the signed regrets.
+    pub net_regret_wei: Option<i128>,
+    /// Number of batches compared with the blocks of their timing window.
+    ///
+    /// None for the snapshots taken before the timing was analyzed, as for the fields below.
+    pub timed_txs: Option<u64>,
+    /// Number of batches included at the cheapest block of their window.
+    pub optimal_timing_txs: Option<u64>,
+    /// Total wei paid above the cost at the cheapest block of the window.
+    pub total_timing_premium_wei: Option<u128>,
 }
 
 /// Recent daily statistics (series) for a batcher.
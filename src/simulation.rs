@@ -0,0 +1,221 @@
+use crate::server::types::{SimulatedExecution, SimulationResponse};
+use alloy_consensus::{Header, Transaction, TxType};
+use alloy_primitives::Address;
+use revm::{
+    Context, DatabaseRef, ExecuteEvm, MainBuilder, MainContext,
+    context::{BlockEnv, TxEnv},
+    context_interface::{Transaction as _, block::BlobExcessGasAndPrice, either::Either},
+    database::CacheDB,
+    primitives::{eip4844::GAS_PER_BLOB, hardfork::SpecId},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub mod database;
+
+use database::StateFixture;
+
+/// The errors that can occur while re-executing a transaction.
+#[derive(Error, Debug)]
+pub enum SimulationError {
+    #[error("Transaction is invalid under {spec} rules: {reason}")]
+    InvalidTransaction { spec: &'static str, reason: String },
+}
+
+/// Everything needed to replay a transaction offline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationFixture {
+    /// The block the transaction was included in.
+    pub block: BlockEnv,
+    /// The transaction.
+    pub tx: TxEnv,
+    /// The state at the parent block touched by the transaction.
+    pub state: StateFixture,
+}
+
+/// Build the revm block environment from a block header.
+pub fn block_env(header: &Header) -> BlockEnv {
+    BlockEnv {
+        number: header.number,
+        beneficiary: header.beneficiary,
+        timestamp: header.timestamp,
+        gas_limit: header.gas_limit,
+        basefee: header.base_fee_per_gas.unwrap_or_default(),
+        difficulty: header.difficulty,
+        prevrandao: Some(header.mix_hash),
+        blob_excess_gas_and_price: header
+            .excess_blob_gas
+            .map(|excess_blob_gas| BlobExcessGasAndPrice::new(excess_blob_gas, false)),
+    }
+}
+
+/// Build the revm transaction environment from any transaction type.
+pub fn tx_env<T: Transaction>(tx: &T, caller: Address) -> TxEnv {
+    TxEnv {
+        tx_type: tx.ty(),
+        caller,
+        gas_limit: tx.gas_limit(),
+        gas_price: tx.max_fee_per_gas(),
+        kind: tx.kind(),
+        value: tx.value(),
+        data: tx.input().clone(),
+        nonce: tx.nonce(),
+        chain_id: tx.chain_id(),
+        access_list: tx.access_list().cloned().unwrap_or_default(),
+        gas_priority_fee: tx.max_priority_fee_per_gas(),
+        blob_hashes: tx.blob_versioned_hashes().unwrap_or_default().to_vec(),
+        max_fee_per_blob_gas: tx.max_fee_per_blob_gas().unwrap_or_default(),
+        authorization_list: tx
+            .authorization_list()
+            .unwrap_or_default()
+            .iter()
+            .cloned()
+            .map(Either::Left)
+            .collect(),
+    }
+}
+
+/// Execute the transaction under the given spec, without committing the state.
+fn execute<DB: DatabaseRef>(
+    db: &mut CacheDB<DB>,
+    spec: SpecId,
+    block: &BlockEnv,
+    tx: &TxEnv,
+) -> Result<SimulatedExecution, SimulationError> {
+    let is_prague = spec.is_enabled_in(SpecId::PRAGUE);
+    let mut block = block.clone();
+    // the blob base fee depends on the update fraction of the spec
+    if let Some(blob) = &mut block.blob_excess_gas_and_price {
+        *blob = BlobExcessGasAndPrice::new(blob.excess_blob_gas, is_prague);
+    }
+    let gas_price = tx.effective_gas_price(block.basefee as u128);
+    let blob_gas_price = block
+        .blob_excess_gas_and_price
+        .as_ref()
+        .map(|blob| blob.blob_gasprice);
+    let chain_id = tx.chain_id.unwrap_or(1);
+    let mut evm = Context::mainnet()
+        .with_db(db)
+        .with_block(block)
+        .modify_cfg_chained(|cfg| {
+            cfg.spec = spec;
+            cfg.chain_id = chain_id;
+            // earlier transactions of the same sender in the block are not replayed
+            cfg.disable_nonce_check = true;
+        })
+        .build_mainnet();
+    let spec_name: &'static str = spec.into();
+    let result = evm
+        .transact(tx.clone())
+        .map_err(|e| SimulationError::InvalidTransaction {
+            spec: spec_name,
+            reason: e.to_string(),
+        })?
+        .result;
+    let gas_used = result.gas_used();
+    let blob_gas_used = tx.blob_hashes.len() as u64 * GAS_PER_BLOB;
+    let wei_spent =
+        gas_used as u128 * gas_price + blob_gas_used as u128 * blob_gas_price.unwrap_or_default();
+    Ok(SimulatedExecution {
+        success: result.is_success(),
+        gas_used,
+        gas_price,
+        blob_gas_used,
+        blob_gas_price,
+        wei_spent,
+    })
+}
+
+/// Replay the transaction under Cancun and Prague rules against the same state.
+///
+/// The state is expected to be the one at the parent block, so earlier transactions in the
+/// same block are not taken into account. EIP-7702 transactions are only replayed under Prague
+/// rules, as they didn't exist before.
+pub fn simulate<DB: DatabaseRef>(
+    db: DB,
+    block: &BlockEnv,
+    tx: &TxEnv,
+) -> Result<SimulationResponse, SimulationError> {
+    // share the cache between the two runs so that the state is read only once
+    let mut cache_db = CacheDB::new(db);
+    let cancun = if tx.tx_type == TxType::Eip7702 as u8 {
+        None
+    } else {
+        Some(execute(&mut cache_db, SpecId::CANCUN, block, tx)?)
+    };
+    let prague = execute(&mut cache_db, SpecId::PRAGUE, block, tx)?;
+    let gas_difference = cancun
+        .as_ref()
+        .map(|cancun| prague.gas_used as i64 - cancun.gas_used as i64);
+    let wei_difference = cancun
+        .as_ref()
+        .map(|cancun| prague.wei_spent as i128 - cancun.wei_spent as i128);
+    Ok(SimulationResponse {
+        timestamp: block.timestamp,
+        cancun,
+        prague,
+        gas_difference,
+        wei_difference,
+    })
+}
+
+impl SimulationFixture {
+    /// Replay the recorded transaction offline.
+    pub fn simulate(&self) -> Result<SimulationResponse, SimulationError> {
+        simulate(&self.state, &self.block, &self.tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip7702::{Authorization, SignedAuthorization};
+    use alloy_primitives::U256;
+
+    #[test]
+    fn test_simulate_calldata_floor_fixture() {
+        // a hand-written state, not recorded from chain: 100 zero bytes and 1900 non-zero bytes
+        // sent to an inbox without code
+        let fixture: SimulationFixture = serde_json::from_str(include_str!(
+            "simulation/fixtures/synthetic_calldata_floor.json"
+        ))
+        .unwrap();
+        let response = fixture.simulate().unwrap();
+        let tokens = 100 + 1900 * 4;
+        let cancun = response.cancun.unwrap();
+        assert!(cancun.success && response.prague.success);
+        assert_eq!(cancun.gas_used, 21_000 + tokens * 4);
+        assert_eq!(response.prague.gas_used, 21_000 + tokens * 10);
+        assert_eq!(cancun.gas_price, 11_000_000_000);
+        assert_eq!(response.gas_difference, Some(46_200));
+        assert_eq!(response.wei_difference, Some(46_200 * 11_000_000_000));
+    }
+
+    #[test]
+    fn test_simulate_eip7702_only_under_prague() {
+        let mut fixture: SimulationFixture = serde_json::from_str(include_str!(
+            "simulation/fixtures/synthetic_calldata_floor.json"
+        ))
+        .unwrap();
+        // the same call as an EIP-7702 transaction with an invalid authorization, which is skipped
+        fixture.tx.tx_type = TxType::Eip7702 as u8;
+        fixture.tx.authorization_list = vec![Either::Left(SignedAuthorization::new_unchecked(
+            Authorization {
+                chain_id: U256::from(1),
+                address: Address::ZERO,
+                nonce: 0,
+            },
+            0,
+            U256::ZERO,
+            U256::ZERO,
+        ))];
+        let response = fixture.simulate().unwrap();
+        assert_eq!(response.cancun, None);
+        assert_eq!(response.gas_difference, None);
+        assert_eq!(response.wei_difference, None);
+        // the floor is above the standard cost of the calldata and the authorization
+        let tokens = 100 + 1900 * 4;
+        assert!(response.prague.success);
+        assert_eq!(response.prague.gas_used, 21_000 + tokens * 10);
+    }
+}
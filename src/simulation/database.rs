@@ -0,0 +1,230 @@
+use alloy_eips::BlockId;
+use alloy_primitives::{Address, B256, Bytes, U256};
+use alloy_provider::{Provider, RootProvider};
+use revm::{
+    DatabaseRef,
+    bytecode::Bytecode,
+    database_interface::{DBErrorMarker, async_db::DatabaseAsyncRef},
+    primitives::KECCAK_EMPTY,
+    state::AccountInfo,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, convert::Infallible, sync::Arc, sync::Mutex};
+use thiserror::Error;
+
+/// The errors that can occur while reading state from the RPC.
+#[derive(Error, Debug)]
+pub enum RpcDatabaseError {
+    #[error("Provider error: {0}")]
+    ProviderError(String),
+    #[error("Block not found: {0}")]
+    BlockNotFound(u64),
+    #[error("Code not found for hash: {0}")]
+    CodeNotFound(B256),
+}
+
+impl DBErrorMarker for RpcDatabaseError {}
+
+/// A state database reading accounts and storage from the RPC at a fixed block.
+#[derive(Debug, Clone)]
+pub struct RpcDatabase {
+    /// The Ethereum provider.
+    provider: Arc<RootProvider>,
+    /// The block the state is read at.
+    block_id: BlockId,
+}
+
+impl RpcDatabase {
+    /// Create a new RPC database reading state at the given block number.
+    pub fn new(provider: Arc<RootProvider>, block_number: u64) -> Self {
+        Self {
+            provider,
+            block_id: BlockId::number(block_number),
+        }
+    }
+}
+
+impl DatabaseAsyncRef for RpcDatabase {
+    type Error = RpcDatabaseError;
+
+    async fn basic_async_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let balance = self.provider.get_balance(address).block_id(self.block_id);
+        let nonce = self
+            .provider
+            .get_transaction_count(address)
+            .block_id(self.block_id);
+        let code = self.provider.get_code_at(address).block_id(self.block_id);
+        let (balance, nonce, code) = tokio::try_join!(balance, nonce, code)
+            .map_err(|e| RpcDatabaseError::ProviderError(e.to_string()))?;
+        let bytecode = Bytecode::new_raw(code);
+        Ok(Some(AccountInfo::new(
+            balance,
+            nonce,
+            bytecode.hash_slow(),
+            bytecode,
+        )))
+    }
+
+    async fn code_by_hash_async_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        // the code is always returned together with the account
+        Err(RpcDatabaseError::CodeNotFound(code_hash))
+    }
+
+    async fn storage_async_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.provider
+            .get_storage_at(address, index)
+            .block_id(self.block_id)
+            .await
+            .map_err(|e| RpcDatabaseError::ProviderError(e.to_string()))
+    }
+
+    async fn block_hash_async_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        let block = self
+            .provider
+            .get_block_by_number(number.into())
+            .await
+            .map_err(|e| RpcDatabaseError::ProviderError(e.to_string()))?
+            .ok_or(RpcDatabaseError::BlockNotFound(number))?;
+        Ok(block.header.hash)
+    }
+}
+
+/// The state of a single account in a [`StateFixture`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountFixture {
+    /// The balance of the account.
+    pub balance: U256,
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The code of the account.
+    #[serde(default)]
+    pub code: Bytes,
+    /// The storage slots read during execution.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// The subset of the state touched by a transaction, used to replay it offline.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateFixture {
+    /// The accounts read during execution.
+    pub accounts: BTreeMap<Address, AccountFixture>,
+    /// The block hashes read during execution.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub block_hashes: BTreeMap<u64, B256>,
+}
+
+impl DatabaseRef for StateFixture {
+    type Error = Infallible;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        Ok(self.accounts.get(&address).map(|account| {
+            let bytecode = Bytecode::new_raw(account.code.clone());
+            AccountInfo::new(
+                account.balance,
+                account.nonce,
+                bytecode.hash_slow(),
+                bytecode,
+            )
+        }))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        Ok(self
+            .accounts
+            .values()
+            .map(|account| Bytecode::new_raw(account.code.clone()))
+            .find(|bytecode| bytecode.hash_slow() == code_hash)
+            .unwrap_or_default())
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        Ok(self
+            .accounts
+            .get(&address)
+            .and_then(|account| account.storage.get(&index).copied())
+            .unwrap_or_default())
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        Ok(self.block_hashes.get(&number).copied().unwrap_or_default())
+    }
+}
+
+/// A database wrapper recording every read into a [`StateFixture`].
+#[derive(Debug)]
+pub struct RecordingDatabase<DB> {
+    /// The wrapped database.
+    inner: DB,
+    /// The state read so far.
+    recorded: Mutex<StateFixture>,
+}
+
+impl<DB> RecordingDatabase<DB> {
+    /// Wrap a database to record its reads.
+    pub fn new(inner: DB) -> Self {
+        Self {
+            inner,
+            recorded: Mutex::new(StateFixture::default()),
+        }
+    }
+
+    /// The state read so far.
+    pub fn into_fixture(self) -> StateFixture {
+        self.recorded
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<DB: DatabaseRef> DatabaseRef for RecordingDatabase<DB> {
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let account = self.inner.basic_ref(address)?;
+        if let Some(info) = &account {
+            let mut recorded = self.recorded.lock().unwrap_or_else(|e| e.into_inner());
+            let entry = recorded.accounts.entry(address).or_default();
+            entry.balance = info.balance;
+            entry.nonce = info.nonce;
+            if info.code_hash != KECCAK_EMPTY {
+                let code = match &info.code {
+                    Some(code) => code.original_bytes(),
+                    None => self
+                        .inner
+                        .code_by_hash_ref(info.code_hash)?
+                        .original_bytes(),
+                };
+                entry.code = code;
+            }
+        }
+        Ok(account)
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.inner.code_by_hash_ref(code_hash)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.inner.storage_ref(address, index)?;
+        self.recorded
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .accounts
+            .entry(address)
+            .or_default()
+            .storage
+            .insert(index, value);
+        Ok(value)
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.inner.block_hash_ref(number)?;
+        self.recorded
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .block_hashes
+            .insert(number, hash);
+        Ok(hash)
+    }
+}
@@ -0,0 +1,167 @@
+use crate::server::types::TimingEfficiency;
+
+/// The default number of blocks before and after the inclusion block of a tracked batch whose
+/// fees are compared.
+pub const DEFAULT_TIMING_WINDOW_BLOCKS: u64 = 10;
+/// The largest timing window that can be requested, in blocks on each side.
+pub const MAX_TIMING_WINDOW_BLOCKS: u64 = 100;
+
+/// The base fees of a block of the timing window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockFees {
+    /// The block number.
+    pub block_number: u64,
+    /// The execution base fee of the block.
+    pub base_fee_per_gas: u64,
+    /// The blob base fee of the block, None before cancun.
+    pub blob_base_fee: Option<u128>,
+}
+
+/// The gas and fees a transaction paid at its inclusion block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaidGas {
+    /// The inclusion block of the transaction.
+    pub block_number: u64,
+    /// The gas used by the transaction.
+    pub gas_used: u64,
+    /// The priority fee paid per gas, kept in the other blocks.
+    pub priority_fee_per_gas: u128,
+    /// The blob gas used by the transaction.
+    pub blob_gas_used: u64,
+    /// Wei paid for the gas and the blob gas.
+    pub actual_wei: u128,
+}
+
+impl PaidGas {
+    /// It returns what the transaction would have paid at the given block.
+    ///
+    /// None if the block has no blob base fee and the transaction uses blob gas.
+    pub fn cost_at(&self, block: &BlockFees) -> Option<u128> {
+        let blob_wei = match (self.blob_gas_used, block.blob_base_fee) {
+            (0, _) => 0,
+            (blob_gas_used, Some(blob_base_fee)) => blob_gas_used as u128 * blob_base_fee,
+            (_, None) => return None,
+        };
+        let gas_price = block.base_fee_per_gas as u128 + self.priority_fee_per_gas;
+        Some(self.gas_used as u128 * gas_price + blob_wei)
+    }
+}
+
+/// It compares what a transaction paid with what the same gas and blob gas would have cost at
+/// the cheapest block of the window.
+///
+/// The blocks are sorted by number. None if no block of the window can be priced.
+pub fn timing_efficiency(
+    paid: &PaidGas,
+    window_blocks: u64,
+    blocks: &[BlockFees],
+) -> Option<TimingEfficiency> {
+    let (cheapest_block, cheapest_wei) = blocks
+        .iter()
+        .filter_map(|block| paid.cost_at(block).map(|cost| (block, cost)))
+        // the earliest block wins a tie
+        .min_by_key(|(block, cost)| (*cost, block.block_number))?;
+    // safe unwraps as a block was priced
+    let first_block_number = blocks.first().unwrap().block_number;
+    let last_block_number = blocks.last().unwrap().block_number;
+    let window_start = paid.block_number.saturating_sub(window_blocks);
+    let window_end = paid.block_number + window_blocks;
+    Some(TimingEfficiency {
+        window_blocks,
+        blocks_sampled: blocks.len() as u64,
+        first_block_number,
+        last_block_number,
+        complete: first_block_number <= window_start
+            && last_block_number >= window_end
+            && blocks.len() as u64 == window_end - window_start + 1,
+        actual_wei: paid.actual_wei,
+        cheapest_block_number: cheapest_block.block_number,
+        cheapest_base_fee_per_gas: cheapest_block.base_fee_per_gas,
+        cheapest_blob_base_fee: cheapest_block.blob_base_fee,
+        cheapest_wei,
+        timing_premium_wei: paid.actual_wei.saturating_sub(cheapest_wei),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_efficiency() {
+        // 100000 gas with a priority fee of 2 wei and one blob at the inclusion block 101
+        let paid = PaidGas {
+            block_number: 101,
+            gas_used: 100_000,
+            priority_fee_per_gas: 2,
+            blob_gas_used: 131_072,
+            actual_wei: 100_000 * 12 + 131_072 * 5,
+        };
+        let blocks = [
+            BlockFees {
+                block_number: 100,
+                base_fee_per_gas: 11,
+                blob_base_fee: Some(4),
+            },
+            BlockFees {
+                block_number: 101,
+                base_fee_per_gas: 10,
+                blob_base_fee: Some(5),
+            },
+            BlockFees {
+                block_number: 102,
+                base_fee_per_gas: 9,
+                blob_base_fee: Some(3),
+            },
+        ];
+        let timing = timing_efficiency(&paid, 1, &blocks).unwrap();
+        assert_eq!(timing.blocks_sampled, 3);
+        assert_eq!(timing.first_block_number, 100);
+        assert_eq!(timing.last_block_number, 102);
+        assert!(timing.complete);
+        assert_eq!(timing.cheapest_block_number, 102);
+        assert_eq!(timing.cheapest_wei, 100_000 * 11 + 131_072 * 3);
+        // 1 wei less per gas and 2 wei less per blob gas
+        assert_eq!(timing.timing_premium_wei, 100_000 + 131_072 * 2);
+
+        // at the cheapest block
+        let timing = timing_efficiency(&paid, 1, &blocks[1..2]).unwrap();
+        assert_eq!(timing.cheapest_block_number, 101);
+        assert_eq!(timing.timing_premium_wei, 0);
+        assert!(!timing.complete);
+
+        // the blocks after the inclusion block are not produced yet
+        let timing = timing_efficiency(&paid, 1, &blocks[..2]).unwrap();
+        assert_eq!(timing.last_block_number, 101);
+        assert!(!timing.complete);
+
+        // the earliest block wins a tie
+        let tie = [
+            BlockFees {
+                block_number: 103,
+                ..blocks[2]
+            },
+            blocks[2],
+        ];
+        let timing = timing_efficiency(&paid, 1, &tie).unwrap();
+        assert_eq!(timing.cheapest_block_number, 102);
+
+        // a block without blob base fee can't price the blob gas
+        let pre_cancun = BlockFees {
+            block_number: 99,
+            base_fee_per_gas: 1,
+            blob_base_fee: None,
+        };
+        assert_eq!(timing_efficiency(&paid, 1, &[pre_cancun]), None);
+        let calldata_paid = PaidGas {
+            blob_gas_used: 0,
+            ..paid
+        };
+        assert_eq!(
+            timing_efficiency(&calldata_paid, 1, &[pre_cancun])
+                .unwrap()
+                .cheapest_wei,
+            100_000 * 3
+        );
+    }
+}
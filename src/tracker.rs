@@ -2,3 +2,93 @@ pub mod database;
 pub mod l2_monitor;
 pub mod retry_handler;
 pub mod snapshot;
+
+use crate::{
+    batch::arbitrum::ArbitrumDecoder,
+    compression::{Compression, CompressionAlgorithm},
+    provider::ProviderState,
+    server::{
+        handlers::AnalysisOptions,
+        types::{BreakEvenFee, TxAnalysisResponse},
+    },
+    tracker::database::TrackedBatch,
+};
+use alloy_primitives::Address;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The compressions evaluated on the payload of every tracked batch.
+pub const TRACKED_COMPRESSIONS: [Compression; 3] = [
+    Compression {
+        algorithm: CompressionAlgorithm::Zlib,
+        level: 9,
+    },
+    Compression {
+        algorithm: CompressionAlgorithm::Brotli,
+        level: 10,
+    },
+    Compression {
+        algorithm: CompressionAlgorithm::Zstd,
+        level: 19,
+    },
+];
+
+/// The options of the analysis of the tracked batches.
+pub fn tracked_analysis_options(provider_state: &ProviderState) -> AnalysisOptions {
+    AnalysisOptions {
+        compressions: TRACKED_COMPRESSIONS.to_vec(),
+        timing_window: Some(provider_state.timing_window_blocks),
+        da_price_table: provider_state.da_price_table.clone(),
+        ..Default::default()
+    }
+}
+
+/// The tracked batch of an analyzed transaction of a monitored address.
+///
+/// None if the transaction was sent to a sequencer inbox without posting a batch.
+pub fn tracked_batch(
+    tx_hash: &str,
+    batcher_address: Address,
+    analysis: &TxAnalysisResponse,
+) -> eyre::Result<Option<TrackedBatch>> {
+    // the sequencer inbox also receives calls that don't post batches
+    if analysis.l2_batch.is_none() && ArbitrumDecoder::for_inbox(batcher_address).is_some() {
+        return Ok(None);
+    }
+    let analysis_result = serde_json::to_string(analysis)
+        .map_err(|e| eyre::eyre!("Failed to serialize analysis result: {}", e))?;
+    Ok(Some(TrackedBatch {
+        id: None,
+        tx_hash: tx_hash.to_string(),
+        batcher_address: format!("{:#x}", batcher_address),
+        analysis_result,
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64,
+        last_analyzed_block: None,
+        l2_tx_count: analysis
+            .l2_batch
+            .as_ref()
+            .and_then(|l2_batch| l2_batch.l2_tx_count)
+            .map(|l2_tx_count| l2_tx_count as i64),
+        sequence_number: analysis
+            .l2_batch
+            .as_ref()
+            .and_then(|l2_batch| l2_batch.arbitrum.as_ref())
+            .map(|arbitrum| arbitrum.sequence_number as i64),
+        status: Some(analysis.success as i64),
+    }))
+}
+
+/// The break-even blob base fee at the block of an analyzed batch.
+///
+/// None if the batch happened before Cancun hard fork.
+pub fn batch_break_even_fee(analysis: &TxAnalysisResponse) -> Option<BreakEvenFee> {
+    Some(BreakEvenFee {
+        block_number: analysis.block_number,
+        timestamp: analysis.timestamp as i64,
+        base_fee_per_gas: analysis.fees.base_fee_per_gas,
+        blob_base_fee: analysis.blob_gas_price?,
+        break_even_blob_base_fee: analysis.break_even_blob_base_fee?,
+    })
+}
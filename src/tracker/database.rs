@@ -1,13 +1,153 @@
-use crate::server::types::{
-    BatcherBlobDataGas, BatcherDailyTxs, BatcherEthSaved, BatcherPectraDataGas, DailyBatcherStats,
+use crate::{
+    blob::{
+        sharing::{BlobBatch, simulate_blob_sharing},
+        utilization_pct,
+    },
+    compression::CompressionAlgorithm,
+    fork::CalldataPricing,
+    price::{FiatConversion, PricePoint, wei_to_fiat},
+    server::types::{
+        BatcherAltDaCost, BatcherBlobDataGas, BatcherBlobUtilization, BatcherByteProfile,
+        BatcherCompression, BatcherDailyTxs, BatcherEip7918ExtraCost, BatcherEthSaved,
+        BatcherFeeBreakdown, BatcherL2TxCost, BatcherPectraDataGas, BatcherRegret,
+        BatcherTimingEfficiency, BatcherWastedSpend, BlobSharingSimulation, BreakEvenFee,
+        DailyBatcherStats, HourlyBreakEvenFee, PostingMode, WrongModeBatch, WrongSideBatch,
+    },
 };
 use async_trait::async_trait;
 use eyre::Result;
+use revm::primitives::eip4844::GAS_PER_BLOB;
 use sqlx::{
     Row,
-    sqlite::{SqlitePool, SqlitePoolOptions},
+    sqlite::{SqlitePool, SqlitePoolOptions, SqliteRow},
 };
 use std::collections::HashMap;
+use tracing::warn;
+
+/// The most a batch is tracked after its block, in seconds: the timing window and the retries of
+/// a failed analysis take well under it.
+const TRACKING_DELAY_MARGIN: i64 = 6 * 3600;
+
+/// It returns the calldata gas of a tracked batch at the floor token cost of the hypothetical
+/// pricing.
+///
+/// None if the batch was analyzed before the calldata bytes were recorded.
+fn hypothetical_floor_calldata_gas(
+    analysis: &serde_json::Value,
+    pricing: &CalldataPricing,
+) -> Option<u64> {
+    let zero_bytes = analysis["zero_bytes"].as_u64()?;
+    let non_zero_bytes = analysis["non_zero_bytes"].as_u64()?;
+    Some(pricing.floor_calldata_gas(zero_bytes, non_zero_bytes))
+}
+
+/// It returns the posting mode of a tracked batch, the wei spent on its data in that mode and the
+/// wei it would have spent in the other mode.
+///
+/// None if the batch happened before Cancun, when there was no blob alternative.
+fn batch_mode_costs(analysis: &serde_json::Value) -> Option<(PostingMode, u128, u128)> {
+    let blob_data_wei_spent = analysis["blob_data_wei_spent"].as_u64()? as u128;
+    let eip_7623_calldata_wei_spent = analysis["eip_7623_calldata_wei_spent"].as_u64()? as u128;
+    if analysis["blob_gas_used"].as_u64().unwrap_or(0) > 0 {
+        Some((
+            PostingMode::Blob,
+            blob_data_wei_spent,
+            eip_7623_calldata_wei_spent,
+        ))
+    } else {
+        Some((
+            PostingMode::Calldata,
+            eip_7623_calldata_wei_spent,
+            blob_data_wei_spent,
+        ))
+    }
+}
+
+/// It returns the wei a tracked batch saved by posting its data in blobs rather than as EIP-7623
+/// calldata, or would have saved for calldata batches.
+///
+/// Batches before Cancun are compared with no blob cost.
+fn batch_eth_saved_wei(analysis: &serde_json::Value) -> u128 {
+    match batch_mode_costs(analysis) {
+        Some((PostingMode::Blob, blob_mode_wei, calldata_mode_wei))
+        | Some((PostingMode::Calldata, calldata_mode_wei, blob_mode_wei)) => {
+            calldata_mode_wei.saturating_sub(blob_mode_wei)
+        }
+        None => analysis["eip_7623_calldata_wei_spent"]
+            .as_u64()
+            .unwrap_or(0) as u128,
+    }
+}
+
+/// It returns the wei a tracked batch saved, or would have saved, against its data posted as
+/// calldata at the floor of the hypothetical pricing.
+///
+/// The posting modes are compared as in [`batch_eth_saved_wei`], with the calldata mode repriced.
+///
+/// None if the batch was analyzed before its bytes were counted.
+fn hypothetical_batch_eth_saved_wei(
+    analysis: &serde_json::Value,
+    pricing: &CalldataPricing,
+) -> Option<u128> {
+    let floor_calldata_gas = hypothetical_floor_calldata_gas(analysis, pricing)?;
+    let gas_price = analysis["gas_price"].as_u64().unwrap_or(0) as u128;
+    let blob_mode_wei = match batch_mode_costs(analysis) {
+        Some((PostingMode::Blob, blob_mode_wei, _))
+        | Some((PostingMode::Calldata, _, blob_mode_wei)) => blob_mode_wei,
+        // batches before Cancun are compared with no blob cost
+        None => 0,
+    };
+    Some((floor_calldata_gas as u128 * gas_price).saturating_sub(blob_mode_wei))
+}
+
+/// The ETH prices of the tracked batches of a request, read once per hour.
+struct BatchEthPrices<'a> {
+    /// The conversion of the request, None if no fiat value is requested.
+    fiat: Option<FiatConversion<'a>>,
+    /// The price of each hour, at the first batch of the hour read.
+    hourly_prices: HashMap<u64, Option<f64>>,
+}
+
+impl<'a> BatchEthPrices<'a> {
+    fn new(fiat: Option<FiatConversion<'a>>) -> Self {
+        Self {
+            fiat,
+            hourly_prices: HashMap::new(),
+        }
+    }
+
+    /// It returns the ETH price at the block of a tracked batch.
+    ///
+    /// Batches analyzed before the block number was recorded are priced at their timestamp only.
+    /// None if no fiat value is requested or the price can't be read, leaving the fiat totals of
+    /// the batch None.
+    async fn price(&mut self, analysis: &serde_json::Value, row_timestamp: i64) -> Option<f64> {
+        let fiat = self.fiat?;
+        let at = PricePoint {
+            block_number: analysis["block_number"].as_u64(),
+            timestamp: analysis["timestamp"]
+                .as_u64()
+                .unwrap_or(row_timestamp as u64),
+        };
+        let hour = at.timestamp / 3600;
+        if let Some(eth_price) = self.hourly_prices.get(&hour) {
+            return *eth_price;
+        }
+        let eth_price = fiat.eth_price(at).await.unwrap_or_else(|e| {
+            warn!("Failed to get the ETH price at {:?}: {}", at, e);
+            None
+        });
+        self.hourly_prices.insert(hour, eth_price);
+        eth_price
+    }
+}
+
+/// It adds the fiat value of wei to a fiat total, which stays None once a batch has no price.
+fn add_fiat(total: &mut Option<f64>, wei: u128, eth_price: Option<f64>) {
+    *total = total
+        .zip(eth_price)
+        .map(|(total, eth_price)| total + wei_to_fiat(wei, eth_price));
+}
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct TrackedBatch {
@@ -23,6 +163,12 @@ pub struct TrackedBatch {
     pub timestamp: i64, // SQLite INTEGER can be mapped to i64
     #[sqlx(default)] // If last_analyzed_block is not selected, it will default.
     pub last_analyzed_block: Option<i64>, // SQLite INTEGER can be Option<i64>
+    #[sqlx(default)] // NULL when the batch could not be decoded.
+    pub l2_tx_count: Option<i64>,
+    #[sqlx(default)] // NULL for batches not posted to an Arbitrum sequencer inbox.
+    pub sequence_number: Option<i64>,
+    #[sqlx(default)] // The receipt status: 1 for success, 0 for reverted. NULL if tracked before.
+    pub status: Option<i64>,
 }
 
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -87,6 +233,7 @@ pub trait Database: Send + Sync {
     ) -> Result<u64>;
 
     // methods for aggregated L2 batch analytics across all batchers
+    // reverted batches are left out, except from the transaction count, fees and wasted spend
     async fn get_all_daily_transactions(
         &self,
         start_timestamp: i64,
@@ -97,6 +244,8 @@ pub trait Database: Send + Sync {
         &self,
         start_timestamp: i64,
         end_timestamp: i64,
+        pricing: Option<CalldataPricing>,
+        fiat: Option<FiatConversion<'_>>,
     ) -> Result<Vec<BatcherEthSaved>>;
 
     async fn get_all_total_blob_data_gas(
@@ -109,8 +258,93 @@ pub trait Database: Send + Sync {
         &self,
         start_timestamp: i64,
         end_timestamp: i64,
+        pricing: Option<CalldataPricing>,
     ) -> Result<Vec<BatcherPectraDataGas>>;
 
+    async fn get_all_blob_utilization(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherBlobUtilization>>;
+
+    async fn get_blob_sharing_simulation(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        window_blocks: u64,
+    ) -> Result<BlobSharingSimulation>;
+
+    async fn get_all_l2_tx_cost(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        fiat: Option<FiatConversion<'_>>,
+    ) -> Result<Vec<BatcherL2TxCost>>;
+
+    async fn get_all_eip_7918_extra_cost(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherEip7918ExtraCost>>;
+
+    async fn get_all_compression(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        fiat: Option<FiatConversion<'_>>,
+    ) -> Result<Vec<BatcherCompression>>;
+
+    async fn get_all_alt_da_cost(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherAltDaCost>>;
+
+    async fn get_all_byte_profile(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherByteProfile>>;
+
+    async fn get_all_fee_breakdown(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherFeeBreakdown>>;
+
+    async fn get_all_wasted_spend(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherWastedSpend>>;
+
+    async fn get_all_regret(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherRegret>>;
+
+    async fn get_wrong_mode_batches(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<WrongModeBatch>>;
+
+    async fn get_all_timing_efficiency(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherTimingEfficiency>>;
+
+    // methods for the break-even blob base fee series
+    async fn save_break_even_fee(&self, fee: &BreakEvenFee) -> Result<()>;
+
+    async fn get_hourly_break_even_fees(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<HourlyBreakEvenFee>>;
+
     // Save aggregated daily snapshot stats for each batcher
     async fn insert_daily_batcher_stats(&self, stats: &[DailyBatcherStats]) -> Result<()>;
 
@@ -119,6 +353,13 @@ pub trait Database: Send + Sync {
         &self,
         limit_per_batcher: i64,
     ) -> Result<Vec<DailyBatcherStats>>;
+
+    // Fetch the daily snapshot rows of all batchers taken in the given range
+    async fn get_daily_stats(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<DailyBatcherStats>>;
 }
 
 pub struct SqliteDatabase {
@@ -143,12 +384,21 @@ impl SqliteDatabase {
                 batcher_address TEXT NOT NULL,
                 analysis_result TEXT NOT NULL,
                 timestamp INTEGER NOT NULL,
-                last_analyzed_block INTEGER
+                last_analyzed_block INTEGER,
+                l2_tx_count INTEGER,
+                sequence_number INTEGER,
+                status INTEGER
             )",
         )
         .execute(&pool)
         .await?;
 
+        // databases created before the batch decoding lack the l2 tx count
+        Self::add_column_if_missing(&pool, "l2_batches_txs", "l2_tx_count", "INTEGER").await?;
+        Self::add_column_if_missing(&pool, "l2_batches_txs", "sequence_number", "INTEGER").await?;
+        // batches tracked before the receipt status was recorded are assumed successful
+        Self::add_column_if_missing(&pool, "l2_batches_txs", "status", "INTEGER").await?;
+
         // create failed transactions table
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS failed_transactions (
@@ -175,12 +425,70 @@ impl SqliteDatabase {
                 total_daily_txs INTEGER NOT NULL,
                 total_blob_data_gas INTEGER NOT NULL,
                 total_pectra_data_gas INTEGER NOT NULL,
+                total_eip_7918_extra_wei TEXT,
+                total_data_bytes INTEGER,
+                total_zero_bytes INTEGER,
+                total_data_tokens INTEGER,
+                floor_applied_txs INTEGER,
+                wrong_mode_txs INTEGER,
+                total_regret_wei TEXT,
+                net_regret_wei TEXT,
+                timed_txs INTEGER,
+                optimal_timing_txs INTEGER,
+                total_timing_premium_wei TEXT,
                 UNIQUE(batcher_address, snapshot_timestamp)
             )",
         )
         .execute(&pool)
         .await?;
 
+        // create break-even blob base fee table, one row per sampled block
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS break_even_fees (
+                block_number INTEGER PRIMARY KEY,
+                timestamp INTEGER NOT NULL,
+                base_fee_per_gas INTEGER NOT NULL,
+                blob_base_fee TEXT NOT NULL,
+                break_even_blob_base_fee TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await?;
+
+        // snapshots taken before the EIP-7918 counterfactual lack the extra cost
+        Self::add_column_if_missing(
+            &pool,
+            "daily_batcher_stats",
+            "total_eip_7918_extra_wei",
+            "TEXT",
+        )
+        .await?;
+        // snapshots taken before the byte breakdown lack the byte profile
+        for column in [
+            "total_data_bytes",
+            "total_zero_bytes",
+            "total_data_tokens",
+            "floor_applied_txs",
+        ] {
+            Self::add_column_if_missing(&pool, "daily_batcher_stats", column, "INTEGER").await?;
+        }
+        // snapshots taken before the regret was computed lack it
+        for (column, definition) in [
+            ("wrong_mode_txs", "INTEGER"),
+            ("total_regret_wei", "TEXT"),
+            ("net_regret_wei", "TEXT"),
+        ] {
+            Self::add_column_if_missing(&pool, "daily_batcher_stats", column, definition).await?;
+        }
+        // snapshots taken before the timing was analyzed lack it
+        for (column, definition) in [
+            ("timed_txs", "INTEGER"),
+            ("optimal_timing_txs", "INTEGER"),
+            ("total_timing_premium_wei", "TEXT"),
+        ] {
+            Self::add_column_if_missing(&pool, "daily_batcher_stats", column, definition).await?;
+        }
+
         // sqlx uses `?` for SQLite parameters, not `?1` etc. for numbered params by default.
         // But for `VALUES (...)` it can be `VALUES (?, ?, ...)`
         let initial_block_i64 = initial_block as i64;
@@ -194,6 +502,31 @@ impl SqliteDatabase {
 
         Ok(SqliteDatabase { pool })
     }
+
+    /// Add a column to an existing table, if the table doesn't have it yet.
+    async fn add_column_if_missing(
+        pool: &SqlitePool,
+        table: &str,
+        column: &str,
+        definition: &str,
+    ) -> Result<()> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM pragma_table_info(?) WHERE name = ?",
+        )
+        .bind(table)
+        .bind(column)
+        .fetch_one(pool)
+        .await?;
+        if count == 0 {
+            sqlx::query(&format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, definition
+            ))
+            .execute(pool)
+            .await?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -209,13 +542,16 @@ impl Database for SqliteDatabase {
 
     async fn save_tracked_batch(&self, batch: &TrackedBatch) -> Result<()> {
         sqlx::query(
-            "INSERT INTO l2_batches_txs (tx_hash, batcher_address, analysis_result, timestamp, last_analyzed_block)
-             VALUES (?, ?, ?, ?, NULL)", // last_analyzed_block is NULL for normal txs
+            "INSERT INTO l2_batches_txs (tx_hash, batcher_address, analysis_result, timestamp, last_analyzed_block, l2_tx_count, sequence_number, status)
+             VALUES (?, ?, ?, ?, NULL, ?, ?, ?)", // last_analyzed_block is NULL for normal txs
         )
         .bind(&batch.tx_hash)
         .bind(batch.batcher_address.to_lowercase()) // Store addresses in lowercase for consistency
         .bind(&batch.analysis_result)
         .bind(batch.timestamp) // sqlx can map i64 to INTEGER
+        .bind(batch.l2_tx_count)
+        .bind(batch.sequence_number)
+        .bind(batch.status)
         .execute(&self.pool)
         .await?;
         Ok(())
@@ -349,7 +685,7 @@ impl Database for SqliteDatabase {
         let rows = sqlx::query(
             "SELECT analysis_result FROM l2_batches_txs 
              WHERE batcher_address = LOWER(?) AND timestamp >= ? AND timestamp <= ? 
-             AND tx_hash != 'monitoring_state'",
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
         )
         .bind(batcher_address)
         .bind(start_timestamp)
@@ -363,14 +699,7 @@ impl Database for SqliteDatabase {
 
             // Parse the JSON analysis result to extract ETH saved data
             if let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) {
-                let blob_data_wei_spent =
-                    analysis["blob_data_wei_spent"].as_u64().unwrap_or(0) as u128;
-                let eip_7623_calldata_wei_spent = analysis["eip_7623_calldata_wei_spent"]
-                    .as_u64()
-                    .unwrap_or(0) as u128;
-
-                // Calculate ETH saved: difference between what would be spent on EIP-7623 and what was actually spent on blob data
-                let eth_saved_wei = eip_7623_calldata_wei_spent.saturating_sub(blob_data_wei_spent);
+                let eth_saved_wei = batch_eth_saved_wei(&analysis);
                 total_eth_saved += eth_saved_wei;
             }
         }
@@ -387,7 +716,7 @@ impl Database for SqliteDatabase {
         let rows = sqlx::query(
             "SELECT analysis_result FROM l2_batches_txs 
              WHERE batcher_address = LOWER(?) AND timestamp >= ? AND timestamp <= ? 
-             AND tx_hash != 'monitoring_state'",
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
         )
         .bind(batcher_address)
         .bind(start_timestamp)
@@ -416,7 +745,7 @@ impl Database for SqliteDatabase {
         let rows = sqlx::query(
             "SELECT analysis_result FROM l2_batches_txs 
              WHERE batcher_address = LOWER(?) AND timestamp >= ? AND timestamp <= ? 
-             AND tx_hash != 'monitoring_state'",
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
         )
         .bind(batcher_address)
         .bind(start_timestamp)
@@ -469,44 +798,64 @@ impl Database for SqliteDatabase {
         &self,
         start_timestamp: i64,
         end_timestamp: i64,
+        pricing: Option<CalldataPricing>,
+        fiat: Option<FiatConversion<'_>>,
     ) -> Result<Vec<BatcherEthSaved>> {
         let rows = sqlx::query(
-            "SELECT batcher_address, analysis_result FROM l2_batches_txs 
+            "SELECT batcher_address, analysis_result, timestamp FROM l2_batches_txs 
              WHERE timestamp >= ? AND timestamp <= ? 
-             AND tx_hash != 'monitoring_state'",
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
         )
         .bind(start_timestamp)
         .bind(end_timestamp)
         .fetch_all(&self.pool)
         .await?;
 
-        let mut batcher_eth_saved: HashMap<String, u128> = HashMap::new();
+        // (eth saved, hypothetical eth saved, fiat eth saved) per batcher
+        let mut batcher_eth_saved: HashMap<String, (u128, u128, Option<f64>)> = HashMap::new();
+
+        let mut eth_prices = BatchEthPrices::new(fiat);
 
         for row in rows {
             let batcher_address: String = row.get("batcher_address");
             let analysis_result: String = row.get("analysis_result");
+            let timestamp: i64 = row.get("timestamp");
 
             // Parse the JSON analysis result to extract ETH saved data
             if let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) {
-                let blob_data_wei_spent =
-                    analysis["blob_data_wei_spent"].as_u64().unwrap_or(0) as u128;
-                let eip_7623_calldata_wei_spent = analysis["eip_7623_calldata_wei_spent"]
-                    .as_u64()
-                    .unwrap_or(0) as u128;
-
-                // Calculate ETH saved: difference between what would be spent on EIP-7623 and what was actually spent on blob data
-                let eth_saved_wei = eip_7623_calldata_wei_spent.saturating_sub(blob_data_wei_spent);
-
-                *batcher_eth_saved.entry(batcher_address).or_insert(0) += eth_saved_wei;
+                let eth_saved_wei = batch_eth_saved_wei(&analysis);
+
+                let eth_price = eth_prices.price(&analysis, timestamp).await;
+                let entry =
+                    batcher_eth_saved
+                        .entry(batcher_address)
+                        .or_insert((0, 0, fiat.map(|_| 0.0)));
+                entry.0 += eth_saved_wei;
+                add_fiat(&mut entry.2, eth_saved_wei, eth_price);
+                if let Some(hypothetical_eth_saved_wei) = pricing
+                    .as_ref()
+                    .and_then(|pricing| hypothetical_batch_eth_saved_wei(&analysis, pricing))
+                {
+                    entry.1 += hypothetical_eth_saved_wei;
+                }
             }
         }
 
         Ok(batcher_eth_saved
             .into_iter()
-            .map(|(batcher_address, total_eth_saved_wei)| BatcherEthSaved {
-                batcher_address,
-                total_eth_saved_wei,
-            })
+            .map(
+                |(
+                    batcher_address,
+                    (total_eth_saved_wei, hypothetical_eth_saved_wei, total_eth_saved_fiat),
+                )| {
+                    BatcherEthSaved {
+                        batcher_address,
+                        total_eth_saved_wei,
+                        hypothetical_eth_saved_wei: pricing.map(|_| hypothetical_eth_saved_wei),
+                        total_eth_saved_fiat,
+                    }
+                },
+            )
             .collect())
     }
 
@@ -518,7 +867,7 @@ impl Database for SqliteDatabase {
         let rows = sqlx::query(
             "SELECT batcher_address, analysis_result FROM l2_batches_txs 
              WHERE timestamp >= ? AND timestamp <= ? 
-             AND tx_hash != 'monitoring_state'",
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
         )
         .bind(start_timestamp)
         .bind(end_timestamp)
@@ -551,141 +900,1151 @@ impl Database for SqliteDatabase {
         &self,
         start_timestamp: i64,
         end_timestamp: i64,
+        pricing: Option<CalldataPricing>,
     ) -> Result<Vec<BatcherPectraDataGas>> {
         let rows = sqlx::query(
             "SELECT batcher_address, analysis_result FROM l2_batches_txs 
              WHERE timestamp >= ? AND timestamp <= ? 
-             AND tx_hash != 'monitoring_state'",
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
         )
         .bind(start_timestamp)
         .bind(end_timestamp)
         .fetch_all(&self.pool)
         .await?;
 
-        let mut batcher_pectra_gas: HashMap<String, u64> = HashMap::new();
+        // (pectra data gas, hypothetical pectra data gas) per batcher
+        let mut batcher_pectra_gas: HashMap<String, (u64, u64)> = HashMap::new();
 
         for row in rows {
             let batcher_address: String = row.get("batcher_address");
             let analysis_result: String = row.get("analysis_result");
             if let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) {
                 let eip_7623_calldata_gas = analysis["eip_7623_calldata_gas"].as_u64().unwrap_or(0);
-                *batcher_pectra_gas.entry(batcher_address).or_insert(0) += eip_7623_calldata_gas;
+                let entry = batcher_pectra_gas.entry(batcher_address).or_default();
+                entry.0 += eip_7623_calldata_gas;
+                if let Some(floor_calldata_gas) = pricing
+                    .as_ref()
+                    .and_then(|pricing| hypothetical_floor_calldata_gas(&analysis, pricing))
+                {
+                    entry.1 += floor_calldata_gas;
+                }
             }
         }
 
         Ok(batcher_pectra_gas
             .into_iter()
             .map(
-                |(batcher_address, total_pectra_data_gas)| BatcherPectraDataGas {
-                    batcher_address,
-                    total_pectra_data_gas,
+                |(batcher_address, (total_pectra_data_gas, hypothetical_pectra_data_gas))| {
+                    BatcherPectraDataGas {
+                        batcher_address,
+                        total_pectra_data_gas,
+                        hypothetical_pectra_data_gas: pricing.map(|_| hypothetical_pectra_data_gas),
+                    }
                 },
             )
             .collect())
     }
 
-    async fn insert_daily_batcher_stats(&self, stats: &[DailyBatcherStats]) -> Result<()> {
-        let mut tx = self.pool.begin().await?;
+    async fn get_all_blob_utilization(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherBlobUtilization>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, analysis_result FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
 
-        for s in stats {
-            sqlx::query(
-                "INSERT OR IGNORE INTO daily_batcher_stats (
-                    batcher_address,
-                    snapshot_timestamp,
-                    total_eth_saved_wei,
-                    total_daily_txs,
-                    total_blob_data_gas,
-                    total_pectra_data_gas
-                ) VALUES (?, ?, ?, ?, ?, ?)",
-            )
-            .bind(s.batcher_address.to_lowercase())
-            .bind(s.snapshot_timestamp)
-            .bind(s.total_eth_saved_wei.to_string())
-            .bind(s.total_daily_txs as i64)
-            .bind(s.total_blob_data_gas as i64)
-            .bind(s.total_pectra_data_gas as i64)
-            .execute(&mut *tx)
-            .await?;
+        // (used bytes, capacity bytes, empty space wei spent)
+        let mut batcher_utilization: HashMap<String, (u64, u64, u128)> = HashMap::new();
+
+        for row in rows {
+            let batcher_address: String = row.get("batcher_address");
+            let analysis_result: String = row.get("analysis_result");
+            if let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) {
+                let blob_utilization = &analysis["blob_utilization"];
+                if blob_utilization.is_null() {
+                    // not a blob transaction
+                    continue;
+                }
+                let used_bytes = blob_utilization["used_bytes"].as_u64().unwrap_or(0);
+                let capacity_bytes = blob_utilization["capacity_bytes"].as_u64().unwrap_or(0);
+                let empty_space_wei_spent = blob_utilization["empty_space_wei_spent"]
+                    .as_u64()
+                    .unwrap_or(0) as u128;
+                let entry = batcher_utilization.entry(batcher_address).or_default();
+                entry.0 += used_bytes;
+                entry.1 += capacity_bytes;
+                entry.2 += empty_space_wei_spent;
+            }
         }
 
-        tx.commit().await?;
-        Ok(())
+        Ok(batcher_utilization
+            .into_iter()
+            .map(
+                |(
+                    batcher_address,
+                    (total_used_bytes, total_capacity_bytes, total_empty_space_wei_spent),
+                )| {
+                    BatcherBlobUtilization {
+                        batcher_address,
+                        total_used_bytes,
+                        total_capacity_bytes,
+                        avg_utilization_pct: utilization_pct(
+                            total_used_bytes,
+                            total_capacity_bytes,
+                        ),
+                        total_empty_space_wei_spent,
+                    }
+                },
+            )
+            .collect())
     }
 
-    async fn get_recent_daily_stats(
+    async fn get_blob_sharing_simulation(
         &self,
-        limit_per_batcher: i64,
-    ) -> Result<Vec<DailyBatcherStats>> {
+        start_timestamp: i64,
+        end_timestamp: i64,
+        window_blocks: u64,
+    ) -> Result<BlobSharingSimulation> {
         let rows = sqlx::query(
-            "SELECT batcher_address, snapshot_timestamp, total_eth_saved_wei, total_daily_txs, total_blob_data_gas, total_pectra_data_gas FROM (
-                SELECT *, ROW_NUMBER() OVER (PARTITION BY batcher_address ORDER BY snapshot_timestamp DESC) as rn
-                FROM daily_batcher_stats
-            ) WHERE rn <= ?
-            ORDER BY batcher_address, snapshot_timestamp ASC"
+            "SELECT batcher_address, analysis_result FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
         )
-        .bind(limit_per_batcher)
+        .bind(start_timestamp)
+        .bind(end_timestamp)
         .fetch_all(&self.pool)
         .await?;
 
-        let mut stats = Vec::with_capacity(rows.len());
+        let mut batches = Vec::new();
+
         for row in rows {
             let batcher_address: String = row.get("batcher_address");
-            let snapshot_timestamp: i64 = row.get("snapshot_timestamp");
-            let total_eth_saved_wei_str: String = row.get("total_eth_saved_wei");
-            let total_eth_saved_wei: u128 = total_eth_saved_wei_str.parse().unwrap_or(0);
-            let total_daily_txs: i64 = row.get("total_daily_txs");
-            let total_blob_data_gas: i64 = row.get("total_blob_data_gas");
-            let total_pectra_data_gas: i64 = row.get("total_pectra_data_gas");
-
-            stats.push(DailyBatcherStats {
+            let analysis_result: String = row.get("analysis_result");
+            let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) else {
+                continue;
+            };
+            // calldata batches and batches tracked before the block number was recorded are
+            // left out
+            let (Some(block_number), Some(blob_gas_used), Some(blob_gas_price), Some(used_bytes)) = (
+                analysis["block_number"].as_u64(),
+                analysis["blob_gas_used"].as_u64().filter(|gas| *gas > 0),
+                analysis["blob_gas_price"].as_u64(),
+                analysis["blob_utilization"]["used_bytes"].as_u64(),
+            ) else {
+                continue;
+            };
+            batches.push(BlobBatch {
                 batcher_address,
-                snapshot_timestamp,
-                total_eth_saved_wei,
-                total_daily_txs: total_daily_txs as u64,
-                total_blob_data_gas: total_blob_data_gas as u64,
-                total_pectra_data_gas: total_pectra_data_gas as u64,
+                block_number,
+                used_bytes,
+                blob_count: blob_gas_used / GAS_PER_BLOB,
+                blob_gas_price: blob_gas_price as u128,
             });
         }
-        Ok(stats)
+
+        Ok(simulate_blob_sharing(&batches, window_blocks))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::{SystemTime, UNIX_EPOCH};
-    use tempfile::NamedTempFile;
+    async fn get_all_l2_tx_cost(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        fiat: Option<FiatConversion<'_>>,
+    ) -> Result<Vec<BatcherL2TxCost>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, analysis_result, l2_tx_count, timestamp FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0 AND l2_tx_count IS NOT NULL",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
 
-    async fn create_test_database() -> Result<SqliteDatabase> {
-        let temp_file = NamedTempFile::new()?;
-        let db_path = temp_file.path().to_str().unwrap();
-        let db = SqliteDatabase::new(db_path, 0).await?;
+        // (l2 tx count, l1 wei spent, l1 fiat spent)
+        let mut batcher_l2_tx_cost: HashMap<String, (u64, u128, Option<f64>)> = HashMap::new();
 
-        // prevent the temp file from being deleted
-        std::mem::forget(temp_file);
+        let mut eth_prices = BatchEthPrices::new(fiat);
 
-        Ok(db)
-    }
+        for row in rows {
+            let batcher_address: String = row.get("batcher_address");
+            let analysis_result: String = row.get("analysis_result");
+            let l2_tx_count: i64 = row.get("l2_tx_count");
+            let timestamp: i64 = row.get("timestamp");
+            if let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) {
+                // the transactions of a channel spanning several batches are not counted, so
+                // neither is the cost of its batches
+                let incomplete_channel_count =
+                    analysis["l2_batch"]["op_stack"]["incomplete_channel_count"]
+                        .as_u64()
+                        .unwrap_or(0);
+                if l2_tx_count == 0 || incomplete_channel_count > 0 {
+                    continue;
+                }
+                // the actual cost of the batch: gas and, for blob txs only, blob gas
+                let gas_used = analysis["gas_used"].as_u64().unwrap_or(0) as u128;
+                let gas_price = analysis["gas_price"].as_u64().unwrap_or(0) as u128;
+                let blob_gas_used = analysis["blob_gas_used"].as_u64().unwrap_or(0) as u128;
+                let blob_gas_price = analysis["blob_gas_price"].as_u64().unwrap_or(0) as u128;
+                let l1_wei_spent = gas_used * gas_price + blob_gas_used * blob_gas_price;
+                let eth_price = eth_prices.price(&analysis, timestamp).await;
+                let entry =
+                    batcher_l2_tx_cost
+                        .entry(batcher_address)
+                        .or_insert((0, 0, fiat.map(|_| 0.0)));
+                entry.0 += l2_tx_count as u64;
+                entry.1 += l1_wei_spent;
+                add_fiat(&mut entry.2, l1_wei_spent, eth_price);
+            }
+        }
 
-    #[tokio::test]
-    async fn test_case_insensitive_batcher_address_search() -> Result<()> {
-        let db = create_test_database().await?;
+        Ok(batcher_l2_tx_cost
+            .into_iter()
+            .map(
+                |(
+                    batcher_address,
+                    (total_l2_tx_count, total_l1_wei_spent, total_l1_fiat_spent),
+                )| {
+                    BatcherL2TxCost {
+                        batcher_address,
+                        total_l2_tx_count,
+                        total_l1_wei_spent,
+                        avg_l1_wei_per_l2_tx: total_l1_wei_spent
+                            .checked_div(total_l2_tx_count as u128)
+                            .unwrap_or(0),
+                        total_l1_fiat_spent,
+                        avg_l1_fiat_per_l2_tx: total_l1_fiat_spent
+                            .filter(|_| total_l2_tx_count > 0)
+                            .map(|total_l1_fiat_spent| {
+                                total_l1_fiat_spent / total_l2_tx_count as f64
+                            }),
+                    }
+                },
+            )
+            .collect())
+    }
 
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+    async fn get_all_eip_7918_extra_cost(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherEip7918ExtraCost>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, analysis_result FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
 
-        // create test data with mixed case address
-        let mixed_case_address = "0x5050F69a9786F081509234F1a7F4684b5E5b76C9"; // Mixed case
-        let batch = TrackedBatch {
-            id: None,
-            tx_hash: "0xtest123".to_string(),
-            batcher_address: mixed_case_address.to_string(),
-            analysis_result: r#"{"blob_gas_used": 100000, "eip_7623_calldata_gas": 5000, "blob_data_wei_spent": 2000000000000000, "eip_7623_calldata_wei_spent": 3000000000000000}"#.to_string(),
-            timestamp: now,
-            last_analyzed_block: None,
-        };
+        let mut batcher_extra_cost: HashMap<String, u128> = HashMap::new();
+
+        for row in rows {
+            let batcher_address: String = row.get("batcher_address");
+            let analysis_result: String = row.get("analysis_result");
+            if let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) {
+                // only the blob batches pay the blob base fee
+                if analysis["blob_gas_used"].as_u64().unwrap_or(0) == 0 {
+                    continue;
+                }
+                let (Some(blob_data_wei_spent), Some(eip_7918_blob_data_wei_spent)) = (
+                    analysis["blob_data_wei_spent"].as_u64(),
+                    analysis["eip_7918_blob_data_wei_spent"].as_u64(),
+                ) else {
+                    continue;
+                };
+                *batcher_extra_cost.entry(batcher_address).or_insert(0) +=
+                    eip_7918_blob_data_wei_spent.saturating_sub(blob_data_wei_spent) as u128;
+            }
+        }
+
+        Ok(batcher_extra_cost
+            .into_iter()
+            .map(
+                |(batcher_address, total_eip_7918_extra_wei)| BatcherEip7918ExtraCost {
+                    batcher_address,
+                    total_eip_7918_extra_wei,
+                },
+            )
+            .collect())
+    }
+
+    async fn get_all_compression(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+        fiat: Option<FiatConversion<'_>>,
+    ) -> Result<Vec<BatcherCompression>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, analysis_result, timestamp FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut batcher_compression: HashMap<
+            (String, CompressionAlgorithm, u32),
+            BatcherCompression,
+        > = HashMap::new();
+
+        let mut eth_prices = BatchEthPrices::new(fiat);
+
+        for row in rows {
+            let batcher_address: String = row.get("batcher_address");
+            let analysis_result: String = row.get("analysis_result");
+            let timestamp: i64 = row.get("timestamp");
+            let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) else {
+                continue;
+            };
+            // batches analyzed without compression have no results
+            let Some(results) = analysis["compression"]["results"].as_array() else {
+                continue;
+            };
+            let eth_price = eth_prices.price(&analysis, timestamp).await;
+            let payload_size = analysis["compression"]["payload_size"]
+                .as_u64()
+                .unwrap_or(0);
+            for result in results {
+                let Ok(algorithm) =
+                    serde_json::from_value::<CompressionAlgorithm>(result["algorithm"].clone())
+                else {
+                    continue;
+                };
+                let level = result["level"].as_u64().unwrap_or(0) as u32;
+                let entry = batcher_compression
+                    .entry((batcher_address.clone(), algorithm, level))
+                    .or_insert_with(|| BatcherCompression {
+                        batcher_address: batcher_address.clone(),
+                        algorithm,
+                        level,
+                        batch_count: 0,
+                        total_payload_size: 0,
+                        total_compressed_size: 0,
+                        compression_ratio: 0.0,
+                        total_eip_7623_calldata_wei_spent: 0,
+                        total_blob_data_wei_spent: 0,
+                        total_eip_7623_calldata_fiat_spent: fiat.map(|_| 0.0),
+                        total_blob_data_fiat_spent: fiat.map(|_| 0.0),
+                    });
+                let eip_7623_calldata_wei_spent =
+                    result["eip_7623_calldata_wei_spent"].as_u64().unwrap_or(0) as u128;
+                let blob_data_wei_spent =
+                    result["blob_data_wei_spent"].as_u64().unwrap_or(0) as u128;
+                entry.batch_count += 1;
+                entry.total_payload_size += payload_size;
+                entry.total_compressed_size += result["compressed_size"].as_u64().unwrap_or(0);
+                entry.total_eip_7623_calldata_wei_spent += eip_7623_calldata_wei_spent;
+                entry.total_blob_data_wei_spent += blob_data_wei_spent;
+                add_fiat(
+                    &mut entry.total_eip_7623_calldata_fiat_spent,
+                    eip_7623_calldata_wei_spent,
+                    eth_price,
+                );
+                add_fiat(
+                    &mut entry.total_blob_data_fiat_spent,
+                    blob_data_wei_spent,
+                    eth_price,
+                );
+            }
+        }
+
+        let mut batchers: Vec<BatcherCompression> = batcher_compression
+            .into_values()
+            .map(|mut batcher| {
+                if batcher.total_compressed_size > 0 {
+                    batcher.compression_ratio =
+                        batcher.total_payload_size as f64 / batcher.total_compressed_size as f64;
+                }
+                batcher
+            })
+            .collect();
+        batchers.sort_by(|a, b| {
+            (&a.batcher_address, a.algorithm.to_string(), a.level).cmp(&(
+                &b.batcher_address,
+                b.algorithm.to_string(),
+                b.level,
+            ))
+        });
+        Ok(batchers)
+    }
+
+    async fn get_all_alt_da_cost(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherAltDaCost>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, analysis_result FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut batcher_alt_da: HashMap<(String, String), BatcherAltDaCost> = HashMap::new();
+
+        for row in rows {
+            let batcher_address: String = row.get("batcher_address");
+            let analysis_result: String = row.get("analysis_result");
+            let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) else {
+                continue;
+            };
+            // batches analyzed without a price table have no costs
+            let Some(costs) = analysis["alt_da"]["costs"].as_array() else {
+                continue;
+            };
+            let payload_size = analysis["alt_da"]["payload_size"].as_u64().unwrap_or(0);
+            let blob_data_wei_spent = analysis["blob_data_wei_spent"].as_u64().unwrap_or(0) as u128;
+            let eip_7623_calldata_wei_spent = analysis["eip_7623_calldata_wei_spent"]
+                .as_u64()
+                .unwrap_or(0) as u128;
+            for cost in costs {
+                let Some(layer) = cost["layer"].as_str() else {
+                    continue;
+                };
+                let entry = batcher_alt_da
+                    .entry((batcher_address.clone(), layer.to_string()))
+                    .or_insert_with(|| BatcherAltDaCost {
+                        batcher_address: batcher_address.clone(),
+                        layer: layer.to_string(),
+                        batch_count: 0,
+                        total_payload_size: 0,
+                        total_alt_da_wei_spent: 0,
+                        total_blob_data_wei_spent: 0,
+                        total_eip_7623_calldata_wei_spent: 0,
+                    });
+                entry.batch_count += 1;
+                entry.total_payload_size += payload_size;
+                entry.total_alt_da_wei_spent += cost["wei_spent"].as_u64().unwrap_or(0) as u128;
+                entry.total_blob_data_wei_spent += blob_data_wei_spent;
+                entry.total_eip_7623_calldata_wei_spent += eip_7623_calldata_wei_spent;
+            }
+        }
+
+        let mut batchers: Vec<BatcherAltDaCost> = batcher_alt_da.into_values().collect();
+        batchers
+            .sort_by(|a, b| (&a.batcher_address, &a.layer).cmp(&(&b.batcher_address, &b.layer)));
+        Ok(batchers)
+    }
+
+    async fn get_all_byte_profile(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherByteProfile>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, analysis_result FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut batcher_byte_profile: HashMap<String, BatcherByteProfile> = HashMap::new();
+
+        for row in rows {
+            let batcher_address: String = row.get("batcher_address");
+            let analysis_result: String = row.get("analysis_result");
+            let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) else {
+                continue;
+            };
+            // the data of blob batches is the payload of their blobs
+            let breakdown = if analysis["blob_payload"].is_object() {
+                &analysis["blob_payload"]
+            } else {
+                &analysis["calldata"]
+            };
+            // batches analyzed before the byte breakdown was recorded
+            let (Some(length), Some(zero_bytes), Some(tokens)) = (
+                breakdown["length"].as_u64(),
+                breakdown["zero_bytes"].as_u64(),
+                breakdown["tokens"].as_u64(),
+            ) else {
+                continue;
+            };
+            let entry = batcher_byte_profile
+                .entry(batcher_address.clone())
+                .or_insert_with(|| BatcherByteProfile {
+                    batcher_address,
+                    batch_count: 0,
+                    total_data_bytes: 0,
+                    total_zero_bytes: 0,
+                    total_data_tokens: 0,
+                    floor_applied_txs: 0,
+                });
+            entry.batch_count += 1;
+            entry.total_data_bytes += length;
+            entry.total_zero_bytes += zero_bytes;
+            entry.total_data_tokens += tokens;
+            if analysis["floor_applied"].as_bool().unwrap_or(false) {
+                entry.floor_applied_txs += 1;
+            }
+        }
+
+        let mut batchers: Vec<BatcherByteProfile> = batcher_byte_profile.into_values().collect();
+        batchers.sort_by(|a, b| a.batcher_address.cmp(&b.batcher_address));
+        Ok(batchers)
+    }
+
+    async fn get_all_fee_breakdown(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherFeeBreakdown>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, analysis_result FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state'",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // (batch count, base fee, priority fee, blob fee, gas used, max blob fee bid)
+        let mut batcher_fees: HashMap<String, (u64, u128, u128, u128, u128, u128)> = HashMap::new();
+
+        for row in rows {
+            let batcher_address: String = row.get("batcher_address");
+            let analysis_result: String = row.get("analysis_result");
+            let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) else {
+                continue;
+            };
+            let fees = &analysis["fees"];
+            // batches analyzed before the fee breakdown was recorded
+            let (Some(base_fee_wei_burned), Some(priority_fee_wei_paid), Some(blob_fee_wei_burned)) = (
+                fees["base_fee_wei_burned"].as_u64(),
+                fees["priority_fee_wei_paid"].as_u64(),
+                fees["blob_fee_wei_burned"].as_u64(),
+            ) else {
+                continue;
+            };
+            let max_blob_fee_wei = fees["max_fee_per_blob_gas"].as_u64().unwrap_or(0) as u128
+                * analysis["blob_gas_used"].as_u64().unwrap_or(0) as u128;
+            let entry = batcher_fees
+                .entry(batcher_address)
+                .or_insert((0, 0, 0, 0, 0, 0));
+            entry.0 += 1;
+            entry.1 += base_fee_wei_burned as u128;
+            entry.2 += priority_fee_wei_paid as u128;
+            entry.3 += blob_fee_wei_burned as u128;
+            entry.4 += analysis["gas_used"].as_u64().unwrap_or(0) as u128;
+            entry.5 += max_blob_fee_wei;
+        }
+
+        let mut batchers: Vec<BatcherFeeBreakdown> = batcher_fees
+            .into_iter()
+            .map(
+                |(
+                    batcher_address,
+                    (
+                        batch_count,
+                        total_base_fee_wei_burned,
+                        total_priority_fee_wei_paid,
+                        total_blob_fee_wei_burned,
+                        total_gas_used,
+                        total_max_blob_fee_wei,
+                    ),
+                )| {
+                    let total_fee_wei = total_base_fee_wei_burned
+                        + total_priority_fee_wei_paid
+                        + total_blob_fee_wei_burned;
+                    BatcherFeeBreakdown {
+                        batcher_address,
+                        batch_count,
+                        total_base_fee_wei_burned,
+                        total_priority_fee_wei_paid,
+                        total_blob_fee_wei_burned,
+                        priority_fee_pct: if total_fee_wei > 0 {
+                            total_priority_fee_wei_paid as f64 / total_fee_wei as f64 * 100.0
+                        } else {
+                            0.0
+                        },
+                        avg_priority_fee_per_gas: total_priority_fee_wei_paid
+                            .checked_div(total_gas_used)
+                            .unwrap_or(0),
+                        blob_fee_bid_ratio: (total_blob_fee_wei_burned > 0).then(|| {
+                            total_max_blob_fee_wei as f64 / total_blob_fee_wei_burned as f64
+                        }),
+                    }
+                },
+            )
+            .collect();
+        batchers.sort_by(|a, b| a.batcher_address.cmp(&b.batcher_address));
+        Ok(batchers)
+    }
+
+    async fn get_all_wasted_spend(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherWastedSpend>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, analysis_result FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status = 0",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut batcher_wasted_spend: HashMap<String, (u64, u128)> = HashMap::new();
+
+        for row in rows {
+            let batcher_address: String = row.get("batcher_address");
+            let analysis_result: String = row.get("analysis_result");
+            if let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) {
+                let gas_wei_spent = analysis["gas_used"].as_u64().unwrap_or(0) as u128
+                    * analysis["gas_price"].as_u64().unwrap_or(0) as u128;
+                // the blob data is paid even if the transaction reverts
+                let blob_data_wei_spent = if analysis["blob_gas_used"].as_u64().unwrap_or(0) > 0 {
+                    analysis["blob_data_wei_spent"].as_u64().unwrap_or(0) as u128
+                } else {
+                    0
+                };
+                let entry = batcher_wasted_spend
+                    .entry(batcher_address)
+                    .or_insert((0, 0));
+                entry.0 += 1;
+                entry.1 += gas_wei_spent + blob_data_wei_spent;
+            }
+        }
+
+        let mut batchers: Vec<BatcherWastedSpend> = batcher_wasted_spend
+            .into_iter()
+            .map(
+                |(batcher_address, (reverted_tx_count, total_wei_burned))| BatcherWastedSpend {
+                    batcher_address,
+                    reverted_tx_count,
+                    total_wei_burned,
+                },
+            )
+            .collect();
+        batchers.sort_by(|a, b| a.batcher_address.cmp(&b.batcher_address));
+        Ok(batchers)
+    }
+
+    async fn get_all_regret(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherRegret>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, analysis_result FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut batcher_regret: HashMap<String, BatcherRegret> = HashMap::new();
+
+        for row in rows {
+            let batcher_address: String = row.get("batcher_address");
+            let analysis_result: String = row.get("analysis_result");
+            let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) else {
+                continue;
+            };
+            let Some((_, chosen_mode_wei, alternative_mode_wei)) = batch_mode_costs(&analysis)
+            else {
+                continue;
+            };
+            let entry = batcher_regret
+                .entry(batcher_address.clone())
+                .or_insert_with(|| BatcherRegret {
+                    batcher_address,
+                    batch_count: 0,
+                    wrong_mode_txs: 0,
+                    total_regret_wei: 0,
+                    net_regret_wei: 0,
+                });
+            entry.batch_count += 1;
+            if chosen_mode_wei > alternative_mode_wei {
+                entry.wrong_mode_txs += 1;
+                entry.total_regret_wei += chosen_mode_wei - alternative_mode_wei;
+            }
+            entry.net_regret_wei += chosen_mode_wei as i128 - alternative_mode_wei as i128;
+        }
+
+        let mut batchers: Vec<BatcherRegret> = batcher_regret.into_values().collect();
+        batchers.sort_by(|a, b| a.batcher_address.cmp(&b.batcher_address));
+        Ok(batchers)
+    }
+
+    async fn get_wrong_mode_batches(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<WrongModeBatch>> {
+        let rows = sqlx::query(
+            "SELECT tx_hash, batcher_address, analysis_result, timestamp FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut batches = Vec::new();
+
+        for row in rows {
+            let analysis_result: String = row.get("analysis_result");
+            let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) else {
+                continue;
+            };
+            let Some((chosen_mode, chosen_mode_wei, alternative_mode_wei)) =
+                batch_mode_costs(&analysis)
+            else {
+                continue;
+            };
+            if chosen_mode_wei <= alternative_mode_wei {
+                continue;
+            }
+            batches.push(WrongModeBatch {
+                batcher_address: row.get("batcher_address"),
+                tx_hash: row.get("tx_hash"),
+                timestamp: row.get("timestamp"),
+                chosen_mode,
+                chosen_mode_wei,
+                alternative_mode_wei,
+                regret_wei: chosen_mode_wei - alternative_mode_wei,
+            });
+        }
+
+        batches.sort_by_key(|batch| std::cmp::Reverse(batch.regret_wei));
+        Ok(batches)
+    }
+
+    async fn get_all_timing_efficiency(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<BatcherTimingEfficiency>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, analysis_result FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ? 
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut batcher_timing: HashMap<String, BatcherTimingEfficiency> = HashMap::new();
+
+        for row in rows {
+            let batcher_address: String = row.get("batcher_address");
+            let analysis_result: String = row.get("analysis_result");
+            let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) else {
+                continue;
+            };
+            // batches analyzed without a timing window, or before the blocks after them were
+            // produced, are left out
+            let timing = &analysis["timing"];
+            if timing["complete"].as_bool() != Some(true) {
+                continue;
+            }
+            let (Some(actual_wei), Some(cheapest_wei), Some(timing_premium_wei)) = (
+                timing["actual_wei"].as_u64(),
+                timing["cheapest_wei"].as_u64(),
+                timing["timing_premium_wei"].as_u64(),
+            ) else {
+                continue;
+            };
+            let entry = batcher_timing
+                .entry(batcher_address.clone())
+                .or_insert_with(|| BatcherTimingEfficiency {
+                    batcher_address,
+                    timed_txs: 0,
+                    optimal_timing_txs: 0,
+                    total_actual_wei: 0,
+                    total_cheapest_wei: 0,
+                    total_timing_premium_wei: 0,
+                });
+            entry.timed_txs += 1;
+            if timing_premium_wei == 0 {
+                entry.optimal_timing_txs += 1;
+            }
+            entry.total_actual_wei += actual_wei as u128;
+            entry.total_cheapest_wei += cheapest_wei as u128;
+            entry.total_timing_premium_wei += timing_premium_wei as u128;
+        }
+
+        let mut batchers: Vec<BatcherTimingEfficiency> = batcher_timing.into_values().collect();
+        batchers.sort_by(|a, b| a.batcher_address.cmp(&b.batcher_address));
+        Ok(batchers)
+    }
+
+    async fn save_break_even_fee(&self, fee: &BreakEvenFee) -> Result<()> {
+        // a block can be sampled both as the latest block and as the block of a batch
+        sqlx::query(
+            "INSERT OR IGNORE INTO break_even_fees (block_number, timestamp, base_fee_per_gas, blob_base_fee, break_even_blob_base_fee)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(fee.block_number as i64)
+        .bind(fee.timestamp)
+        .bind(fee.base_fee_per_gas as i64)
+        .bind(fee.blob_base_fee.to_string())
+        .bind(fee.break_even_blob_base_fee.to_string())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_hourly_break_even_fees(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<HourlyBreakEvenFee>> {
+        let fee_rows = sqlx::query(
+            "SELECT timestamp, base_fee_per_gas, blob_base_fee, break_even_blob_base_fee
+             FROM break_even_fees WHERE timestamp >= ? AND timestamp <= ?",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // (samples, base fee, blob base fee, break-even blob base fee) sums per hour
+        let mut hourly_sums: HashMap<i64, (u64, u128, u128, u128)> = HashMap::new();
+        for row in fee_rows {
+            let timestamp: i64 = row.get("timestamp");
+            let base_fee_per_gas: i64 = row.get("base_fee_per_gas");
+            let blob_base_fee: String = row.get("blob_base_fee");
+            let break_even_blob_base_fee: String = row.get("break_even_blob_base_fee");
+            let entry = hourly_sums.entry(timestamp - timestamp % 3600).or_default();
+            entry.0 += 1;
+            entry.1 += base_fee_per_gas as u128;
+            entry.2 += blob_base_fee.parse::<u128>().unwrap_or(0);
+            entry.3 += break_even_blob_base_fee.parse::<u128>().unwrap_or(0);
+        }
+
+        // the batches are placed by the timestamp of their block, which precedes their tracking
+        let batch_rows = sqlx::query(
+            "SELECT tx_hash, batcher_address, analysis_result FROM l2_batches_txs 
+             WHERE timestamp >= ? AND timestamp <= ?
+             AND tx_hash != 'monitoring_state' AND status IS NOT 0",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp.saturating_add(TRACKING_DELAY_MARGIN))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut wrong_side_batches: HashMap<i64, Vec<WrongSideBatch>> = HashMap::new();
+        for row in batch_rows {
+            let analysis_result: String = row.get("analysis_result");
+            let Ok(analysis) = serde_json::from_str::<serde_json::Value>(&analysis_result) else {
+                continue;
+            };
+            // batches analyzed before the break-even blob base fee was computed are left out
+            let (Some(timestamp), Some(blob_base_fee), Some(break_even_blob_base_fee)) = (
+                analysis["timestamp"].as_i64(),
+                analysis["blob_gas_price"].as_u64(),
+                analysis["break_even_blob_base_fee"].as_u64(),
+            ) else {
+                continue;
+            };
+            if timestamp < start_timestamp || timestamp > end_timestamp {
+                continue;
+            }
+            let (blob_base_fee, break_even_blob_base_fee) =
+                (blob_base_fee as u128, break_even_blob_base_fee as u128);
+            let chosen_mode = if analysis["blob_gas_used"].as_u64().unwrap_or(0) > 0 {
+                PostingMode::Blob
+            } else {
+                PostingMode::Calldata
+            };
+            let wrong_side = match chosen_mode {
+                PostingMode::Blob => blob_base_fee > break_even_blob_base_fee,
+                PostingMode::Calldata => blob_base_fee < break_even_blob_base_fee,
+            };
+            if !wrong_side {
+                continue;
+            }
+            wrong_side_batches
+                .entry(timestamp - timestamp % 3600)
+                .or_default()
+                .push(WrongSideBatch {
+                    batcher_address: row.get("batcher_address"),
+                    tx_hash: row.get("tx_hash"),
+                    timestamp,
+                    chosen_mode,
+                    blob_base_fee,
+                    break_even_blob_base_fee,
+                });
+        }
+
+        let mut points: Vec<HourlyBreakEvenFee> = hourly_sums
+            .into_iter()
+            .map(
+                |(hour, (samples, base_fee_per_gas, blob_base_fee, break_even_blob_base_fee))| {
+                    let blob_base_fee = blob_base_fee / samples as u128;
+                    let break_even_blob_base_fee = break_even_blob_base_fee / samples as u128;
+                    let mut wrong_side_batches =
+                        wrong_side_batches.remove(&hour).unwrap_or_default();
+                    wrong_side_batches.sort_by_key(|batch| batch.timestamp);
+                    HourlyBreakEvenFee {
+                        timestamp: hour,
+                        samples,
+                        base_fee_per_gas: (base_fee_per_gas / samples as u128) as u64,
+                        blob_base_fee,
+                        break_even_blob_base_fee,
+                        cheaper_mode: if blob_base_fee <= break_even_blob_base_fee {
+                            PostingMode::Blob
+                        } else {
+                            PostingMode::Calldata
+                        },
+                        wrong_side_batches,
+                    }
+                },
+            )
+            .collect();
+        points.sort_by_key(|point| point.timestamp);
+        Ok(points)
+    }
+
+    async fn insert_daily_batcher_stats(&self, stats: &[DailyBatcherStats]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        for s in stats {
+            sqlx::query(
+                "INSERT OR IGNORE INTO daily_batcher_stats (
+                    batcher_address,
+                    snapshot_timestamp,
+                    total_eth_saved_wei,
+                    total_daily_txs,
+                    total_blob_data_gas,
+                    total_pectra_data_gas,
+                    total_eip_7918_extra_wei,
+                    total_data_bytes,
+                    total_zero_bytes,
+                    total_data_tokens,
+                    floor_applied_txs,
+                    wrong_mode_txs,
+                    total_regret_wei,
+                    net_regret_wei,
+                    timed_txs,
+                    optimal_timing_txs,
+                    total_timing_premium_wei
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(s.batcher_address.to_lowercase())
+            .bind(s.snapshot_timestamp)
+            .bind(s.total_eth_saved_wei.to_string())
+            .bind(s.total_daily_txs as i64)
+            .bind(s.total_blob_data_gas as i64)
+            .bind(s.total_pectra_data_gas as i64)
+            .bind(
+                s.total_eip_7918_extra_wei
+                    .map(|extra_wei| extra_wei.to_string()),
+            )
+            .bind(s.total_data_bytes.map(|value| value as i64))
+            .bind(s.total_zero_bytes.map(|value| value as i64))
+            .bind(s.total_data_tokens.map(|value| value as i64))
+            .bind(s.floor_applied_txs.map(|value| value as i64))
+            .bind(s.wrong_mode_txs.map(|value| value as i64))
+            .bind(s.total_regret_wei.map(|value| value.to_string()))
+            .bind(s.net_regret_wei.map(|value| value.to_string()))
+            .bind(s.timed_txs.map(|value| value as i64))
+            .bind(s.optimal_timing_txs.map(|value| value as i64))
+            .bind(s.total_timing_premium_wei.map(|value| value.to_string()))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_recent_daily_stats(
+        &self,
+        limit_per_batcher: i64,
+    ) -> Result<Vec<DailyBatcherStats>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, snapshot_timestamp, total_eth_saved_wei, total_daily_txs, total_blob_data_gas, total_pectra_data_gas, total_eip_7918_extra_wei, total_data_bytes, total_zero_bytes, total_data_tokens, floor_applied_txs, wrong_mode_txs, total_regret_wei, net_regret_wei, timed_txs, optimal_timing_txs, total_timing_premium_wei FROM (
+                SELECT *, ROW_NUMBER() OVER (PARTITION BY batcher_address ORDER BY snapshot_timestamp DESC) as rn
+                FROM daily_batcher_stats
+            ) WHERE rn <= ?
+            ORDER BY batcher_address, snapshot_timestamp ASC"
+        )
+        .bind(limit_per_batcher)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(daily_batcher_stats_from_row).collect())
+    }
+
+    async fn get_daily_stats(
+        &self,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<Vec<DailyBatcherStats>> {
+        let rows = sqlx::query(
+            "SELECT batcher_address, snapshot_timestamp, total_eth_saved_wei, total_daily_txs, total_blob_data_gas, total_pectra_data_gas, total_eip_7918_extra_wei, total_data_bytes, total_zero_bytes, total_data_tokens, floor_applied_txs, wrong_mode_txs, total_regret_wei, net_regret_wei, timed_txs, optimal_timing_txs, total_timing_premium_wei
+             FROM daily_batcher_stats
+             WHERE snapshot_timestamp >= ? AND snapshot_timestamp <= ?
+             ORDER BY batcher_address, snapshot_timestamp ASC",
+        )
+        .bind(start_timestamp)
+        .bind(end_timestamp)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(daily_batcher_stats_from_row).collect())
+    }
+}
+
+/// Read a row of the daily snapshot table.
+fn daily_batcher_stats_from_row(row: &SqliteRow) -> DailyBatcherStats {
+    let total_eth_saved_wei_str: String = row.get("total_eth_saved_wei");
+    let total_daily_txs: i64 = row.get("total_daily_txs");
+    let total_blob_data_gas: i64 = row.get("total_blob_data_gas");
+    let total_pectra_data_gas: i64 = row.get("total_pectra_data_gas");
+    let total_eip_7918_extra_wei: Option<String> = row.get("total_eip_7918_extra_wei");
+    let total_data_bytes: Option<i64> = row.get("total_data_bytes");
+    let total_zero_bytes: Option<i64> = row.get("total_zero_bytes");
+    let total_data_tokens: Option<i64> = row.get("total_data_tokens");
+    let floor_applied_txs: Option<i64> = row.get("floor_applied_txs");
+    let wrong_mode_txs: Option<i64> = row.get("wrong_mode_txs");
+    let total_regret_wei: Option<String> = row.get("total_regret_wei");
+    let net_regret_wei: Option<String> = row.get("net_regret_wei");
+    let timed_txs: Option<i64> = row.get("timed_txs");
+    let optimal_timing_txs: Option<i64> = row.get("optimal_timing_txs");
+    let total_timing_premium_wei: Option<String> = row.get("total_timing_premium_wei");
+
+    DailyBatcherStats {
+        batcher_address: row.get("batcher_address"),
+        snapshot_timestamp: row.get("snapshot_timestamp"),
+        total_eth_saved_wei: total_eth_saved_wei_str.parse().unwrap_or(0),
+        total_daily_txs: total_daily_txs as u64,
+        total_blob_data_gas: total_blob_data_gas as u64,
+        total_pectra_data_gas: total_pectra_data_gas as u64,
+        total_eip_7918_extra_wei: total_eip_7918_extra_wei
+            .and_then(|extra_wei| extra_wei.parse().ok()),
+        total_data_bytes: total_data_bytes.map(|value| value as u64),
+        total_zero_bytes: total_zero_bytes.map(|value| value as u64),
+        total_data_tokens: total_data_tokens.map(|value| value as u64),
+        floor_applied_txs: floor_applied_txs.map(|value| value as u64),
+        wrong_mode_txs: wrong_mode_txs.map(|value| value as u64),
+        total_regret_wei: total_regret_wei.and_then(|value| value.parse().ok()),
+        net_regret_wei: net_regret_wei.and_then(|value| value.parse().ok()),
+        timed_txs: timed_txs.map(|value| value as u64),
+        optimal_timing_txs: optimal_timing_txs.map(|value| value as u64),
+        total_timing_premium_wei: total_timing_premium_wei.and_then(|value| value.parse().ok()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use tempfile::NamedTempFile;
+
+    async fn create_test_database() -> Result<SqliteDatabase> {
+        let temp_file = NamedTempFile::new()?;
+        let db_path = temp_file.path().to_str().unwrap();
+        let db = SqliteDatabase::new(db_path, 0).await?;
+
+        // prevent the temp file from being deleted
+        std::mem::forget(temp_file);
+
+        Ok(db)
+    }
+
+    /// The batcher of the tracked batches of the tests.
+    const BATCHER_ADDRESS: &str = "0x5050f69a9786f081509234f1a7f4684b5e5b76c9";
+    /// Another batcher, for the tests comparing batchers.
+    const OTHER_BATCHER_ADDRESS: &str = "0x6887246668a3b87f54deb3b94ba47a6f63f32985";
+
+    /// A successful batch of [`BATCHER_ADDRESS`] tracked at 1600000000.
+    fn tracked_batch(tx_hash: &str, analysis_result: &str) -> TrackedBatch {
+        TrackedBatch {
+            id: None,
+            tx_hash: tx_hash.to_string(),
+            batcher_address: BATCHER_ADDRESS.to_string(),
+            analysis_result: analysis_result.to_string(),
+            timestamp: 1600000000,
+            last_analyzed_block: None,
+            l2_tx_count: None,
+            sequence_number: None,
+            status: Some(1),
+        }
+    }
+
+    /// A test database holding the given batches.
+    async fn database_with_batches(batches: &[TrackedBatch]) -> Result<SqliteDatabase> {
+        let db = create_test_database().await?;
+        for batch in batches {
+            db.save_tracked_batch(batch).await?;
+        }
+        Ok(db)
+    }
+
+    /// An empty daily snapshot of [`BATCHER_ADDRESS`].
+    fn daily_stats(snapshot_timestamp: i64) -> DailyBatcherStats {
+        DailyBatcherStats {
+            batcher_address: BATCHER_ADDRESS.to_string(),
+            snapshot_timestamp,
+            total_eth_saved_wei: 0,
+            total_daily_txs: 0,
+            total_blob_data_gas: 0,
+            total_pectra_data_gas: 0,
+            total_eip_7918_extra_wei: None,
+            total_data_bytes: None,
+            total_zero_bytes: None,
+            total_data_tokens: None,
+            floor_applied_txs: None,
+            wrong_mode_txs: None,
+            total_regret_wei: None,
+            net_regret_wei: None,
+            timed_txs: None,
+            optimal_timing_txs: None,
+            total_timing_premium_wei: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_case_insensitive_batcher_address_search() -> Result<()> {
+        let db = create_test_database().await?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        // create test data with mixed case address
+        let mixed_case_address = "0x5050F69a9786F081509234F1a7F4684b5E5b76C9"; // Mixed case
+        let batch = TrackedBatch {
+            id: None,
+            tx_hash: "0xtest123".to_string(),
+            batcher_address: mixed_case_address.to_string(),
+            analysis_result: r#"{"blob_gas_used": 100000, "eip_7623_calldata_gas": 5000, "blob_data_wei_spent": 2000000000000000, "eip_7623_calldata_wei_spent": 3000000000000000}"#.to_string(),
+            timestamp: now,
+            last_analyzed_block: None,
+            l2_tx_count: None,
+            sequence_number: None,
+            status: None,
+        };
 
         // save the batch (should be stored in lowercase)
         db.save_tracked_batch(&batch).await?;
@@ -763,6 +2122,9 @@ mod tests {
             analysis_result: r#"{"blob_gas_used": 50000}"#.to_string(),
             timestamp: now,
             last_analyzed_block: None,
+            l2_tx_count: None,
+            sequence_number: None,
+            status: None,
         };
 
         // save the batch
@@ -781,4 +2143,727 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_l2_tx_cost_skips_undecoded_batches() -> Result<()> {
+        let analysis_result =
+            r#"{"gas_used": 21000, "gas_price": 10, "blob_gas_used": 131072, "blob_gas_price": 2}"#;
+        let db = database_with_batches(&[
+            TrackedBatch {
+                l2_tx_count: Some(100),
+                ..tracked_batch("0xdecoded", analysis_result)
+            },
+            tracked_batch("0xundecoded", analysis_result),
+            // the first batch of a channel continued in the next batch
+            TrackedBatch {
+                l2_tx_count: Some(0),
+                ..tracked_batch(
+                    "0xchannelstart",
+                    r#"{"gas_used": 21000, "gas_price": 10, "blob_gas_used": 131072, "blob_gas_price": 2, "l2_batch": {"op_stack": {"frame_count": 1, "channel_count": 0, "incomplete_channel_count": 1}}}"#,
+                )
+            },
+            // a complete channel followed by the start of the next one
+            TrackedBatch {
+                l2_tx_count: Some(50),
+                ..tracked_batch(
+                    "0xchannelend",
+                    r#"{"gas_used": 21000, "gas_price": 10, "blob_gas_used": 131072, "blob_gas_price": 2, "l2_batch": {"op_stack": {"frame_count": 2, "channel_count": 1, "incomplete_channel_count": 1}}}"#,
+                )
+            },
+        ])
+        .await?;
+
+        let batchers = db.get_all_l2_tx_cost(1500000000, 1700000000, None).await?;
+        assert_eq!(
+            batchers,
+            vec![BatcherL2TxCost {
+                batcher_address: BATCHER_ADDRESS.to_string(),
+                total_l2_tx_count: 100,
+                total_l1_wei_spent: 21000 * 10 + 131072 * 2,
+                avg_l1_wei_per_l2_tx: (21000 * 10 + 131072 * 2) / 100,
+                total_l1_fiat_spent: None,
+                avg_l1_fiat_per_l2_tx: None,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hypothetical_pricing_skips_batches_without_bytes() -> Result<()> {
+        // 100 zero bytes and 50 non-zero bytes, 300 tokens
+        let db = database_with_batches(&[
+            tracked_batch(
+                "0xwithbytes",
+                r#"{"gas_price": 10, "blob_data_wei_spent": 1000, "eip_7623_calldata_gas": 3000, "eip_7623_calldata_wei_spent": 30000, "zero_bytes": 100, "non_zero_bytes": 50}"#,
+            ),
+            tracked_batch(
+                "0xwithoutbytes",
+                r#"{"gas_price": 10, "blob_data_wei_spent": 1000, "eip_7623_calldata_gas": 500, "eip_7623_calldata_wei_spent": 5000}"#,
+            ),
+        ])
+        .await?;
+
+        let pricing = Some(CalldataPricing::eip_7976());
+        let eth_saved = db
+            .get_all_eth_saved_data(1500000000, 1700000000, pricing, None)
+            .await?;
+        assert_eq!(
+            eth_saved,
+            vec![BatcherEthSaved {
+                batcher_address: BATCHER_ADDRESS.to_string(),
+                total_eth_saved_wei: 29000 + 4000,
+                hypothetical_eth_saved_wei: Some(15 * 300 * 10 - 1000),
+                total_eth_saved_fiat: None,
+            }]
+        );
+        let pectra_data_gas = db
+            .get_all_total_pectra_data_gas(1500000000, 1700000000, pricing)
+            .await?;
+        assert_eq!(
+            pectra_data_gas,
+            vec![BatcherPectraDataGas {
+                batcher_address: BATCHER_ADDRESS.to_string(),
+                total_pectra_data_gas: 3500,
+                hypothetical_pectra_data_gas: Some(15 * 300),
+            }]
+        );
+        let pectra_data_gas = db
+            .get_all_total_pectra_data_gas(1500000000, 1700000000, None)
+            .await?;
+        assert_eq!(pectra_data_gas[0].hypothetical_pectra_data_gas, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_eip_7918_extra_cost_daily_series() -> Result<()> {
+        let db = database_with_batches(&[
+            tracked_batch(
+                "0xblob",
+                r#"{"blob_gas_used": 131072, "blob_data_wei_spent": 131072, "eip_7918_blob_data_wei_spent": 1310720}"#,
+            ),
+            // the blob counterfactual of calldata batches is not paid
+            tracked_batch(
+                "0xcalldata",
+                r#"{"blob_gas_used": 0, "blob_data_wei_spent": 131072, "eip_7918_blob_data_wei_spent": 1310720}"#,
+            ),
+            tracked_batch(
+                "0xoldblob",
+                r#"{"blob_gas_used": 131072, "blob_data_wei_spent": 131072}"#,
+            ),
+        ])
+        .await?;
+        let extra_cost = db
+            .get_all_eip_7918_extra_cost(1500000000, 1700000000)
+            .await?;
+        assert_eq!(
+            extra_cost,
+            vec![BatcherEip7918ExtraCost {
+                batcher_address: BATCHER_ADDRESS.to_string(),
+                total_eip_7918_extra_wei: 1310720 - 131072,
+            }]
+        );
+
+        let snapshots = [
+            daily_stats(86_400),
+            DailyBatcherStats {
+                total_eip_7918_extra_wei: Some(1179648),
+                ..daily_stats(2 * 86_400)
+            },
+            DailyBatcherStats {
+                total_eip_7918_extra_wei: Some(0),
+                ..daily_stats(3 * 86_400)
+            },
+        ];
+        db.insert_daily_batcher_stats(&snapshots).await?;
+        let daily_stats = db.get_daily_stats(86_400, 2 * 86_400).await?;
+        assert_eq!(daily_stats, snapshots[..2]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_byte_profile_of_posted_data() -> Result<()> {
+        let db = database_with_batches(&[
+            // the payload of the blobs is counted, not the calldata
+            tracked_batch(
+                "0xblob",
+                r#"{"floor_applied": false, "calldata": {"length": 0, "zero_bytes": 0, "tokens": 0}, "blob_payload": {"length": 1000, "zero_bytes": 100, "tokens": 3700}}"#,
+            ),
+            tracked_batch(
+                "0xcalldata",
+                r#"{"floor_applied": true, "calldata": {"length": 200, "zero_bytes": 50, "tokens": 650}, "blob_payload": null}"#,
+            ),
+            tracked_batch(
+                "0xold",
+                r#"{"floor_applied": true, "zero_bytes": 50, "non_zero_bytes": 150}"#,
+            ),
+        ])
+        .await?;
+        let byte_profile = db.get_all_byte_profile(1500000000, 1700000000).await?;
+        assert_eq!(
+            byte_profile,
+            vec![BatcherByteProfile {
+                batcher_address: BATCHER_ADDRESS.to_string(),
+                batch_count: 2,
+                total_data_bytes: 1200,
+                total_zero_bytes: 150,
+                total_data_tokens: 4350,
+                floor_applied_txs: 1,
+            }]
+        );
+
+        let snapshot = DailyBatcherStats {
+            total_daily_txs: 2,
+            total_eip_7918_extra_wei: Some(0),
+            total_data_bytes: Some(1200),
+            total_zero_bytes: Some(150),
+            total_data_tokens: Some(4350),
+            floor_applied_txs: Some(1),
+            ..daily_stats(86_400)
+        };
+        db.insert_daily_batcher_stats(std::slice::from_ref(&snapshot))
+            .await?;
+        assert_eq!(db.get_daily_stats(0, 86_400).await?, vec![snapshot]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fee_breakdown_aggregated_per_batcher() -> Result<()> {
+        let db = database_with_batches(&[
+            tracked_batch(
+                "0xblob",
+                r#"{"gas_used": 21000, "blob_gas_used": 131072, "fees": {"base_fee_wei_burned": 210000, "priority_fee_wei_paid": 21000, "blob_fee_wei_burned": 131072, "max_fee_per_blob_gas": 3}}"#,
+            ),
+            tracked_batch(
+                "0xcalldata",
+                r#"{"gas_used": 79000, "blob_gas_used": 0, "fees": {"base_fee_wei_burned": 790000, "priority_fee_wei_paid": 479000, "blob_fee_wei_burned": 0, "max_fee_per_blob_gas": null}}"#,
+            ),
+            tracked_batch("0xold", r#"{"gas_used": 21000, "blob_gas_used": 0}"#),
+        ])
+        .await?;
+        let fee_breakdown = db.get_all_fee_breakdown(1500000000, 1700000000).await?;
+        assert_eq!(
+            fee_breakdown,
+            vec![BatcherFeeBreakdown {
+                batcher_address: BATCHER_ADDRESS.to_string(),
+                batch_count: 2,
+                total_base_fee_wei_burned: 1000000,
+                total_priority_fee_wei_paid: 500000,
+                total_blob_fee_wei_burned: 131072,
+                priority_fee_pct: 500000.0 / 1631072.0 * 100.0,
+                avg_priority_fee_per_gas: 5,
+                blob_fee_bid_ratio: Some(3.0),
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reverted_batches_tracked_as_wasted_spend() -> Result<()> {
+        let analysis_result = r#"{"gas_used": 21000, "gas_price": 10, "blob_gas_used": 131072, "blob_data_wei_spent": 131072, "eip_7623_calldata_wei_spent": 1000000}"#;
+        let db = database_with_batches(&[
+            tracked_batch("0xsuccess", analysis_result),
+            TrackedBatch {
+                status: Some(0),
+                ..tracked_batch("0xreverted", analysis_result)
+            },
+            TrackedBatch {
+                status: None,
+                ..tracked_batch("0xold", analysis_result)
+            },
+        ])
+        .await?;
+
+        let eth_saved = db
+            .get_all_eth_saved_data(1500000000, 1700000000, None, None)
+            .await?;
+        assert_eq!(eth_saved[0].total_eth_saved_wei, 2 * (1000000 - 131072));
+        assert_eq!(
+            db.get_all_daily_transactions(1500000000, 1700000000)
+                .await?[0]
+                .tx_count,
+            3
+        );
+
+        let wasted_spend = db.get_all_wasted_spend(1500000000, 1700000000).await?;
+        assert_eq!(
+            wasted_spend,
+            vec![BatcherWastedSpend {
+                batcher_address: BATCHER_ADDRESS.to_string(),
+                reverted_tx_count: 1,
+                total_wei_burned: 210000 + 131072,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_regret_of_the_posting_mode() -> Result<()> {
+        let db = database_with_batches(&[
+            tracked_batch(
+                "0xrightblob",
+                r#"{"blob_gas_used": 131072, "blob_data_wei_spent": 131072, "eip_7623_calldata_wei_spent": 1000000}"#,
+            ),
+            TrackedBatch {
+                timestamp: 1600000001,
+                ..tracked_batch(
+                    "0xwrongblob",
+                    r#"{"blob_gas_used": 131072, "blob_data_wei_spent": 1310720, "eip_7623_calldata_wei_spent": 10000}"#,
+                )
+            },
+            TrackedBatch {
+                timestamp: 1600000002,
+                ..tracked_batch(
+                    "0xwrongcalldata",
+                    r#"{"blob_gas_used": 0, "blob_data_wei_spent": 131072, "eip_7623_calldata_wei_spent": 200000}"#,
+                )
+            },
+            tracked_batch(
+                "0xprecancun",
+                r#"{"blob_gas_used": 0, "blob_data_wei_spent": null, "eip_7623_calldata_wei_spent": 200000}"#,
+            ),
+        ])
+        .await?;
+
+        let regret = db.get_all_regret(1500000000, 1700000000).await?;
+        assert_eq!(
+            regret,
+            vec![BatcherRegret {
+                batcher_address: BATCHER_ADDRESS.to_string(),
+                batch_count: 3,
+                wrong_mode_txs: 2,
+                total_regret_wei: (1310720 - 10000) + (200000 - 131072),
+                net_regret_wei: (131072 - 1000000) + (1310720 - 10000) + (200000 - 131072),
+            }]
+        );
+
+        let wrong_mode_batches = db.get_wrong_mode_batches(1500000000, 1700000000).await?;
+        assert_eq!(
+            wrong_mode_batches
+                .iter()
+                .map(|batch| (batch.tx_hash.as_str(), batch.chosen_mode, batch.regret_wei))
+                .collect::<Vec<_>>(),
+            vec![
+                ("0xwrongblob", PostingMode::Blob, 1310720 - 10000),
+                ("0xwrongcalldata", PostingMode::Calldata, 200000 - 131072),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hourly_break_even_fees() -> Result<()> {
+        let wrong_blob_analysis = r#"{"timestamp": 1600005700, "blob_gas_used": 131072, "blob_gas_price": 100, "break_even_blob_base_fee": 77}"#;
+        let db = database_with_batches(&[
+            TrackedBatch {
+                timestamp: 1600002010,
+                ..tracked_batch(
+                    "0xrightblob",
+                    r#"{"timestamp": 1600002000, "blob_gas_used": 131072, "blob_gas_price": 10, "break_even_blob_base_fee": 77}"#,
+                )
+            },
+            TrackedBatch {
+                timestamp: 1600002130,
+                ..tracked_batch(
+                    "0xwrongcalldata",
+                    r#"{"timestamp": 1600002120, "blob_gas_used": 0, "blob_gas_price": 30, "break_even_blob_base_fee": 77}"#,
+                )
+            },
+            TrackedBatch {
+                timestamp: 1600005710,
+                ..tracked_batch("0xwrongblob", wrong_blob_analysis)
+            },
+            TrackedBatch {
+                timestamp: 1600005710,
+                status: Some(0),
+                ..tracked_batch("0xreverted", wrong_blob_analysis)
+            },
+            TrackedBatch {
+                timestamp: 1600005710,
+                ..tracked_batch(
+                    "0xold",
+                    r#"{"timestamp": 1600005700, "blob_gas_used": 131072, "blob_gas_price": 100}"#,
+                )
+            },
+        ])
+        .await?;
+        // two samples in the first hour, one in the second, and the block of a batch sampled
+        // again
+        for (block_number, timestamp, blob_base_fee) in [
+            (100, 1600002000, 10),
+            (110, 1600002120, 30),
+            (400, 1600005700, 100),
+            (100, 1600002000, 10),
+        ] {
+            db.save_break_even_fee(&BreakEvenFee {
+                block_number,
+                timestamp,
+                base_fee_per_gas: 2,
+                blob_base_fee,
+                break_even_blob_base_fee: 77,
+            })
+            .await?;
+        }
+
+        let points = db
+            .get_hourly_break_even_fees(1600000000, 1600010000)
+            .await?;
+        assert_eq!(
+            points
+                .iter()
+                .map(|point| (
+                    point.timestamp,
+                    point.samples,
+                    point.blob_base_fee,
+                    point.break_even_blob_base_fee,
+                    point.cheaper_mode,
+                    point
+                        .wrong_side_batches
+                        .iter()
+                        .map(|batch| batch.tx_hash.as_str())
+                        .collect::<Vec<_>>()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    1600002000,
+                    2,
+                    20,
+                    77,
+                    PostingMode::Blob,
+                    vec!["0xwrongcalldata"]
+                ),
+                (
+                    1600005600,
+                    1,
+                    100,
+                    77,
+                    PostingMode::Calldata,
+                    vec!["0xwrongblob"]
+                ),
+            ]
+        );
+
+        // the range applies to the block timestamps
+        let points = db
+            .get_hourly_break_even_fees(1600005000, 1600010000)
+            .await?;
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].wrong_side_batches.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_timing_efficiency_aggregated_per_batcher() -> Result<()> {
+        let late_analysis = r#"{"timing": {"actual_wei": 3000, "cheapest_wei": 2000, "timing_premium_wei": 1000, "complete": true}}"#;
+        let db = database_with_batches(&[
+            tracked_batch(
+                "0xoptimal",
+                r#"{"timing": {"actual_wei": 1000, "cheapest_wei": 1000, "timing_premium_wei": 0, "complete": true}}"#,
+            ),
+            tracked_batch("0xlate", late_analysis),
+            TrackedBatch {
+                status: Some(0),
+                ..tracked_batch("0xreverted", late_analysis)
+            },
+            tracked_batch("0xuntimed", r#"{"timing": null}"#),
+            // analyzed before the end of its window
+            tracked_batch(
+                "0xincomplete",
+                r#"{"timing": {"actual_wei": 3000, "cheapest_wei": 2000, "timing_premium_wei": 1000, "complete": false}}"#,
+            ),
+        ])
+        .await?;
+
+        let timing = db.get_all_timing_efficiency(1500000000, 1700000000).await?;
+        assert_eq!(
+            timing,
+            vec![BatcherTimingEfficiency {
+                batcher_address: BATCHER_ADDRESS.to_string(),
+                timed_txs: 2,
+                optimal_timing_txs: 1,
+                total_actual_wei: 4000,
+                total_cheapest_wei: 3000,
+                total_timing_premium_wei: 1000,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_compression_aggregated_per_algorithm_and_level() -> Result<()> {
+        let db = database_with_batches(&[
+            tracked_batch(
+                "0x1",
+                r#"{"compression": {"payload_size": 1000, "results": [
+                    {"algorithm": "zstd", "level": 19, "compressed_size": 400, "eip_7623_calldata_wei_spent": 100, "blob_data_wei_spent": 10},
+                    {"algorithm": "zlib", "level": 9, "compressed_size": 500, "eip_7623_calldata_wei_spent": 120, "blob_data_wei_spent": 10}
+                ]}}"#,
+            ),
+            tracked_batch(
+                "0x2",
+                r#"{"compression": {"payload_size": 1000, "results": [
+                    {"algorithm": "zstd", "level": 19, "compressed_size": 100, "eip_7623_calldata_wei_spent": 30, "blob_data_wei_spent": 10}
+                ]}}"#,
+            ),
+            tracked_batch("0x3", r#"{"compression": null}"#),
+        ])
+        .await?;
+
+        let compression = db.get_all_compression(1500000000, 1700000000, None).await?;
+        assert_eq!(
+            compression,
+            vec![
+                BatcherCompression {
+                    batcher_address: BATCHER_ADDRESS.to_string(),
+                    algorithm: CompressionAlgorithm::Zlib,
+                    level: 9,
+                    batch_count: 1,
+                    total_payload_size: 1000,
+                    total_compressed_size: 500,
+                    compression_ratio: 2.0,
+                    total_eip_7623_calldata_wei_spent: 120,
+                    total_blob_data_wei_spent: 10,
+                    total_eip_7623_calldata_fiat_spent: None,
+                    total_blob_data_fiat_spent: None,
+                },
+                BatcherCompression {
+                    batcher_address: BATCHER_ADDRESS.to_string(),
+                    algorithm: CompressionAlgorithm::Zstd,
+                    level: 19,
+                    batch_count: 2,
+                    total_payload_size: 2000,
+                    total_compressed_size: 500,
+                    compression_ratio: 4.0,
+                    total_eip_7623_calldata_wei_spent: 130,
+                    total_blob_data_wei_spent: 20,
+                    total_eip_7623_calldata_fiat_spent: None,
+                    total_blob_data_fiat_spent: None,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_alt_da_cost_aggregated_per_layer() -> Result<()> {
+        let analysis_result = r#"{"blob_data_wei_spent": 100, "eip_7623_calldata_wei_spent": 900, "alt_da": {"payload_size": 1000, "costs": [
+            {"layer": "celestia", "unit": "mb", "price_wei": 20000, "wei_spent": 20},
+            {"layer": "eigenda", "unit": "blob", "price_wei": 50, "wei_spent": 50}
+        ]}}"#;
+        let db = database_with_batches(&[
+            tracked_batch("0x1", analysis_result),
+            // before the first rate of eigenda
+            tracked_batch(
+                "0x2",
+                r#"{"blob_data_wei_spent": 300, "eip_7623_calldata_wei_spent": 2700, "alt_da": {"payload_size": 3000, "costs": [
+                    {"layer": "celestia", "unit": "mb", "price_wei": 20000, "wei_spent": 60}
+                ]}}"#,
+            ),
+            tracked_batch(
+                "0x3",
+                r#"{"blob_data_wei_spent": 100, "eip_7623_calldata_wei_spent": 900, "alt_da": null}"#,
+            ),
+            TrackedBatch {
+                status: Some(0),
+                ..tracked_batch("0x4", analysis_result)
+            },
+        ])
+        .await?;
+
+        let alt_da = db.get_all_alt_da_cost(1500000000, 1700000000).await?;
+        assert_eq!(
+            alt_da,
+            vec![
+                BatcherAltDaCost {
+                    batcher_address: BATCHER_ADDRESS.to_string(),
+                    layer: "celestia".to_string(),
+                    batch_count: 2,
+                    total_payload_size: 4000,
+                    total_alt_da_wei_spent: 80,
+                    total_blob_data_wei_spent: 400,
+                    total_eip_7623_calldata_wei_spent: 3600,
+                },
+                BatcherAltDaCost {
+                    batcher_address: BATCHER_ADDRESS.to_string(),
+                    layer: "eigenda".to_string(),
+                    batch_count: 1,
+                    total_payload_size: 1000,
+                    total_alt_da_wei_spent: 50,
+                    total_blob_data_wei_spent: 100,
+                    total_eip_7623_calldata_wei_spent: 900,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_blob_sharing_of_tracked_blob_batches() -> Result<()> {
+        // two half-full blobs in the same block
+        let half_full_blob_analysis = r#"{"block_number": 100, "blob_gas_used": 131072, "blob_gas_price": 10, "blob_utilization": {"used_bytes": 63488}}"#;
+        let db = database_with_batches(&[
+            tracked_batch("0x1", half_full_blob_analysis),
+            TrackedBatch {
+                batcher_address: OTHER_BATCHER_ADDRESS.to_string(),
+                ..tracked_batch("0x2", half_full_blob_analysis)
+            },
+            tracked_batch(
+                "0x3",
+                r#"{"block_number": 100, "blob_gas_used": 0, "blob_gas_price": 10, "blob_utilization": null}"#,
+            ),
+            tracked_batch(
+                "0x4",
+                r#"{"blob_gas_used": 131072, "blob_gas_price": 10, "blob_utilization": {"used_bytes": 63488}}"#,
+            ),
+            TrackedBatch {
+                status: Some(0),
+                ..tracked_batch("0x5", half_full_blob_analysis)
+            },
+        ])
+        .await?;
+
+        let simulation = db
+            .get_blob_sharing_simulation(1500000000, 1700000000, 1)
+            .await?;
+        assert_eq!(simulation.window_count, 1);
+        assert_eq!(simulation.shared_window_count, 1);
+        assert_eq!(simulation.blob_count, 2);
+        assert_eq!(simulation.shared_blob_count, 1);
+        assert_eq!(simulation.total_savings_wei, 131072 * 10);
+        assert_eq!(simulation.batchers.len(), 2);
+        assert_eq!(simulation.batchers[0].batcher_address, BATCHER_ADDRESS);
+        assert_eq!(simulation.batchers[0].savings_wei, 131072 * 10 / 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fiat_priced_at_each_batch_timestamp() -> Result<()> {
+        // 1 ETH saved at 2000 USD and 1 ETH saved at 3000 USD, and a batch older than the first
+        // price
+        let db = database_with_batches(&[
+            tracked_batch(
+                "0x1",
+                r#"{"timestamp": 1600000000, "blob_data_wei_spent": 0, "eip_7623_calldata_wei_spent": 1000000000000000000}"#,
+            ),
+            tracked_batch(
+                "0x2",
+                r#"{"timestamp": 1600086400, "blob_data_wei_spent": 0, "eip_7623_calldata_wei_spent": 1000000000000000000}"#,
+            ),
+            TrackedBatch {
+                batcher_address: OTHER_BATCHER_ADDRESS.to_string(),
+                ..tracked_batch(
+                    "0x3",
+                    r#"{"timestamp": 1500000000, "blob_data_wei_spent": 0, "eip_7623_calldata_wei_spent": 1000000000000000000}"#,
+                )
+            },
+        ])
+        .await?;
+
+        let price_source =
+            crate::price::csv::CsvPriceSource::parse("1599955200,2000\n1600041600,3000\n")?;
+        let fiat = FiatConversion {
+            price_source: &price_source,
+            currency: crate::price::Currency::Usd,
+        };
+        let mut eth_saved = db
+            .get_all_eth_saved_data(1500000000, 1700000000, None, Some(fiat))
+            .await?;
+        eth_saved.sort_by(|a, b| a.batcher_address.cmp(&b.batcher_address));
+        assert_eq!(
+            eth_saved,
+            vec![
+                BatcherEthSaved {
+                    batcher_address: BATCHER_ADDRESS.to_string(),
+                    total_eth_saved_wei: 2000000000000000000,
+                    hypothetical_eth_saved_wei: None,
+                    total_eth_saved_fiat: Some(5000.0),
+                },
+                BatcherEthSaved {
+                    batcher_address: OTHER_BATCHER_ADDRESS.to_string(),
+                    total_eth_saved_wei: 1000000000000000000,
+                    hypothetical_eth_saved_wei: None,
+                    total_eth_saved_fiat: None,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fiat_priced_once_per_hour() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        /// A price source failing before 1600000000, counting the prices read.
+        struct CountingPriceSource {
+            reads: AtomicUsize,
+        }
+
+        #[async_trait]
+        impl crate::price::PriceSource for CountingPriceSource {
+            async fn eth_price(
+                &self,
+                _currency: crate::price::Currency,
+                at: PricePoint,
+            ) -> Result<Option<f64>> {
+                self.reads.fetch_add(1, Ordering::Relaxed);
+                if at.timestamp < 1600000000 {
+                    eyre::bail!("no archive state at block {:?}", at.block_number);
+                }
+                Ok(Some(2000.0))
+            }
+        }
+
+        // two batches in the same hour, 1 ETH saved each, and a batch whose price can't be read
+        let db = database_with_batches(&[
+            tracked_batch(
+                "0x1",
+                r#"{"block_number": 100, "timestamp": 1600000000, "blob_data_wei_spent": 0, "eip_7623_calldata_wei_spent": 1000000000000000000}"#,
+            ),
+            tracked_batch(
+                "0x2",
+                r#"{"block_number": 105, "timestamp": 1600000060, "blob_data_wei_spent": 0, "eip_7623_calldata_wei_spent": 1000000000000000000}"#,
+            ),
+            TrackedBatch {
+                batcher_address: OTHER_BATCHER_ADDRESS.to_string(),
+                ..tracked_batch(
+                    "0x3",
+                    r#"{"block_number": 1, "timestamp": 1500000000, "blob_data_wei_spent": 0, "eip_7623_calldata_wei_spent": 1000000000000000000}"#,
+                )
+            },
+        ])
+        .await?;
+
+        let price_source = CountingPriceSource {
+            reads: AtomicUsize::new(0),
+        };
+        let fiat = FiatConversion {
+            price_source: &price_source,
+            currency: crate::price::Currency::Usd,
+        };
+        let mut eth_saved = db
+            .get_all_eth_saved_data(1500000000, 1700000000, None, Some(fiat))
+            .await?;
+        eth_saved.sort_by(|a, b| a.batcher_address.cmp(&b.batcher_address));
+        // the failed price leaves the fiat total of its batcher None
+        assert_eq!(eth_saved[0].total_eth_saved_fiat, Some(4000.0));
+        assert_eq!(eth_saved[1].total_eth_saved_wei, 1000000000000000000);
+        assert_eq!(eth_saved[1].total_eth_saved_fiat, None);
+        // one read per hour
+        assert_eq!(price_source.reads.load(Ordering::Relaxed), 2);
+
+        Ok(())
+    }
 }
@@ -1,20 +1,24 @@
+use crate::batch::arbitrum::ARBITRUM_CHAINS;
+use crate::blob::fee::{blob_base_fee, break_even_blob_base_fee};
 use crate::provider::ProviderState;
-use crate::tracker::database::{Database, TrackedBatch};
+use crate::server::types::BreakEvenFee;
+use crate::tracker::database::Database;
 use crate::tracker::retry_handler::RetryHandler;
+use alloy_eips::BlockNumberOrTag;
 use alloy_primitives::{Address, FixedBytes, hex::FromHex};
 use alloy_provider::Provider;
 use eyre::Result;
-use serde_json;
 use std::sync::{Arc, LazyLock};
-use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{error, info};
 
 // Placeholder for the L2 batcher addresses
 static L2_BATCHERS_ADDRESSES: LazyLock<Vec<Address>> = LazyLock::new(|| {
-    let addresses = vec![
+    let mut addresses = vec![
         Address::from_hex("0x5050F69a9786F081509234F1a7F4684b5E5b76C9").unwrap(), // Base
         Address::from_hex("0x6887246668a3b87F54DeB3b94Ba47a6f63F32985").unwrap(), // Optimism
     ];
+    // Arbitrum batches are sent to the sequencer inbox contract, so we track the inbox itself
+    addresses.extend(ARBITRUM_CHAINS.iter().map(|chain| chain.sequencer_inbox));
     addresses
 });
 
@@ -35,10 +39,20 @@ pub async fn start_monitoring(db: Arc<dyn Database>, provider_state: ProviderSta
 
         let start_block = db.get_last_analyzed_block().await? + 1;
         let current_block = provider_state.ethereum_provider.get_block_number().await?;
+        // the batches are analyzed once the blocks of their timing window are produced
+        let end_block = current_block.saturating_sub(provider_state.timing_window_blocks);
+        if end_block < start_block {
+            info!(
+                "No block past the timing window since block {}",
+                start_block
+            );
+            tokio::time::sleep(tokio::time::Duration::from_secs(120)).await;
+            continue;
+        }
 
         info!(
             "Checking transactions from block {} to {}",
-            start_block, current_block
+            start_block, end_block
         );
 
         // for each monitored address, get its transactions
@@ -51,7 +65,7 @@ pub async fn start_monitoring(db: Arc<dyn Database>, provider_state: ProviderSta
             // get (up to 10) normal transactions from Etherscan
             match provider_state
                 .etherscan_provider
-                .get_normal_txs(batcher_address, start_block, current_block, 10)
+                .get_normal_txs(batcher_address, start_block, end_block, 10)
                 .await
             {
                 Ok(response) => {
@@ -81,15 +95,14 @@ pub async fn start_monitoring(db: Arc<dyn Database>, provider_state: ProviderSta
                         let tx_hash_bytes = FixedBytes::from_hex(&tx_hash)
                             .map_err(|e| eyre::eyre!("Failed to parse transaction hash: {}", e))?;
 
-                        let analysis_result = match crate::server::handlers::analyze_transaction(
+                        let analysis = match crate::server::handlers::analyze_transaction(
                             &provider_state,
                             tx_hash_bytes,
+                            crate::tracker::tracked_analysis_options(&provider_state),
                         )
                         .await
                         {
-                            Ok(analysis) => serde_json::to_string(&analysis).map_err(|e| {
-                                eyre::eyre!("Failed to serialize analysis result: {}", e)
-                            })?,
+                            Ok(analysis) => analysis,
                             Err(e) => {
                                 error!(
                                     "Failed to analyze transaction {}: {}. Adding to retry queue...",
@@ -114,16 +127,14 @@ pub async fn start_monitoring(db: Arc<dyn Database>, provider_state: ProviderSta
                             }
                         };
 
-                        let tracked_batch = TrackedBatch {
-                            id: None,
-                            tx_hash,
-                            batcher_address: format!("{:#x}", batcher_address),
-                            analysis_result,
-                            timestamp: SystemTime::now()
-                                .duration_since(UNIX_EPOCH)
-                                .unwrap()
-                                .as_secs() as i64,
-                            last_analyzed_block: None,
+                        let Some(tracked_batch) =
+                            crate::tracker::tracked_batch(&tx_hash, batcher_address, &analysis)?
+                        else {
+                            info!(
+                                "Skipping non-batch transaction to sequencer inbox: {}",
+                                tx_hash
+                            );
+                            continue;
                         };
 
                         // save to database
@@ -135,6 +146,12 @@ pub async fn start_monitoring(db: Arc<dyn Database>, provider_state: ProviderSta
                         } else {
                             info!("Successfully saved transaction: {}", tracked_batch.tx_hash);
                         }
+
+                        if let Some(fee) = crate::tracker::batch_break_even_fee(&analysis)
+                            && let Err(e) = db.save_break_even_fee(&fee).await
+                        {
+                            error!("Failed to save break-even fee: {}", e);
+                        }
                     }
                 }
                 Err(e) => {
@@ -147,11 +164,55 @@ pub async fn start_monitoring(db: Arc<dyn Database>, provider_state: ProviderSta
         }
 
         // update the last analyzed block
-        if let Err(e) = db.update_last_analyzed_block(current_block).await {
+        if let Err(e) = db.update_last_analyzed_block(end_block).await {
             error!("Failed to update last analyzed block: {}", e);
         }
 
+        // sample the break-even blob base fee at the latest block
+        match latest_break_even_fee(&provider_state, current_block).await {
+            Ok(Some(fee)) => {
+                if let Err(e) = db.save_break_even_fee(&fee).await {
+                    error!("Failed to save break-even fee: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => error!("Failed to sample break-even fee: {}", e),
+        }
+
         info!("L2 Batches Monitoring Service: Completed check. Sleeping for 2 minutes...");
         tokio::time::sleep(tokio::time::Duration::from_secs(120)).await;
     }
 }
+
+/// The break-even blob base fee at the given block.
+///
+/// None if the block is not found or happened before Cancun hard fork.
+async fn latest_break_even_fee(
+    provider_state: &ProviderState,
+    block_number: u64,
+) -> Result<Option<BreakEvenFee>> {
+    let Some(block) = provider_state
+        .ethereum_provider
+        .get_block_by_number(BlockNumberOrTag::Number(block_number))
+        .await?
+    else {
+        return Ok(None);
+    };
+    let fork_params = provider_state
+        .fork_schedule
+        .params_at(block.header.number, block.header.timestamp);
+    let (Some(base_fee_per_gas), Some(excess_blob_gas), Some(blob_params)) = (
+        block.header.base_fee_per_gas,
+        block.header.excess_blob_gas,
+        fork_params.blob,
+    ) else {
+        return Ok(None);
+    };
+    Ok(Some(BreakEvenFee {
+        block_number: block.header.number,
+        timestamp: block.header.timestamp as i64,
+        base_fee_per_gas,
+        blob_base_fee: blob_base_fee(excess_blob_gas, &blob_params),
+        break_even_blob_base_fee: break_even_blob_base_fee(base_fee_per_gas, &fork_params),
+    }))
+}
@@ -1,8 +1,8 @@
 use crate::provider::ProviderState;
-use crate::tracker::database::{Database, FailedTransaction, TrackedBatch};
-use alloy_primitives::{FixedBytes, hex::FromHex};
+use crate::server::types::TxAnalysisResponse;
+use crate::tracker::database::{Database, FailedTransaction};
+use alloy_primitives::{Address, FixedBytes, hex::FromHex};
 use eyre::Result;
-use serde_json;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{error, info, warn};
@@ -113,18 +113,25 @@ impl RetryHandler {
             );
 
             match self.retry_transaction_analysis(&failed_tx).await {
-                Ok(analysis_result) => {
+                Ok(analysis) => {
+                    let batcher_address = Address::from_hex(&failed_tx.batcher_address)
+                        .map_err(|e| eyre::eyre!("Failed to parse batcher address: {}", e))?;
                     // Success! Save to main database and remove from retry queue
-                    let tracked_batch = TrackedBatch {
-                        id: None,
-                        tx_hash: failed_tx.tx_hash.clone(),
-                        batcher_address: failed_tx.batcher_address.clone(),
-                        analysis_result,
-                        timestamp: SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs() as i64,
-                        last_analyzed_block: None,
+                    let Some(tracked_batch) = crate::tracker::tracked_batch(
+                        &failed_tx.tx_hash,
+                        batcher_address,
+                        &analysis,
+                    )?
+                    else {
+                        info!(
+                            "Skipping non-batch transaction to sequencer inbox: {}",
+                            failed_tx.tx_hash
+                        );
+                        if let Err(e) = self.db.remove_failed_transaction(&failed_tx.tx_hash).await
+                        {
+                            error!("Failed to remove transaction from retry queue: {}", e);
+                        }
+                        continue;
                     };
 
                     if let Err(e) = self.db.save_tracked_batch(&tracked_batch).await {
@@ -158,6 +165,12 @@ impl RetryHandler {
                                 failed_tx.tx_hash
                             );
                         }
+
+                        if let Some(fee) = crate::tracker::batch_break_even_fee(&analysis)
+                            && let Err(e) = self.db.save_break_even_fee(&fee).await
+                        {
+                            error!("Failed to save break-even fee: {}", e);
+                        }
                     }
                 }
                 Err(e) => {
@@ -201,16 +214,21 @@ impl RetryHandler {
     }
 
     /// Retry analyzing a specific transaction
-    async fn retry_transaction_analysis(&self, failed_tx: &FailedTransaction) -> Result<String> {
+    async fn retry_transaction_analysis(
+        &self,
+        failed_tx: &FailedTransaction,
+    ) -> Result<TxAnalysisResponse> {
         let tx_hash_bytes = FixedBytes::from_hex(&failed_tx.tx_hash)
             .map_err(|e| eyre::eyre!("Failed to parse transaction hash: {}", e))?;
 
-        let analysis_result =
-            crate::server::handlers::analyze_transaction(&self.provider_state, tx_hash_bytes)
-                .await?;
+        let analysis_result = crate::server::handlers::analyze_transaction(
+            &self.provider_state,
+            tx_hash_bytes,
+            crate::tracker::tracked_analysis_options(&self.provider_state),
+        )
+        .await?;
 
-        serde_json::to_string(&analysis_result)
-            .map_err(|e| eyre::eyre!("Failed to serialize analysis result: {}", e))
+        Ok(analysis_result)
     }
 
     /// Start the retry processing loop
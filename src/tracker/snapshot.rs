@@ -1,6 +1,7 @@
 use crate::{
     server::types::{
-        BatcherBlobDataGas, BatcherDailyTxs, BatcherEthSaved, BatcherPectraDataGas,
+        BatcherBlobDataGas, BatcherByteProfile, BatcherDailyTxs, BatcherEip7918ExtraCost,
+        BatcherEthSaved, BatcherPectraDataGas, BatcherRegret, BatcherTimingEfficiency,
         DailyBatcherStats,
     },
     tracker::database::Database,
@@ -40,11 +41,20 @@ async fn create_and_save_snapshot(db: Arc<dyn Database>) -> Result<()> {
 
     // aggregate metrics for all batchers
     let daily_txs: Vec<BatcherDailyTxs> = db.get_all_daily_transactions(start_ts, end_ts).await?;
-    let eth_saved: Vec<BatcherEthSaved> = db.get_all_eth_saved_data(start_ts, end_ts).await?;
+    let eth_saved: Vec<BatcherEthSaved> = db
+        .get_all_eth_saved_data(start_ts, end_ts, None, None)
+        .await?;
     let blob_gas: Vec<BatcherBlobDataGas> =
         db.get_all_total_blob_data_gas(start_ts, end_ts).await?;
-    let pectra_gas: Vec<BatcherPectraDataGas> =
-        db.get_all_total_pectra_data_gas(start_ts, end_ts).await?;
+    let pectra_gas: Vec<BatcherPectraDataGas> = db
+        .get_all_total_pectra_data_gas(start_ts, end_ts, None)
+        .await?;
+    let eip_7918_extra_cost: Vec<BatcherEip7918ExtraCost> =
+        db.get_all_eip_7918_extra_cost(start_ts, end_ts).await?;
+    let byte_profile: Vec<BatcherByteProfile> = db.get_all_byte_profile(start_ts, end_ts).await?;
+    let regret: Vec<BatcherRegret> = db.get_all_regret(start_ts, end_ts).await?;
+    let timing: Vec<BatcherTimingEfficiency> =
+        db.get_all_timing_efficiency(start_ts, end_ts).await?;
 
     #[derive(Default)]
     struct TmpStats {
@@ -52,6 +62,17 @@ async fn create_and_save_snapshot(db: Arc<dyn Database>) -> Result<()> {
         total_eth_saved_wei: u128,
         total_blob_data_gas: u64,
         total_pectra_data_gas: u64,
+        total_eip_7918_extra_wei: u128,
+        total_data_bytes: u64,
+        total_zero_bytes: u64,
+        total_data_tokens: u64,
+        floor_applied_txs: u64,
+        wrong_mode_txs: u64,
+        total_regret_wei: u128,
+        net_regret_wei: i128,
+        timed_txs: u64,
+        optimal_timing_txs: u64,
+        total_timing_premium_wei: u128,
     }
 
     let mut map: HashMap<String, TmpStats> = HashMap::new();
@@ -74,6 +95,30 @@ async fn create_and_save_snapshot(db: Arc<dyn Database>) -> Result<()> {
             .or_default()
             .total_pectra_data_gas = item.total_pectra_data_gas;
     }
+    for item in eip_7918_extra_cost {
+        map.entry(item.batcher_address)
+            .or_default()
+            .total_eip_7918_extra_wei = item.total_eip_7918_extra_wei;
+    }
+    for item in byte_profile {
+        let entry = map.entry(item.batcher_address).or_default();
+        entry.total_data_bytes = item.total_data_bytes;
+        entry.total_zero_bytes = item.total_zero_bytes;
+        entry.total_data_tokens = item.total_data_tokens;
+        entry.floor_applied_txs = item.floor_applied_txs;
+    }
+    for item in regret {
+        let entry = map.entry(item.batcher_address).or_default();
+        entry.wrong_mode_txs = item.wrong_mode_txs;
+        entry.total_regret_wei = item.total_regret_wei;
+        entry.net_regret_wei = item.net_regret_wei;
+    }
+    for item in timing {
+        let entry = map.entry(item.batcher_address).or_default();
+        entry.timed_txs = item.timed_txs;
+        entry.optimal_timing_txs = item.optimal_timing_txs;
+        entry.total_timing_premium_wei = item.total_timing_premium_wei;
+    }
 
     let snapshot_ts = start_ts;
 
@@ -86,6 +131,17 @@ async fn create_and_save_snapshot(db: Arc<dyn Database>) -> Result<()> {
             total_daily_txs: s.total_daily_txs,
             total_blob_data_gas: s.total_blob_data_gas,
             total_pectra_data_gas: s.total_pectra_data_gas,
+            total_eip_7918_extra_wei: Some(s.total_eip_7918_extra_wei),
+            total_data_bytes: Some(s.total_data_bytes),
+            total_zero_bytes: Some(s.total_zero_bytes),
+            total_data_tokens: Some(s.total_data_tokens),
+            floor_applied_txs: Some(s.floor_applied_txs),
+            wrong_mode_txs: Some(s.wrong_mode_txs),
+            total_regret_wei: Some(s.total_regret_wei),
+            net_regret_wei: Some(s.net_regret_wei),
+            timed_txs: Some(s.timed_txs),
+            optimal_timing_txs: Some(s.optimal_timing_txs),
+            total_timing_premium_wei: Some(s.total_timing_premium_wei),
         });
     }
 
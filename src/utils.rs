@@ -1,36 +1,267 @@
+use crate::fork::{Fork, ForkParams};
+use alloy_consensus::Transaction;
+use alloy_eips::eip7702::Authorization;
+use alloy_primitives::{Address, Bytes, U256};
 use revm::interpreter::gas::get_tokens_in_calldata;
+use std::collections::HashMap;
 
-/// The cost of the calldata floor per token.
-///
-/// This is taken from the EIP-7623 spec.
-const TOTAL_COST_FLOOR_PER_TOKEN: u64 = 10;
-/// The block number of the istanbul hard fork on Ethereum mainnet.
-pub const ISTANBUL_BLOCK_NUMBER: u64 = 9_069_000;
 /// The base stipend for the calldata.
 pub const BASE_STIPEND: u64 = 21000;
-/// The size of a blob in bytes.
-pub const BLOB_SIZE: u64 = 128_000;
-/// The standard cost of calldata token.
-pub const STANDARD_TOKEN_COST: u64 = 4;
 /// The number of bytes in a blob.
 pub const BYTES_PER_BLOB: u64 = 131_072;
-/// Is istabul hard fork enabled?
-const fn is_istanbul_enabled(block_number: u64) -> bool {
-    block_number >= ISTANBUL_BLOCK_NUMBER
-}
+/// The gas charged before execution for a contract creation, on top of the base stipend.
+pub const CREATE_GAS: u64 = 32_000;
+/// The gas charged per address in the access list.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-2930
+pub const ACCESS_LIST_ADDRESS_GAS: u64 = 2_400;
+/// The gas charged per storage key in the access list.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-2930
+pub const ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1_900;
+/// The gas charged per 32-byte word of initcode.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-3860
+pub const INITCODE_WORD_GAS: u64 = 2;
+/// The gas charged per authorization, assuming the authority account is empty.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-7702
+pub const PER_EMPTY_ACCOUNT_GAS: u64 = 25_000;
+/// The gas charged per authorization when the authority account already exists.
+///
+/// The difference with [`PER_EMPTY_ACCOUNT_GAS`] is refunded.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-7702
+pub const PER_AUTH_BASE_GAS: u64 = 12_500;
+/// The prefix of the code of an account delegated with EIP-7702.
+pub const DELEGATION_DESIGNATOR_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
 /// It returns the gas cost of the calldata following the new EIP-7623 rules.
 ///
 /// Link: https://eips.ethereum.org/EIPS/eip-7623
-pub fn compute_calldata_gas(calldata: &[u8], block_number: u64) -> u64 {
-    let is_istanbul = is_istanbul_enabled(block_number);
-    let tokens_in_calldata = get_tokens_in_calldata(calldata, is_istanbul);
-    TOTAL_COST_FLOOR_PER_TOKEN * tokens_in_calldata
+pub fn compute_calldata_gas(calldata: &[u8], fork_params: &ForkParams) -> u64 {
+    let tokens_in_calldata = get_tokens_in_calldata(calldata, fork_params.is_istanbul());
+    fork_params.total_cost_floor_per_token * tokens_in_calldata
 }
 /// It returns the gas cost of the calldata following legacy rules.
 ///
 /// Link: https://eips.ethereum.org/EIPS/eip-7623
-pub fn compute_legacy_calldata_gas(calldata: &[u8], block_number: u64) -> u64 {
-    let is_istanbul = is_istanbul_enabled(block_number);
-    let tokens_in_calldata = get_tokens_in_calldata(calldata, is_istanbul);
-    STANDARD_TOKEN_COST * tokens_in_calldata
+pub fn compute_legacy_calldata_gas(calldata: &[u8], fork_params: &ForkParams) -> u64 {
+    let tokens_in_calldata = get_tokens_in_calldata(calldata, fork_params.is_istanbul());
+    fork_params.standard_token_cost * tokens_in_calldata
+}
+
+/// It returns the number of tokens of the calldata: a zero byte is one token, a non-zero byte is 4
+/// tokens (17 before istanbul).
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-7623
+pub fn count_calldata_tokens(calldata: &[u8], fork_params: &ForkParams) -> u64 {
+    get_tokens_in_calldata(calldata, fork_params.is_istanbul())
+}
+
+/// The zero and non-zero bytes of some calldata.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CalldataBytes {
+    /// The number of zero bytes.
+    pub zero_bytes: u64,
+    /// The number of non-zero bytes.
+    pub non_zero_bytes: u64,
+}
+
+/// It counts the zero and non-zero bytes of the calldata.
+pub fn count_calldata_bytes(calldata: &[u8]) -> CalldataBytes {
+    let zero_bytes = calldata.iter().filter(|byte| **byte == 0).count() as u64;
+    CalldataBytes {
+        zero_bytes,
+        non_zero_bytes: calldata.len() as u64 - zero_bytes,
+    }
+}
+
+/// The intrinsic gas of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntrinsicGas {
+    /// The standard intrinsic gas, charged before execution.
+    pub standard: u64,
+    /// The EIP-7623 floor, the minimum gas used by the transaction.
+    pub floor: u64,
+}
+
+/// It returns the standard intrinsic gas and the EIP-7623 floor of any transaction type.
+///
+/// The floor is computed even before prague, so that the EIP-7623 counterfactual can be
+/// evaluated.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-7623
+pub fn compute_intrinsic_gas<T: Transaction>(tx: &T, fork_params: &ForkParams) -> IntrinsicGas {
+    let input = tx.input();
+    let tokens_in_calldata = get_tokens_in_calldata(input, fork_params.is_istanbul());
+    let mut standard = BASE_STIPEND + fork_params.standard_token_cost * tokens_in_calldata;
+    if tx.is_create() {
+        standard += CREATE_GAS;
+        if fork_params.fork >= Fork::Shanghai {
+            standard += INITCODE_WORD_GAS * (input.len() as u64).div_ceil(32);
+        }
+    }
+    if let Some(access_list) = tx.access_list() {
+        let storage_keys: usize = access_list.iter().map(|item| item.storage_keys.len()).sum();
+        standard += ACCESS_LIST_ADDRESS_GAS * access_list.len() as u64
+            + ACCESS_LIST_STORAGE_KEY_GAS * storage_keys as u64;
+    }
+    if let Some(authorization_list) = tx.authorization_list() {
+        standard += PER_EMPTY_ACCOUNT_GAS * authorization_list.len() as u64;
+    }
+    let floor = BASE_STIPEND + fork_params.total_cost_floor_per_token * tokens_in_calldata;
+    IntrinsicGas { standard, floor }
+}
+
+/// An authority account before the transaction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthorityAccount {
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The code of the account.
+    pub code: Bytes,
+}
+
+impl AuthorityAccount {
+    /// Whether the account is empty as defined by EIP-161.
+    pub fn is_empty(&self) -> bool {
+        self.nonce == 0 && self.balance.is_zero() && self.code.is_empty()
+    }
+}
+
+/// The gas of the authorization list of a transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AuthorizationGas {
+    /// The gas charged in the intrinsic gas.
+    pub charged: u64,
+    /// The gas refunded for the authorities that already exist.
+    pub refunded: u64,
+    /// The number of authorizations that are applied.
+    pub valid_count: u64,
+}
+
+/// It returns the gas charged and refunded for the authorization list of a transaction.
+///
+/// The authorizations are processed in order, each one paired with the recovered authority,
+/// and the accounts are the authorities before the transaction. Invalid authorizations are
+/// charged but skipped, so they are not refunded.
+///
+/// Link: https://eips.ethereum.org/EIPS/eip-7702
+pub fn compute_authorization_gas(
+    chain_id: u64,
+    sender: Address,
+    authorizations: &[(Option<Address>, Authorization)],
+    accounts: &HashMap<Address, AuthorityAccount>,
+) -> AuthorizationGas {
+    let mut accounts = accounts.clone();
+    // the nonce of the sender is increased before processing the authorizations
+    if let Some(sender_account) = accounts.get_mut(&sender) {
+        sender_account.nonce += 1;
+    }
+    let mut authorization_gas = AuthorizationGas::default();
+    for (authority, authorization) in authorizations {
+        authorization_gas.charged += PER_EMPTY_ACCOUNT_GAS;
+        let Some(authority) = authority else {
+            continue;
+        };
+        if !authorization.chain_id.is_zero() && authorization.chain_id != U256::from(chain_id) {
+            continue;
+        }
+        let account = accounts.entry(*authority).or_default();
+        let is_delegated_or_eoa =
+            account.code.is_empty() || account.code.starts_with(&DELEGATION_DESIGNATOR_PREFIX);
+        if !is_delegated_or_eoa || account.nonce != authorization.nonce {
+            continue;
+        }
+        if !account.is_empty() {
+            authorization_gas.refunded += PER_EMPTY_ACCOUNT_GAS - PER_AUTH_BASE_GAS;
+        }
+        account.nonce += 1;
+        account.code = [
+            DELEGATION_DESIGNATOR_PREFIX.as_slice(),
+            authorization.address.as_slice(),
+        ]
+        .concat()
+        .into();
+        authorization_gas.valid_count += 1;
+    }
+    authorization_gas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::TxEip2930;
+    use alloy_eips::eip2930::{AccessList, AccessListItem};
+    use alloy_primitives::{B256, TxKind};
+
+    #[test]
+    fn test_intrinsic_gas_with_access_list_and_create() {
+        let fork_params = Fork::Prague.default_params();
+        let tx = TxEip2930 {
+            to: TxKind::Create,
+            // 2 zero bytes and 2 non-zero bytes, 10 tokens, 1 word
+            input: Bytes::from_static(&[0, 0, 1, 1]),
+            access_list: AccessList(vec![AccessListItem {
+                address: Address::ZERO,
+                storage_keys: vec![B256::ZERO, B256::ZERO],
+            }]),
+            ..Default::default()
+        };
+        let intrinsic_gas = compute_intrinsic_gas(&tx, &fork_params);
+        assert_eq!(
+            intrinsic_gas.standard,
+            BASE_STIPEND + 40 + CREATE_GAS + INITCODE_WORD_GAS + 2_400 + 2 * 1_900
+        );
+        assert_eq!(intrinsic_gas.floor, BASE_STIPEND + 100);
+    }
+
+    #[test]
+    fn test_authorization_gas_refunds_existing_authorities() {
+        let sender = Address::repeat_byte(1);
+        let existing = Address::repeat_byte(2);
+        let empty = Address::repeat_byte(3);
+        let authorization = |chain_id: u64, nonce: u64| Authorization {
+            chain_id: U256::from(chain_id),
+            address: Address::repeat_byte(0xde),
+            nonce,
+        };
+        let accounts = HashMap::from([
+            (
+                sender,
+                AuthorityAccount {
+                    nonce: 7,
+                    ..Default::default()
+                },
+            ),
+            (
+                existing,
+                AuthorityAccount {
+                    balance: U256::from(1),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let authorizations = [
+            // the sender signs for the nonce after the transaction one
+            (Some(sender), authorization(1, 8)),
+            (Some(existing), authorization(0, 0)),
+            // the empty account exists after its first authorization
+            (Some(empty), authorization(1, 0)),
+            (Some(empty), authorization(1, 1)),
+            // invalid chain id, nonce and signature are charged but not refunded
+            (Some(existing), authorization(10, 1)),
+            (Some(existing), authorization(1, 0)),
+            (None, authorization(1, 0)),
+        ];
+        let authorization_gas = compute_authorization_gas(1, sender, &authorizations, &accounts);
+        assert_eq!(authorization_gas.charged, 7 * PER_EMPTY_ACCOUNT_GAS);
+        assert_eq!(
+            authorization_gas.refunded,
+            3 * (PER_EMPTY_ACCOUNT_GAS - PER_AUTH_BASE_GAS)
+        );
+        assert_eq!(authorization_gas.valid_count, 4);
+    }
 }